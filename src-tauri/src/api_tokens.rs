@@ -0,0 +1,242 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use crate::config::ConfigProvider;
+use crate::secrets::{SecretKey, SecretNamespace, SecretStore};
+
+const TOKEN_PREFIX: &str = "mcpb_";
+
+// Metadata for an inbound API token; the token itself lives in the secret store.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct ApiTokenInfo {
+    pub id: String,
+    pub label: String,
+    pub created_at: String,
+}
+
+// Returned once on creation; the token cannot be read back afterwards.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct CreatedApiToken {
+    pub info: ApiTokenInfo,
+    pub token: String,
+}
+
+// Label of the token a request authenticated with, attached to the HTTP
+// request so the session and its events can record who made them.
+#[derive(Debug, Clone)]
+pub struct TokenIdentity(pub String);
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct TokensFile(BTreeMap<String, ApiTokenInfo>);
+
+fn tokens_path(cp: &dyn ConfigProvider) -> PathBuf {
+    cp.base_dir().join("api_tokens.json")
+}
+
+fn token_secret_key(id: &str) -> SecretKey<'_> {
+    SecretKey {
+        namespace: SecretNamespace::ApiToken,
+        identifier: id,
+    }
+}
+
+// Token values by id, read from the keyring once so requests do not hit it.
+static CACHE: Mutex<Option<HashMap<String, String>>> = Mutex::new(None);
+
+fn invalidate_cache() {
+    *CACHE.lock().unwrap() = None;
+}
+
+fn load_file(cp: &dyn ConfigProvider) -> TokensFile {
+    fs::read_to_string(tokens_path(cp))
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+fn save_file(cp: &dyn ConfigProvider, file: &TokensFile) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(file).map_err(|e| format!("to json: {e}"))?;
+    fs::create_dir_all(cp.base_dir()).map_err(|e| format!("create dir: {e}"))?;
+    fs::write(tokens_path(cp), content).map_err(|e| format!("write api tokens: {e}"))
+}
+
+pub fn list_tokens(cp: &dyn ConfigProvider) -> Vec<ApiTokenInfo> {
+    load_file(cp).0.into_values().collect()
+}
+
+pub fn create_token(cp: &dyn ConfigProvider, label: &str) -> Result<CreatedApiToken, String> {
+    create_token_with_store(cp, cp.secret_store(), label)
+}
+
+pub fn create_token_with_store(
+    cp: &dyn ConfigProvider,
+    secret_store: &dyn SecretStore,
+    label: &str,
+) -> Result<CreatedApiToken, String> {
+    let label = label.trim();
+    if label.is_empty() {
+        return Err("token label must not be empty".into());
+    }
+    let mut file = load_file(cp);
+    if file.0.values().any(|t| t.label == label) {
+        return Err(format!("a token labelled '{label}' already exists"));
+    }
+    let id = uuid::Uuid::new_v4().simple().to_string();
+    let token = format!(
+        "{TOKEN_PREFIX}{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    );
+    secret_store
+        .set(&token_secret_key(&id), &token)
+        .map_err(|e| format!("store api token in keyring: {e}"))?;
+    let info = ApiTokenInfo {
+        id: id.clone(),
+        label: label.to_string(),
+        created_at: chrono::Utc::now().to_rfc3339(),
+    };
+    file.0.insert(id.clone(), info.clone());
+    if let Err(e) = save_file(cp, &file) {
+        let _ = secret_store.delete(&token_secret_key(&id));
+        return Err(e);
+    }
+    invalidate_cache();
+    Ok(CreatedApiToken { info, token })
+}
+
+pub fn revoke_token(cp: &dyn ConfigProvider, id: &str) -> Result<(), String> {
+    revoke_token_with_store(cp, cp.secret_store(), id)
+}
+
+pub fn revoke_token_with_store(
+    cp: &dyn ConfigProvider,
+    secret_store: &dyn SecretStore,
+    id: &str,
+) -> Result<(), String> {
+    let mut file = load_file(cp);
+    if file.0.remove(id).is_none() {
+        return Err("token not found".into());
+    }
+    // Drop the metadata first: a token without metadata no longer verifies
+    // even if the keyring delete fails.
+    save_file(cp, &file)?;
+    invalidate_cache();
+    secret_store
+        .delete(&token_secret_key(id))
+        .map_err(|e| format!("delete api token from keyring: {e}"))
+}
+
+// Token matching `presented`, if any.
+pub fn verify(cp: &dyn ConfigProvider, presented: &str) -> Option<ApiTokenInfo> {
+    let file = load_file(cp);
+    let mut cache = CACHE.lock().unwrap();
+    let values = cache.get_or_insert_with(|| read_values(&file, cp.secret_store()));
+    find_match(&file, values, presented)
+}
+
+pub fn verify_with_store(
+    cp: &dyn ConfigProvider,
+    secret_store: &dyn SecretStore,
+    presented: &str,
+) -> Option<ApiTokenInfo> {
+    let file = load_file(cp);
+    find_match(&file, &read_values(&file, secret_store), presented)
+}
+
+fn read_values(file: &TokensFile, secret_store: &dyn SecretStore) -> HashMap<String, String> {
+    file.0
+        .keys()
+        .filter_map(|id| match secret_store.get(&token_secret_key(id)) {
+            Ok(Some(value)) => Some((id.clone(), value)),
+            Ok(None) => None,
+            Err(err) => {
+                tracing::warn!(target = "auth", token = %id, "failed to load api token from keyring: {err}");
+                None
+            }
+        })
+        .collect()
+}
+
+fn find_match(
+    file: &TokensFile,
+    values: &HashMap<String, String>,
+    presented: &str,
+) -> Option<ApiTokenInfo> {
+    file.0
+        .values()
+        .find(|info| {
+            values
+                .get(&info.id)
+                .is_some_and(|v| constant_time_eq(v.as_bytes(), presented.as_bytes()))
+        })
+        .cloned()
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::secrets::MemorySecretStore;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    struct TempProvider(PathBuf);
+
+    impl TempProvider {
+        fn new() -> Self {
+            let dir = std::env::temp_dir().join(format!(
+                "mcp-bouncer-api-tokens-{}-{}",
+                std::process::id(),
+                SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap()
+                    .as_nanos()
+            ));
+            fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+    }
+
+    impl ConfigProvider for TempProvider {
+        fn base_dir(&self) -> PathBuf {
+            self.0.clone()
+        }
+    }
+
+    #[test]
+    fn tokens_verify_until_revoked() {
+        let cp = TempProvider::new();
+        let store = MemorySecretStore::new();
+        let created = create_token_with_store(&cp, &store, "cursor").unwrap();
+        assert!(created.token.starts_with(TOKEN_PREFIX));
+        assert!(create_token_with_store(&cp, &store, "cursor").is_err());
+
+        let raw = fs::read_to_string(tokens_path(&cp)).unwrap();
+        assert!(
+            !raw.contains(&created.token),
+            "token must not be written to disk"
+        );
+
+        let found = verify_with_store(&cp, &store, &created.token).unwrap();
+        assert_eq!(found.label, "cursor");
+        assert!(verify_with_store(&cp, &store, "mcpb_wrong").is_none());
+
+        revoke_token_with_store(&cp, &store, &created.info.id).unwrap();
+        assert!(verify_with_store(&cp, &store, &created.token).is_none());
+        assert!(list_tokens(&cp).is_empty());
+    }
+
+    #[test]
+    fn constant_time_eq_matches_only_equal_input() {
+        assert!(constant_time_eq(b"abc", b"abc"));
+        assert!(!constant_time_eq(b"abc", b"abd"));
+        assert!(!constant_time_eq(b"abc", b"ab"));
+    }
+}
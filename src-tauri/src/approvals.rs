@@ -0,0 +1,202 @@
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use specta::Type;
+use tokio::sync::{Mutex, oneshot};
+
+use crate::events::{EventEmitter, approval_requested, approval_resolved};
+
+pub const DEFAULT_TIMEOUT_SECS: u64 = 120;
+
+// Answer to a pending tool call, sent from the UI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalDecision {
+    Allow,
+    // Allow this call and later calls of the same tool in the same session.
+    AllowForSession,
+    Deny,
+}
+
+// How a call that needed approval ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Allowed,
+    // Allowed earlier with "always allow for this session"; nobody was asked.
+    PreApproved,
+    Denied,
+    TimedOut,
+}
+
+impl Outcome {
+    pub fn allowed(self) -> bool {
+        matches!(self, Outcome::Allowed | Outcome::PreApproved)
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Outcome::Allowed => "allowed",
+            Outcome::PreApproved => "allowed_for_session",
+            Outcome::Denied => "denied",
+            Outcome::TimedOut => "timed_out",
+        }
+    }
+}
+
+// What the UI shows for a pending call. `session` is the downstream service
+// instance the call came through, which "allow for session" is scoped to.
+pub struct ApprovalRequest<'a> {
+    pub session: u64,
+    pub session_id: &'a str,
+    pub client_name: Option<&'a str>,
+    pub server_name: &'a str,
+    pub tool_name: &'a str,
+    pub arguments: Option<&'a serde_json::Map<String, serde_json::Value>>,
+}
+
+#[derive(Default)]
+struct State {
+    pending: HashMap<String, oneshot::Sender<ApprovalDecision>>,
+    // (session, server, tool) triples allowed for the rest of the session.
+    session_allowed: HashSet<(u64, String, String)>,
+}
+
+static STATE: std::sync::OnceLock<Mutex<State>> = std::sync::OnceLock::new();
+
+fn state() -> &'static Mutex<State> {
+    STATE.get_or_init(|| Mutex::new(State::default()))
+}
+
+// Emits the pending call and waits for `resolve` or the timeout.
+pub async fn request<E: EventEmitter>(
+    emitter: &E,
+    req: ApprovalRequest<'_>,
+    timeout: Duration,
+) -> Outcome {
+    let key = (
+        req.session,
+        req.server_name.to_string(),
+        req.tool_name.to_string(),
+    );
+    let id = uuid::Uuid::new_v4().to_string();
+    let (tx, rx) = oneshot::channel();
+    {
+        let mut guard = state().lock().await;
+        if guard.session_allowed.contains(&key) {
+            return Outcome::PreApproved;
+        }
+        guard.pending.insert(id.clone(), tx);
+    }
+    approval_requested(
+        emitter,
+        &json!({
+            "id": id,
+            "session_id": req.session_id,
+            "client_name": req.client_name,
+            "server_name": req.server_name,
+            "tool_name": req.tool_name,
+            "arguments": req.arguments,
+            "timeout_ms": timeout.as_millis() as u64,
+        }),
+    );
+    let outcome = match tokio::time::timeout(timeout, rx).await {
+        Ok(Ok(ApprovalDecision::Allow)) => Outcome::Allowed,
+        Ok(Ok(ApprovalDecision::AllowForSession)) => {
+            state().lock().await.session_allowed.insert(key);
+            Outcome::Allowed
+        }
+        Ok(Ok(ApprovalDecision::Deny)) | Ok(Err(_)) => Outcome::Denied,
+        Err(_) => {
+            state().lock().await.pending.remove(&id);
+            Outcome::TimedOut
+        }
+    };
+    approval_resolved(emitter, &id, outcome.as_str());
+    outcome
+}
+
+// Called once a session's transport has closed so its grants do not pile up.
+pub async fn forget_sessions(sessions: &[u64]) {
+    if sessions.is_empty() {
+        return;
+    }
+    state()
+        .lock()
+        .await
+        .session_allowed
+        .retain(|(session, _, _)| !sessions.contains(session));
+}
+
+pub async fn resolve(id: &str, decision: ApprovalDecision) -> Result<(), String> {
+    let sender = state().lock().await.pending.remove(id);
+    match sender {
+        Some(tx) => tx
+            .send(decision)
+            .map_err(|_| "approval request is no longer waiting".to_string()),
+        None => Err("approval request not found or already answered".into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::{BufferingEventEmitter, EVENT_APPROVAL_REQUESTED};
+
+    fn req(session: u64) -> ApprovalRequest<'static> {
+        ApprovalRequest {
+            session,
+            session_id: "s",
+            client_name: Some("cli"),
+            server_name: "fs",
+            tool_name: "delete",
+            arguments: None,
+        }
+    }
+
+    async fn answer(emitter: &BufferingEventEmitter, decision: ApprovalDecision) {
+        loop {
+            let id = emitter
+                .0
+                .lock()
+                .unwrap()
+                .iter()
+                .rev()
+                .find(|(name, _)| name == EVENT_APPROVAL_REQUESTED)
+                .and_then(|(_, payload)| payload["id"].as_str().map(str::to_string));
+            if let Some(id) = id
+                && resolve(&id, decision).await.is_ok()
+            {
+                return;
+            }
+            tokio::task::yield_now().await;
+        }
+    }
+
+    #[tokio::test]
+    async fn allow_for_session_skips_later_prompts() {
+        let emitter = BufferingEventEmitter::default();
+        let waiting = request(&emitter, req(1), Duration::from_secs(5));
+        let (outcome, ()) =
+            tokio::join!(waiting, answer(&emitter, ApprovalDecision::AllowForSession));
+        assert_eq!(outcome, Outcome::Allowed);
+        let again = request(&emitter, req(1), Duration::from_secs(5)).await;
+        assert_eq!(again, Outcome::PreApproved);
+        let other = request(&emitter, req(2), Duration::from_millis(10)).await;
+        assert_eq!(other, Outcome::TimedOut);
+
+        forget_sessions(&[1]).await;
+        let forgotten = request(&emitter, req(1), Duration::from_millis(10)).await;
+        assert_eq!(forgotten, Outcome::TimedOut);
+    }
+
+    #[tokio::test]
+    async fn deny_and_unknown_ids() {
+        let emitter = BufferingEventEmitter::default();
+        let waiting = request(&emitter, req(3), Duration::from_secs(5));
+        let (outcome, ()) = tokio::join!(waiting, answer(&emitter, ApprovalDecision::Deny));
+        assert_eq!(outcome, Outcome::Denied);
+        assert!(resolve("missing", ApprovalDecision::Allow).await.is_err());
+    }
+}
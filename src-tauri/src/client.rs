@@ -4,8 +4,12 @@ use std::{
     time::{Duration, SystemTime},
 };
 
-use rmcp::ServiceExt;
-use rmcp::service::RoleClient;
+use rmcp::model::{
+    ClientCapabilities, ClientInfo, ClientResult, CreateElicitationRequestParam,
+    CreateElicitationResult, CreateMessageRequestParam, CreateMessageResult, ErrorData,
+    ListRootsResult, ServerRequest, SetLevelRequestParam,
+};
+use rmcp::service::{NotificationContext, RequestContext, RoleClient};
 use rmcp::transport::{
     SseClientTransport, StreamableHttpClientTransport, TokioChildProcess,
     auth::{AuthClient, AuthError, OAuthClientConfig, OAuthState},
@@ -14,8 +18,10 @@ use rmcp::transport::{
         StreamableHttpClient, StreamableHttpClientTransportConfig, StreamableHttpError,
     },
 };
+use rmcp::{ClientHandler, Peer, RoleServer, ServiceError, ServiceExt};
 
-use crate::config::{MCPServerConfig, TransportType};
+use crate::config::{ClientConnectionState, MCPServerConfig, TransportType};
+use crate::downstream::{self, ListChanged};
 use crate::events::EventEmitter;
 use crate::logging::RpcEventPublisher;
 use crate::oauth::{
@@ -27,7 +33,7 @@ use anyhow::{Context, Result, anyhow};
 use tokio::sync::Mutex as AsyncMutex;
 use tracing::warn;
 
-pub type ClientService = rmcp::service::RunningService<RoleClient, ()>;
+pub type ClientService = rmcp::service::RunningService<RoleClient, UpstreamHandler>;
 pub type ClientRegistry = tokio::sync::Mutex<HashMap<String, Arc<ClientService>>>;
 
 // Global client registry used by Tauri commands
@@ -37,6 +43,159 @@ pub fn client_registry() -> &'static ClientRegistry {
     CLIENT_REGISTRY_INST.get_or_init(|| tokio::sync::Mutex::new(HashMap::new()))
 }
 
+// Handles server-initiated traffic from one upstream. Sampling, elicitation and
+// roots requests plus progress go back to the downstream call that triggered
+// them; log messages and list changes are relayed to every downstream session,
+// and a tool list change also drops the cached tools.
+#[derive(Clone, Debug)]
+pub struct UpstreamHandler {
+    server_name: String,
+}
+
+impl UpstreamHandler {
+    pub fn new(server_name: &str) -> Self {
+        Self {
+            server_name: server_name.to_string(),
+        }
+    }
+
+    // Server->client requests are answered by the downstream session whose call is
+    // in flight on this upstream, provided that session supports the feature.
+    fn downstream_caller(
+        &self,
+        method: &str,
+        meta: &rmcp::model::Meta,
+        supports: impl Fn(&ClientCapabilities) -> bool,
+    ) -> Result<Peer<RoleServer>, ErrorData> {
+        let token = meta.get_progress_token();
+        let peer =
+            downstream::active_caller(&self.server_name, token.as_ref()).map_err(|reason| {
+                ErrorData::invalid_request(
+                    format!("{method} from '{}': {reason}", self.server_name),
+                    None,
+                )
+            })?;
+        let supported = peer
+            .peer_info()
+            .map(|info| supports(&info.capabilities))
+            .unwrap_or(false);
+        if !supported {
+            return Err(ErrorData::invalid_request(
+                format!(
+                    "{method} from '{}': downstream client does not support it",
+                    self.server_name
+                ),
+                None,
+            ));
+        }
+        Ok(peer)
+    }
+}
+
+fn downstream_error(method: &str, e: ServiceError) -> ErrorData {
+    match e {
+        ServiceError::McpError(err) => err,
+        other => ErrorData::internal_error(format!("downstream {method} failed: {other}"), None),
+    }
+}
+
+impl ClientHandler for UpstreamHandler {
+    async fn create_message(
+        &self,
+        params: CreateMessageRequestParam,
+        context: RequestContext<RoleClient>,
+    ) -> Result<CreateMessageResult, ErrorData> {
+        let method = "sampling/createMessage";
+        let peer = self.downstream_caller(method, &context.meta, |c| c.sampling.is_some())?;
+        peer.create_message(params)
+            .await
+            .map_err(|e| downstream_error(method, e))
+    }
+
+    async fn list_roots(
+        &self,
+        context: RequestContext<RoleClient>,
+    ) -> Result<ListRootsResult, ErrorData> {
+        let method = "roots/list";
+        let peer = self.downstream_caller(method, &context.meta, |c| c.roots.is_some())?;
+        peer.list_roots()
+            .await
+            .map_err(|e| downstream_error(method, e))
+    }
+
+    async fn create_elicitation(
+        &self,
+        request: CreateElicitationRequestParam,
+        context: RequestContext<RoleClient>,
+    ) -> Result<CreateElicitationResult, ErrorData> {
+        let method = "elicitation/create";
+        let peer = self.downstream_caller(method, &context.meta, |c| c.elicitation.is_some())?;
+        let version = crate::protocol::for_session(&peer);
+        if !crate::protocol::supports_elicitation(&version) {
+            return Err(ErrorData::invalid_request(
+                format!(
+                    "{method} from '{}': downstream session negotiated protocol {version}",
+                    self.server_name
+                ),
+                None,
+            ));
+        }
+        // Peer::create_elicitation sits behind rmcp's `elicitation` feature; send it directly.
+        let request = ServerRequest::CreateElicitationRequest(rmcp::model::Request::new(request));
+        match peer.send_request(request).await {
+            Ok(ClientResult::CreateElicitationResult(result)) => Ok(result),
+            Ok(_) => Err(downstream_error(method, ServiceError::UnexpectedResponse)),
+            Err(e) => Err(downstream_error(method, e)),
+        }
+    }
+
+    async fn on_progress(
+        &self,
+        params: rmcp::model::ProgressNotificationParam,
+        _context: NotificationContext<RoleClient>,
+    ) {
+        if !downstream::route_progress(&self.server_name, params) {
+            tracing::debug!(target = "client", server = %self.server_name, "upstream_progress_unrouted");
+        }
+    }
+
+    async fn on_logging_message(
+        &self,
+        params: rmcp::model::LoggingMessageNotificationParam,
+        _context: NotificationContext<RoleClient>,
+    ) {
+        downstream::notify_logging_message(&self.server_name, params).await;
+    }
+
+    async fn on_tool_list_changed(&self, _context: NotificationContext<RoleClient>) {
+        tracing::debug!(target = "client", server = %self.server_name, "upstream_tool_list_changed");
+        crate::tools_cache::clear(&self.server_name).await;
+        downstream::notify_list_changed(ListChanged::Tools).await;
+    }
+
+    async fn on_resource_list_changed(&self, _context: NotificationContext<RoleClient>) {
+        tracing::debug!(target = "client", server = %self.server_name, "upstream_resource_list_changed");
+        downstream::notify_list_changed(ListChanged::Resources).await;
+    }
+
+    async fn on_prompt_list_changed(&self, _context: NotificationContext<RoleClient>) {
+        tracing::debug!(target = "client", server = %self.server_name, "upstream_prompt_list_changed");
+        downstream::notify_list_changed(ListChanged::Prompts).await;
+    }
+
+    fn get_info(&self) -> ClientInfo {
+        ClientInfo {
+            protocol_version: crate::protocol::latest(),
+            capabilities: ClientCapabilities::builder()
+                .enable_roots()
+                .enable_sampling()
+                .enable_elicitation()
+                .build(),
+            ..ClientInfo::default()
+        }
+    }
+}
+
 pub async fn ensure_rmcp_client<E, L>(
     name: &str,
     cfg: &MCPServerConfig,
@@ -55,6 +214,18 @@ where
         return Ok(c.clone());
     }
     tracing::info!(target = "client", server=%name, transport=?cfg.transport, "starting");
+    // Placeholders are expanded here so failures surface on the server's
+    // status instead of reaching the upstream as literal `${...}` text.
+    let resolved = match crate::interpolate::resolve_config(cfg) {
+        Ok(resolved) => resolved,
+        Err(e) => {
+            crate::overlay::set_error(name, Some(e.clone())).await;
+            crate::overlay::set_state(name, ClientConnectionState::Errored).await;
+            crate::events::client_status_changed(&emitter, name, "error");
+            return Err(anyhow!(e));
+        }
+    };
+    let cfg = &resolved;
     let service = match cfg.transport {
         TransportType::StreamableHttp => {
             let endpoint = cfg.endpoint.clone();
@@ -130,7 +301,7 @@ where
                     emitter.clone(),
                     logger.clone(),
                 );
-                match ().serve(transport).await {
+                match UpstreamHandler::new(&cfg.name).serve(transport).await {
                     Ok(svc) => svc,
                     Err(e) => {
                         on_possible_unauthorized(&cfg.name, Some(&endpoint)).await;
@@ -162,7 +333,7 @@ where
                     emitter.clone(),
                     logger.clone(),
                 );
-                match ().serve(transport).await {
+                match UpstreamHandler::new(&cfg.name).serve(transport).await {
                     Ok(svc) => svc,
                     Err(e) => {
                         on_possible_unauthorized(&cfg.name, Some(&endpoint)).await;
@@ -204,7 +375,10 @@ where
                 emitter.clone(),
                 logger.clone(),
             );
-            ().serve(transport).await.context("rmcp serve")?
+            UpstreamHandler::new(&cfg.name)
+                .serve(transport)
+                .await
+                .context("rmcp serve")?
         }
         TransportType::Stdio => {
             let cmd = cfg.command.clone();
@@ -223,12 +397,24 @@ where
                 emitter.clone(),
                 logger.clone(),
             );
-            ().serve(transport).await.context("rmcp serve")?
+            UpstreamHandler::new(&cfg.name)
+                .serve(transport)
+                .await
+                .context("rmcp serve")?
         }
     };
     let arc = Arc::new(service);
     guard.insert(name.to_string(), arc.clone());
+    drop(guard);
     tracing::info!(target = "client", server=%name, "registered");
+    // Sent without the registry lock so a slow upstream never holds up
+    // connecting other servers.
+    if let Some(level) = downstream::upstream_log_level().await
+        && upstream_supports(&arc, |c| c.logging.is_some())
+        && let Err(e) = arc.set_level(SetLevelRequestParam { level }).await
+    {
+        tracing::debug!(target = "client", server=%name, error = %e, "set_level_failed");
+    }
     Ok(arc)
 }
 
@@ -422,6 +608,49 @@ pub async fn shutdown_all_clients() {
     }
 }
 
+// Applies a downstream logging/setLevel to one upstream. Servers that do not
+// advertise logging are skipped.
+pub async fn set_level_for_cfg<E, L>(
+    cfg: &MCPServerConfig,
+    level: rmcp::model::LoggingLevel,
+    emitter: &E,
+    logger: &L,
+) -> Result<()>
+where
+    E: EventEmitter + Clone + Send + Sync + 'static,
+    L: RpcEventPublisher,
+{
+    let client = ensure_rmcp_client(&cfg.name, cfg, emitter, logger).await?;
+    if !upstream_supports(&client, |c| c.logging.is_some()) {
+        return Ok(());
+    }
+    client
+        .set_level(SetLevelRequestParam { level })
+        .await
+        .map_err(|e| anyhow!("rmcp set level").context(e))
+}
+
+// Like rmcp's `list_all_tools`, but stops on an empty or repeated cursor so a
+// misbehaving upstream cannot keep the listing going forever.
+pub async fn list_all_upstream_tools(
+    client: &ClientService,
+) -> Result<Vec<rmcp::model::Tool>, ServiceError> {
+    let mut tools = Vec::new();
+    let mut seen = std::collections::HashSet::new();
+    let mut cursor = None;
+    loop {
+        let page = client
+            .list_tools(Some(rmcp::model::PaginatedRequestParam { cursor }))
+            .await?;
+        tools.extend(page.tools);
+        match page.next_cursor {
+            Some(next) if !next.is_empty() && seen.insert(next.clone()) => cursor = Some(next),
+            _ => break,
+        }
+    }
+    Ok(tools)
+}
+
 pub async fn fetch_tools_for_cfg<E, L>(
     cfg: &MCPServerConfig,
     emitter: &E,
@@ -432,11 +661,12 @@ where
     L: RpcEventPublisher,
 {
     let client = ensure_rmcp_client(&cfg.name, cfg, emitter, logger).await?;
-    let tools = match client.list_all_tools().await {
+    let tools = match list_all_upstream_tools(&client).await {
         Ok(t) => t,
         Err(e) => {
             if matches!(cfg.transport, TransportType::StreamableHttp) {
-                on_possible_unauthorized(&cfg.name, Some(&cfg.endpoint)).await;
+                let endpoint = crate::interpolate::resolve_endpoint(cfg).ok();
+                on_possible_unauthorized(&cfg.name, endpoint.as_deref()).await;
             }
             return Err(anyhow!("rmcp list tools").context(e));
         }
@@ -448,6 +678,92 @@ where
     Ok(vals)
 }
 
+fn upstream_supports(
+    client: &ClientService,
+    has: impl Fn(&rmcp::model::ServerCapabilities) -> bool,
+) -> bool {
+    client
+        .peer()
+        .peer_info()
+        .map(|info| has(&info.capabilities))
+        .unwrap_or(false)
+}
+
+pub async fn fetch_resources_for_cfg<E, L>(
+    cfg: &MCPServerConfig,
+    emitter: &E,
+    logger: &L,
+) -> Result<Vec<rmcp::model::Resource>>
+where
+    E: EventEmitter + Clone + Send + Sync + 'static,
+    L: RpcEventPublisher,
+{
+    let client = ensure_rmcp_client(&cfg.name, cfg, emitter, logger).await?;
+    if !upstream_supports(&client, |c| c.resources.is_some()) {
+        return Ok(Vec::new());
+    }
+    match client.list_all_resources().await {
+        Ok(resources) => Ok(resources),
+        Err(e) => {
+            if matches!(cfg.transport, TransportType::StreamableHttp) {
+                let endpoint = crate::interpolate::resolve_endpoint(cfg).ok();
+                on_possible_unauthorized(&cfg.name, endpoint.as_deref()).await;
+            }
+            Err(anyhow!("rmcp list resources").context(e))
+        }
+    }
+}
+
+pub async fn fetch_resource_templates_for_cfg<E, L>(
+    cfg: &MCPServerConfig,
+    emitter: &E,
+    logger: &L,
+) -> Result<Vec<rmcp::model::ResourceTemplate>>
+where
+    E: EventEmitter + Clone + Send + Sync + 'static,
+    L: RpcEventPublisher,
+{
+    let client = ensure_rmcp_client(&cfg.name, cfg, emitter, logger).await?;
+    if !upstream_supports(&client, |c| c.resources.is_some()) {
+        return Ok(Vec::new());
+    }
+    match client.list_all_resource_templates().await {
+        Ok(templates) => Ok(templates),
+        Err(e) => {
+            if matches!(cfg.transport, TransportType::StreamableHttp) {
+                let endpoint = crate::interpolate::resolve_endpoint(cfg).ok();
+                on_possible_unauthorized(&cfg.name, endpoint.as_deref()).await;
+            }
+            Err(anyhow!("rmcp list resource templates").context(e))
+        }
+    }
+}
+
+pub async fn fetch_prompts_for_cfg<E, L>(
+    cfg: &MCPServerConfig,
+    emitter: &E,
+    logger: &L,
+) -> Result<Vec<rmcp::model::Prompt>>
+where
+    E: EventEmitter + Clone + Send + Sync + 'static,
+    L: RpcEventPublisher,
+{
+    let client = ensure_rmcp_client(&cfg.name, cfg, emitter, logger).await?;
+    if !upstream_supports(&client, |c| c.prompts.is_some()) {
+        return Ok(Vec::new());
+    }
+    match client.list_all_prompts().await {
+        Ok(prompts) => Ok(prompts),
+        Err(e) => {
+            if matches!(cfg.transport, TransportType::StreamableHttp) {
+                let endpoint = crate::interpolate::resolve_endpoint(cfg).ok();
+                on_possible_unauthorized(&cfg.name, endpoint.as_deref()).await;
+            }
+            Err(anyhow!("rmcp list prompts").context(e))
+        }
+    }
+}
+
 // Helper: expose names present in registry (for status computation)
 pub async fn registry_names() -> Vec<String> {
     let reg = client_registry();
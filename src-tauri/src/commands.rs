@@ -1,18 +1,25 @@
 use std::collections::HashMap;
 
 use mcp_bouncer::BIN_NAME_SOCKET_BRIDGE;
-use mcp_bouncer::client::{ensure_rmcp_client, fetch_tools_for_cfg, remove_rmcp_client};
+use mcp_bouncer::api_tokens::{self, ApiTokenInfo, CreatedApiToken};
+use mcp_bouncer::approvals::{self, ApprovalDecision};
+use mcp_bouncer::client::{
+    ensure_rmcp_client, fetch_tools_for_cfg, list_all_upstream_tools, remove_rmcp_client,
+};
 use mcp_bouncer::config::{
     ClientConnectionState, ClientStatus, ConfigProvider, IncomingClient, MCPServerConfig,
-    ServerTransport, Settings, config_dir, default_settings, load_settings, load_settings_with,
-    save_settings, save_settings_with, settings_path,
+    ServerTransport, Settings, SettingsIssue, ToolOverride, check_settings_with, config_dir,
+    default_settings, load_settings, load_settings_with, save_settings, save_settings_with,
+    settings_path,
 };
+use mcp_bouncer::downstream::{ListChanged, notify_all_lists_changed, notify_list_changed};
 use mcp_bouncer::events::{
     EventEmitter, TauriEventEmitter, client_error, client_status_changed, servers_updated,
     settings_updated,
 };
 use mcp_bouncer::incoming::list_incoming;
 use mcp_bouncer::logging::{Event, RpcEventPublisher, SqlitePublisher, with_request_origin};
+use mcp_bouncer::named_secrets::{self, NamedSecretInfo};
 use mcp_bouncer::oauth::{self, start_oauth_for_server};
 use mcp_bouncer::server::get_runtime_listen_addr;
 use mcp_bouncer::types::ToolInfo;
@@ -156,6 +163,7 @@ pub async fn mcp_add_server(app: tauri::AppHandle, config: MCPServerConfig) -> R
     s.mcp_servers.push(config);
     save_settings(&s)?;
     notify_servers_changed(&TauriEventEmitter(app.clone()), "add");
+    notify_all_lists_changed().await;
     if should_connect && let Some(cfg) = get_server_by_name(&server_name) {
         connect_and_initialize(&TauriEventEmitter(app.clone()), &server_name, &cfg).await;
     }
@@ -176,6 +184,7 @@ pub async fn mcp_update_server(
         *item = config;
         save_settings(&s)?;
         notify_servers_changed(&TauriEventEmitter(app.clone()), "update");
+        notify_all_lists_changed().await;
         if enabling {
             if let Some(cfg) = get_server_by_name(&server_name) {
                 connect_and_initialize(&TauriEventEmitter(app.clone()), &server_name, &cfg).await;
@@ -206,6 +215,7 @@ pub async fn mcp_remove_server(app: tauri::AppHandle, name: String) -> Result<()
     let _ = remove_rmcp_client(&name).await;
     mcp_bouncer::overlay::remove(&name).await;
     notify_servers_changed(&TauriEventEmitter(app.clone()), "remove");
+    notify_all_lists_changed().await;
     Ok(())
 }
 
@@ -233,6 +243,7 @@ pub async fn mcp_toggle_server_enabled(
             client_status_changed(&TauriEventEmitter(app.clone()), &server_name, "disable");
         }
         notify_servers_changed(&TauriEventEmitter(app.clone()), "toggle");
+        notify_all_lists_changed().await;
         Ok(())
     } else {
         Err("server not found".into())
@@ -276,7 +287,7 @@ pub async fn mcp_restart_client(app: tauri::AppHandle, name: String) -> Result<(
 #[tauri::command]
 pub async fn mcp_start_oauth(app: tauri::AppHandle, name: String) -> Result<(), String> {
     let cfg = get_server_by_name(&name).ok_or_else(|| "server not found".to_string())?;
-    let endpoint = cfg.endpoint.clone();
+    let endpoint = mcp_bouncer::interpolate::resolve_endpoint(&cfg)?;
     if endpoint.is_empty() {
         return Err("missing endpoint".to_string());
     }
@@ -411,7 +422,103 @@ pub async fn mcp_toggle_tool(
         &client_name,
         &tool_name,
         enabled,
-    )
+    )?;
+    notify_list_changed(ListChanged::Tools).await;
+    Ok(())
+}
+
+#[specta::specta]
+#[tauri::command]
+pub async fn mcp_set_tool_override(
+    app: tauri::AppHandle,
+    client_name: String,
+    tool_name: String,
+    tool_override: Option<ToolOverride>,
+) -> Result<(), String> {
+    let cp = mcp_bouncer::config::OsConfigProvider;
+    let (generated, unlisted) =
+        mcp_bouncer::server::generated_tool_aliases(&TauriEventEmitter(app), &cp, &SqlitePublisher)
+            .await;
+    // A new name can only be checked against servers whose tools were listed.
+    if !unlisted.is_empty() && tool_override.as_ref().is_some_and(|o| o.name.is_some()) {
+        return Err(format!(
+            "could not check the name against the tools of {}: they could not be listed",
+            unlisted.join(", ")
+        ));
+    }
+    mcp_bouncer::config::save_tool_override_with(
+        &cp,
+        &client_name,
+        &tool_name,
+        tool_override,
+        &generated,
+    )?;
+    notify_list_changed(ListChanged::Tools).await;
+    Ok(())
+}
+
+#[specta::specta]
+#[tauri::command]
+pub async fn mcp_toggle_prompt(
+    client_name: String,
+    prompt_name: String,
+    enabled: bool,
+) -> Result<(), String> {
+    mcp_bouncer::config::save_prompts_toggle_with(
+        &mcp_bouncer::config::OsConfigProvider,
+        &client_name,
+        &prompt_name,
+        enabled,
+    )?;
+    notify_list_changed(ListChanged::Prompts).await;
+    Ok(())
+}
+
+#[specta::specta]
+#[tauri::command]
+pub async fn mcp_list_api_tokens() -> Result<Vec<ApiTokenInfo>, String> {
+    Ok(api_tokens::list_tokens(
+        &mcp_bouncer::config::OsConfigProvider,
+    ))
+}
+
+#[specta::specta]
+#[tauri::command]
+pub async fn mcp_create_api_token(label: String) -> Result<CreatedApiToken, String> {
+    api_tokens::create_token(&mcp_bouncer::config::OsConfigProvider, &label)
+}
+
+#[specta::specta]
+#[tauri::command]
+pub async fn mcp_revoke_api_token(id: String) -> Result<(), String> {
+    api_tokens::revoke_token(&mcp_bouncer::config::OsConfigProvider, &id)
+}
+
+#[specta::specta]
+#[tauri::command]
+pub async fn mcp_list_secrets() -> Result<Vec<NamedSecretInfo>, String> {
+    Ok(named_secrets::list_secrets(
+        &mcp_bouncer::config::OsConfigProvider,
+    ))
+}
+
+// Creates or replaces a named secret. Values are never returned to the UI.
+#[specta::specta]
+#[tauri::command]
+pub async fn mcp_set_secret(name: String, value: String) -> Result<NamedSecretInfo, String> {
+    named_secrets::set_secret(&mcp_bouncer::config::OsConfigProvider, &name, &value)
+}
+
+#[specta::specta]
+#[tauri::command]
+pub async fn mcp_delete_secret(name: String) -> Result<(), String> {
+    named_secrets::delete_secret(&mcp_bouncer::config::OsConfigProvider, &name)
+}
+
+#[specta::specta]
+#[tauri::command]
+pub async fn mcp_resolve_approval(id: String, decision: ApprovalDecision) -> Result<(), String> {
+    approvals::resolve(&id, decision).await
 }
 
 #[specta::specta]
@@ -445,6 +552,13 @@ pub async fn settings_update_settings(
     )
 }
 
+// Syntax error or semantic problems in settings.json; empty when it is valid.
+#[specta::specta]
+#[tauri::command]
+pub async fn settings_validate_settings() -> Result<Vec<SettingsIssue>, String> {
+    Ok(check_settings_with(&mcp_bouncer::config::OsConfigProvider))
+}
+
 async fn fetch_and_cache_tools(
     app: &tauri::AppHandle,
     client_name: &str,
@@ -458,23 +572,7 @@ async fn fetch_and_cache_tools(
     let raw = fetch_tools_for_cfg(&cfg, &emitter, &logger)
         .await
         .map_err(|e| e.to_string())?;
-    let mut out: Vec<ToolInfo> = Vec::new();
-    for v in raw.iter() {
-        let name = v.get("name").and_then(|n| n.as_str()).unwrap_or("");
-        let description = v
-            .get("description")
-            .and_then(|d| d.as_str())
-            .map(|s| s.to_string());
-        let input_schema = v
-            .get("input_schema")
-            .cloned()
-            .or_else(|| v.get("inputSchema").cloned());
-        out.push(ToolInfo {
-            name: name.to_string(),
-            description,
-            input_schema,
-        });
-    }
+    let out: Vec<ToolInfo> = raw.iter().map(ToolInfo::from_tool_json).collect();
     mcp_bouncer::tools_cache::set(client_name, out.clone()).await;
     mcp_bouncer::overlay::set_tools(client_name, out.len() as u32).await;
     let mut evt = Event::new("tools/list", format!("internal::{client_name}"));
@@ -510,16 +608,9 @@ where
     client_status_changed(emitter, name, "connecting");
     let logger = SqlitePublisher;
     match ensure_rmcp_client(name, cfg, emitter, &logger).await {
-        Ok(client) => match client.list_all_tools().await {
+        Ok(client) => match list_all_upstream_tools(&client).await {
             Ok(tools) => {
-                let mapped: Vec<ToolInfo> = tools
-                    .iter()
-                    .map(|t| ToolInfo {
-                        name: t.name.to_string(),
-                        description: t.description.clone().map(|s| s.to_string()),
-                        input_schema: None,
-                    })
-                    .collect();
+                let mapped: Vec<ToolInfo> = tools.iter().map(ToolInfo::from).collect();
                 mcp_bouncer::tools_cache::set(name, mapped.clone()).await;
                 ov::set_tools(name, mapped.len() as u32).await;
                 ov::set_state(name, ClientConnectionState::Connected).await;
@@ -543,7 +634,8 @@ where
                     cfg.transport,
                     mcp_bouncer::config::TransportType::StreamableHttp
                 ) {
-                    oauth::on_possible_unauthorized(name, Some(&cfg.endpoint)).await;
+                    let endpoint = mcp_bouncer::interpolate::resolve_endpoint(cfg).ok();
+                    oauth::on_possible_unauthorized(name, endpoint.as_deref()).await;
                 }
                 let snap = mcp_bouncer::overlay::snapshot().await;
                 if let Some(ent) = snap.get(name)
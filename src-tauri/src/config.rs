@@ -1,6 +1,11 @@
 use serde::{Deserialize, Serialize};
 use specta::Type;
-use std::{collections::HashMap, fs, path::PathBuf};
+use std::{
+    collections::HashMap,
+    fs,
+    path::PathBuf,
+    sync::{Mutex, OnceLock},
+};
 
 // Types shared with Tauri commands and service
 
@@ -31,6 +36,35 @@ pub struct MCPServerConfig {
     #[serde(default)]
     pub headers: HashMap<String, String>,
     pub enabled: bool,
+    // Prefix for exposed tool and prompt names: unset uses the server name,
+    // an empty string exposes upstream names unprefixed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[specta(optional)]
+    pub prefix: Option<String>,
+    // Rate and concurrency caps for tools/call across every tool of this server.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[specta(optional)]
+    pub limits: Option<CallLimits>,
+}
+
+// Caps on forwarded tools/call requests, set per server or per tool.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Type)]
+pub struct CallLimits {
+    // Token bucket refilled continuously at this many calls per minute.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[specta(optional)]
+    pub per_minute: Option<u32>,
+    // Bucket size, i.e. calls allowed back to back; defaults to `per_minute`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[specta(optional)]
+    pub burst: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[specta(optional)]
+    pub max_in_flight: Option<u32>,
+    // How long a call over the limit waits for capacity; unset fails at once.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[specta(optional)]
+    pub queue_timeout_ms: Option<u64>,
 }
 
 fn default_transport() -> TransportType {
@@ -49,6 +83,170 @@ pub struct Settings {
     pub mcp_servers: Vec<MCPServerConfig>,
     pub listen_addr: String,
     pub transport: ServerTransport,
+    // Tools per tools/list page; unset (or 0) returns every tool in one page.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[specta(optional)]
+    pub tools_page_size: Option<u32>,
+    // Expose only search_tools and call_tool instead of every upstream tool.
+    #[serde(default)]
+    pub tool_search: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub macros: Vec<MacroTool>,
+    // Extra endpoints at /mcp/<name>, each exposing a subset of servers and tools.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub profiles: Vec<Profile>,
+    // Reject TCP requests to /mcp without a valid `Authorization: Bearer` API token.
+    #[serde(default)]
+    pub require_api_token: bool,
+    // Host header values accepted by the TCP listener besides localhost forms.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_hosts: Vec<String>,
+    // Browser origins accepted besides http(s) pages served from localhost.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_origins: Vec<String>,
+    // Rules picked by the connecting client's clientInfo.name; first match wins.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub client_policies: Vec<ClientPolicy>,
+    // Ask before forwarding calls to tools whose upstream listing sets
+    // destructiveHint; per-tool `approval` overrides take precedence.
+    #[serde(default)]
+    pub approve_destructive_tools: bool,
+    // How long a call waits for an approval decision; unset means 120 seconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[specta(optional)]
+    pub approval_timeout_secs: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct Profile {
+    pub name: String,
+    // Servers reachable through this profile; empty allows every enabled server.
+    #[serde(default)]
+    pub servers: Vec<String>,
+    // Allowed tools as `server::tool` (or a macro name); empty allows every
+    // tool of the allowed servers.
+    #[serde(default)]
+    pub tools: Vec<String>,
+}
+
+impl Profile {
+    pub fn allows_server(&self, server_name: &str) -> bool {
+        self.servers.is_empty() || self.servers.iter().any(|s| s == server_name)
+    }
+
+    pub fn allows_tool(&self, server_name: &str, tool_name: &str) -> bool {
+        self.allows_server(server_name)
+            && (self.tools.is_empty()
+                || self
+                    .tools
+                    .iter()
+                    .any(|t| t.split_once("::") == Some((server_name, tool_name))))
+    }
+
+    pub fn allows_macro(&self, macro_name: &str) -> bool {
+        self.tools.is_empty() || self.tools.iter().any(|t| t == macro_name)
+    }
+}
+
+// Tools a downstream client may see and call. Patterns use `*` as a wildcard;
+// tool patterns are `server::tool` or a macro name. Deny lists win over allow
+// lists, and an empty allow list allows everything.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct ClientPolicy {
+    // Shown in the log entry of every call the policy applied to.
+    pub name: String,
+    // Pattern for clientInfo.name, matched case-insensitively.
+    pub client: String,
+    #[serde(default)]
+    pub allow_servers: Vec<String>,
+    #[serde(default)]
+    pub deny_servers: Vec<String>,
+    #[serde(default)]
+    pub allow_tools: Vec<String>,
+    #[serde(default)]
+    pub deny_tools: Vec<String>,
+    // Only tools annotated with readOnlyHint; macros are excluded.
+    #[serde(default)]
+    pub read_only: bool,
+}
+
+impl ClientPolicy {
+    pub fn matches_client(&self, client_name: &str) -> bool {
+        glob_match(&self.client.to_lowercase(), &client_name.to_lowercase())
+    }
+
+    pub fn allows_server(&self, server_name: &str) -> bool {
+        let matches = |p: &String| glob_match(p, server_name);
+        !self.deny_servers.iter().any(matches)
+            && (self.allow_servers.is_empty() || self.allow_servers.iter().any(matches))
+    }
+
+    pub fn allows_tool(&self, server_name: &str, tool_name: &str, read_only: bool) -> bool {
+        let qualified = format!("{server_name}::{tool_name}");
+        let matches = |p: &String| p.contains("::") && glob_match(p, &qualified);
+        self.allows_server(server_name)
+            && (read_only || !self.read_only)
+            && !self.deny_tools.iter().any(matches)
+            && (self.allow_tools.is_empty() || self.allow_tools.iter().any(matches))
+    }
+
+    pub fn allows_macro(&self, macro_name: &str) -> bool {
+        let matches = |p: &String| !p.contains("::") && glob_match(p, macro_name);
+        !self.read_only
+            && !self.deny_tools.iter().any(matches)
+            && (self.allow_tools.is_empty() || self.allow_tools.iter().any(matches))
+    }
+}
+
+pub fn client_policy_for<'a>(
+    policies: &'a [ClientPolicy],
+    client_name: &str,
+) -> Option<&'a ClientPolicy> {
+    policies.iter().find(|p| p.matches_client(client_name))
+}
+
+// `*` matches any run of characters, including none.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+// A virtual tool that runs a fixed sequence of upstream tool calls.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct MacroTool {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    // JSON schema for the macro's own arguments; defaults to any object.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[specta(optional)]
+    pub input_schema: Option<serde_json::Value>,
+    pub steps: Vec<MacroStep>,
+}
+
+// One upstream call. String arguments may reference {{input.<arg>}},
+// {{steps.<n>.text}}, {{steps.<n>.json.<path>}}, {{steps.<n>.structured.<path>}}
+// and {{env.NAME}}.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct MacroStep {
+    pub server: String,
+    pub tool: String,
+    #[serde(default)]
+    pub arguments: HashMap<String, serde_json::Value>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Type)]
@@ -71,6 +269,23 @@ pub struct ClientStatus {
     pub last_error: Option<String>,
     pub authorization_required: bool,
     pub oauth_authenticated: bool,
+    // Exposed names this server shares with other servers.
+    #[serde(default)]
+    pub name_conflicts: Vec<String>,
+    #[serde(default)]
+    pub usage: CallUsage,
+}
+
+// Live tools/call load on a server, as tracked by the limiter.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Type)]
+pub struct CallUsage {
+    pub in_flight: u32,
+    // Calls that were queued or rejected by a limit since startup.
+    pub throttled: u64,
+    // Tokens left in the server's rate bucket, when it has one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[specta(optional)]
+    pub tokens_available: Option<u32>,
 }
 
 // Logging settings removed: logging is always on and unconfigurable.
@@ -91,6 +306,11 @@ pub struct IncomingClient {
 // Config paths abstraction to make IO testable
 pub trait ConfigProvider: Send + Sync {
     fn base_dir(&self) -> PathBuf;
+
+    // Where API token values are kept; the OS keyring unless overridden.
+    fn secret_store(&self) -> &dyn crate::secrets::SecretStore {
+        crate::named_secrets::os_secret_store()
+    }
 }
 
 #[derive(Default, Clone)]
@@ -108,6 +328,16 @@ pub fn default_settings() -> Settings {
         mcp_servers: Vec::new(),
         listen_addr: "http://localhost:8091/mcp".to_string(),
         transport: ServerTransport::StreamableHttp,
+        tools_page_size: None,
+        tool_search: false,
+        macros: Vec::new(),
+        profiles: Vec::new(),
+        require_api_token: false,
+        allowed_hosts: Vec::new(),
+        allowed_origins: Vec::new(),
+        client_policies: Vec::new(),
+        approve_destructive_tools: false,
+        approval_timeout_secs: None,
     }
 }
 
@@ -115,19 +345,172 @@ pub fn settings_path(cp: &dyn ConfigProvider) -> PathBuf {
     cp.base_dir().join("settings.json")
 }
 
+// A problem in settings.json. Syntax errors carry a line and column; other
+// problems name the offending field, e.g. `mcp_servers[1].endpoint`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct SettingsIssue {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[specta(optional)]
+    pub field: Option<String>,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[specta(optional)]
+    pub line: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[specta(optional)]
+    pub column: Option<u32>,
+}
+
+impl std::fmt::Display for SettingsIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.field, self.line, self.column) {
+            (_, Some(line), Some(column)) => {
+                write!(f, "line {line}, column {column}: {}", self.message)
+            }
+            (Some(field), _, _) => write!(f, "{field}: {}", self.message),
+            _ => f.write_str(&self.message),
+        }
+    }
+}
+
+impl SettingsIssue {
+    fn at(field: String, message: impl Into<String>) -> Self {
+        Self {
+            field: Some(field),
+            message: message.into(),
+            line: None,
+            column: None,
+        }
+    }
+}
+
+pub fn parse_settings(content: &str) -> Result<Settings, SettingsIssue> {
+    serde_json::from_str(content).map_err(|e| {
+        let position = format!(" at line {} column {}", e.line(), e.column());
+        let message = e.to_string();
+        SettingsIssue {
+            field: None,
+            message: message
+                .strip_suffix(&position)
+                .unwrap_or(&message)
+                .to_string(),
+            line: Some(e.line() as u32),
+            column: Some(e.column() as u32),
+        }
+    })
+}
+
+// Reads settings.json; a missing file yields the defaults.
+pub fn read_settings_with(cp: &dyn ConfigProvider) -> Result<Settings, SettingsIssue> {
+    match fs::read_to_string(settings_path(cp)) {
+        Ok(content) => parse_settings(&content),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(default_settings()),
+        Err(e) => Err(SettingsIssue {
+            field: None,
+            message: format!("read settings: {e}"),
+            line: None,
+            column: None,
+        }),
+    }
+}
+
+// HTTP header names are RFC 9110 tokens.
+fn is_valid_header_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c))
+}
+
+// Problems that parse fine but leave servers unusable or ambiguous.
+pub fn validate_settings(settings: &Settings) -> Vec<SettingsIssue> {
+    let mut issues = Vec::new();
+    let mut seen = std::collections::HashSet::new();
+    for (i, server) in settings.mcp_servers.iter().enumerate() {
+        let field = |name: &str| format!("mcp_servers[{i}].{name}");
+        if server.name.trim().is_empty() {
+            issues.push(SettingsIssue::at(field("name"), "server name is empty"));
+        } else if !seen.insert(server.name.as_str()) {
+            issues.push(SettingsIssue::at(
+                field("name"),
+                format!("duplicate server name '{}'", server.name),
+            ));
+        }
+        match server.transport {
+            TransportType::Stdio if server.command.trim().is_empty() => issues.push(
+                SettingsIssue::at(field("command"), "stdio server needs a command"),
+            ),
+            TransportType::Sse | TransportType::StreamableHttp
+                if server.endpoint.trim().is_empty() =>
+            {
+                issues.push(SettingsIssue::at(
+                    field("endpoint"),
+                    "http server needs an endpoint",
+                ))
+            }
+            _ => {}
+        }
+        let mut headers: Vec<&String> = server.headers.keys().collect();
+        headers.sort();
+        for name in headers.into_iter().filter(|n| !is_valid_header_name(n)) {
+            issues.push(SettingsIssue::at(
+                field("headers"),
+                format!("invalid header name '{name}'"),
+            ));
+        }
+    }
+    issues
+}
+
+// Everything wrong with settings.json: its syntax error, or else every
+// semantic problem. Empty when the file is fine or missing.
+pub fn check_settings_with(cp: &dyn ConfigProvider) -> Vec<SettingsIssue> {
+    match read_settings_with(cp) {
+        Ok(settings) => validate_settings(&settings),
+        Err(issue) => vec![issue],
+    }
+}
+
+// Last settings that parsed, per file, so a typo made while the app runs does
+// not drop every server.
+fn last_good() -> &'static Mutex<HashMap<PathBuf, Settings>> {
+    static LAST_GOOD: OnceLock<Mutex<HashMap<PathBuf, Settings>>> = OnceLock::new();
+    LAST_GOOD.get_or_init(Default::default)
+}
+
+// Never fails: an unreadable file falls back to the last good settings (or
+// the defaults) and is reported by `check_settings_with`.
 pub fn load_settings_with(cp: &dyn ConfigProvider) -> Settings {
     let path = settings_path(cp);
-    if let Ok(content) = fs::read_to_string(&path)
-        && let Ok(s) = serde_json::from_str::<Settings>(&content)
-    {
-        return s;
+    match read_settings_with(cp) {
+        Ok(s) => {
+            last_good().lock().unwrap().insert(path, s.clone());
+            s
+        }
+        Err(issue) => {
+            tracing::warn!(target = "config", path = %path.display(), error = %issue, "settings_invalid");
+            last_good()
+                .lock()
+                .unwrap()
+                .get(&path)
+                .cloned()
+                .unwrap_or_else(default_settings)
+        }
     }
-    default_settings()
 }
 
+// Refuses to replace a settings.json that does not parse, so the user's file
+// is not overwritten with fallback settings.
 pub fn save_settings_with(cp: &dyn ConfigProvider, settings: &Settings) -> Result<(), String> {
-    fs::create_dir_all(cp.base_dir()).map_err(|e| format!("create config dir: {e}"))?;
     let path = settings_path(cp);
+    if let Ok(content) = fs::read_to_string(&path)
+        && let Err(issue) = parse_settings(&content)
+    {
+        return Err(format!(
+            "settings.json is invalid ({issue}); fix or remove it before saving"
+        ));
+    }
+    fs::create_dir_all(cp.base_dir()).map_err(|e| format!("create config dir: {e}"))?;
     let content = serde_json::to_string_pretty(settings).map_err(|e| format!("to json: {e}"))?;
     fs::write(&path, content).map_err(|e| format!("write settings: {e}"))
 }
@@ -192,6 +575,226 @@ pub fn save_tools_toggle_with(
     fs::write(&path, content).map_err(|e| format!("write tools state: {e}"))
 }
 
+// Per-tool overrides of the exposed name and description, keyed by server then
+// upstream tool name.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, Type)]
+pub struct ToolOverride {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[specta(optional)]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[specta(optional)]
+    pub description: Option<String>,
+    // Arguments removed from the exposed input schema and dropped from calls.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hidden: Vec<String>,
+    // Arguments pinned on every forwarded call; also hidden from the schema.
+    // String values may use {{env.NAME}}, {{client.name}} and {{client.version}}.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub inject: HashMap<String, serde_json::Value>,
+    // `ask` pauses every call until the user allows it; `auto` never asks,
+    // even when destructive tools need approval.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[specta(optional)]
+    pub approval: Option<ToolApproval>,
+    // Caps applied to this tool on top of the server's `limits`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[specta(optional)]
+    pub limits: Option<CallLimits>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolApproval {
+    Ask,
+    Auto,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct ToolOverrides(pub HashMap<String, HashMap<String, ToolOverride>>);
+
+impl ToolOverrides {
+    pub fn get(&self, server_name: &str, tool_name: &str) -> Option<&ToolOverride> {
+        self.0.get(server_name).and_then(|m| m.get(tool_name))
+    }
+}
+
+pub fn tool_overrides_path(cp: &dyn ConfigProvider) -> PathBuf {
+    cp.base_dir().join("tool_overrides.json")
+}
+
+pub fn load_tool_overrides_with(cp: &dyn ConfigProvider) -> ToolOverrides {
+    let path = tool_overrides_path(cp);
+    if let Ok(content) = fs::read_to_string(&path)
+        && let Ok(s) = serde_json::from_str::<ToolOverrides>(&content)
+    {
+        return s;
+    }
+    ToolOverrides::default()
+}
+
+// Custom names use the same charset as generated aliases.
+pub fn is_valid_tool_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+// Decides which custom names from the override map can be applied. A custom
+// name is rejected when it is invalid, when another tool claims it too or when
+// it equals the generated alias of a tool without a custom name. `tools` lists
+// known tools as (server, tool, generated alias). Errors are sorted by name so
+// the outcome never depends on the order tools were listed in.
+pub fn plan_tool_names(
+    overrides: &ToolOverrides,
+    tools: &[(String, String, String)],
+) -> (HashMap<(String, String), String>, Vec<String>) {
+    let mut claims: std::collections::BTreeMap<&str, Vec<(&str, &str)>> = Default::default();
+    for (server_name, entries) in &overrides.0 {
+        for (tool_name, entry) in entries {
+            if let Some(name) = entry.name.as_deref() {
+                claims
+                    .entry(name)
+                    .or_default()
+                    .push((server_name.as_str(), tool_name.as_str()));
+            }
+        }
+    }
+    let mut generated: HashMap<&str, Vec<String>> = HashMap::new();
+    for (server, tool, alias) in tools {
+        if overrides
+            .get(server, tool)
+            .and_then(|o| o.name.as_ref())
+            .is_none()
+        {
+            generated
+                .entry(alias.as_str())
+                .or_default()
+                .push(format!("{server}::{tool}"));
+        }
+    }
+
+    let mut names = HashMap::new();
+    let mut errors = Vec::new();
+    for (name, mut owners) in claims {
+        owners.sort();
+        let described = owners
+            .iter()
+            .map(|(s, t)| format!("{s}::{t}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        if !is_valid_tool_name(name) {
+            errors.push(format!(
+                "invalid tool name '{name}' for {described}: use letters, digits, '_' or '-'"
+            ));
+        } else if owners.len() > 1 {
+            errors.push(format!(
+                "tool name '{name}' is used by more than one tool: {described}"
+            ));
+        } else if let Some(others) = generated.get_mut(name) {
+            others.sort();
+            errors.push(format!(
+                "tool name '{name}' for {described} collides with {}",
+                others.join(", ")
+            ));
+        } else {
+            let (server, tool) = owners[0];
+            names.insert((server.to_string(), tool.to_string()), name.to_string());
+        }
+    }
+    (names, errors)
+}
+
+// Same rules as `plan_tool_names`, failing with the first error so a bad file
+// is never saved.
+pub fn validate_tool_overrides(
+    overrides: &ToolOverrides,
+    generated: &[(String, String, String)],
+) -> Result<(), String> {
+    match plan_tool_names(overrides, generated).1.into_iter().next() {
+        Some(error) => Err(error),
+        None => Ok(()),
+    }
+}
+
+// Sets or clears (when `entry` is None or empty) the override for one tool.
+pub fn save_tool_override_with(
+    cp: &dyn ConfigProvider,
+    server_name: &str,
+    tool_name: &str,
+    entry: Option<ToolOverride>,
+    generated: &[(String, String, String)],
+) -> Result<(), String> {
+    let mut overrides = load_tool_overrides_with(cp);
+    let tools = overrides.0.entry(server_name.to_string()).or_default();
+    match entry.filter(|e| *e != ToolOverride::default()) {
+        Some(entry) => {
+            tools.insert(tool_name.to_string(), entry);
+        }
+        None => {
+            tools.remove(tool_name);
+        }
+    }
+    if tools.is_empty() {
+        overrides.0.remove(server_name);
+    }
+    validate_tool_overrides(&overrides, generated)?;
+    let content = serde_json::to_string_pretty(&overrides).map_err(|e| format!("to json: {e}"))?;
+    fs::create_dir_all(cp.base_dir()).map_err(|e| format!("create dir: {e}"))?;
+    fs::write(tool_overrides_path(cp), content).map_err(|e| format!("write tool overrides: {e}"))
+}
+
+// Prompts toggle persisted map helpers (same shape as ToolsState)
+#[derive(Serialize, Deserialize, Default)]
+pub struct PromptsState(pub HashMap<String, HashMap<String, bool>>);
+
+pub fn prompts_state_path(cp: &dyn ConfigProvider) -> PathBuf {
+    cp.base_dir().join("prompts_state.json")
+}
+
+pub fn load_prompts_state_with(cp: &dyn ConfigProvider) -> PromptsState {
+    let path = prompts_state_path(cp);
+    if let Ok(content) = fs::read_to_string(&path)
+        && let Ok(s) = serde_json::from_str::<PromptsState>(&content)
+    {
+        return s;
+    }
+    PromptsState::default()
+}
+
+pub fn is_prompt_enabled_with(
+    cp: &dyn ConfigProvider,
+    client_name: &str,
+    prompt_name: &str,
+) -> bool {
+    let state = load_prompts_state_with(cp);
+    state
+        .0
+        .get(client_name)
+        .and_then(|m| m.get(prompt_name))
+        .copied()
+        .unwrap_or(true)
+}
+
+pub fn save_prompts_toggle_with(
+    cp: &dyn ConfigProvider,
+    client_name: &str,
+    prompt_name: &str,
+    enabled: bool,
+) -> Result<(), String> {
+    let path = prompts_state_path(cp);
+    let mut state = load_prompts_state_with(cp);
+    state
+        .0
+        .entry(client_name.to_string())
+        .or_default()
+        .insert(prompt_name.to_string(), enabled);
+    let content = serde_json::to_string_pretty(&state).map_err(|e| format!("to json: {e}"))?;
+    fs::create_dir_all(cp.base_dir()).map_err(|e| format!("create dir: {e}"))?;
+    fs::write(&path, content).map_err(|e| format!("write prompts state: {e}"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -240,12 +843,99 @@ mod tests {
             endpoint: "http://127.0.0.1".into(),
             headers: Default::default(),
             enabled: true,
+            prefix: None,
+            limits: None,
         });
         save_settings_with(&cp, &s).unwrap();
         let loaded = load_settings_with(&cp);
         assert_eq!(loaded.mcp_servers.len(), 1);
         assert_eq!(loaded.listen_addr, s.listen_addr);
         assert_eq!(loaded.transport, s.transport);
+        assert_eq!(loaded.tools_page_size, None);
+    }
+
+    #[test]
+    fn tools_page_size_is_optional_in_settings_file() {
+        let cp = TempConfigProvider::new();
+        std::fs::write(
+            settings_path(&cp),
+            r#"{"mcp_servers":[],"listen_addr":"x","transport":"streamable_http"}"#,
+        )
+        .unwrap();
+        assert_eq!(load_settings_with(&cp).tools_page_size, None);
+
+        let mut s = default_settings();
+        s.tools_page_size = Some(25);
+        save_settings_with(&cp, &s).unwrap();
+        assert_eq!(load_settings_with(&cp).tools_page_size, Some(25));
+    }
+
+    #[test]
+    fn invalid_settings_file_is_reported_and_not_overwritten() {
+        let cp = TempConfigProvider::new();
+        let mut s = default_settings();
+        s.listen_addr = "http://127.0.0.1:9000/mcp".into();
+        save_settings_with(&cp, &s).unwrap();
+        assert_eq!(load_settings_with(&cp).listen_addr, s.listen_addr);
+
+        let broken = "{\n  \"mcp_servers\": [],\n  \"listen_addr\": \"x\",,\n}";
+        fs::write(settings_path(&cp), broken).unwrap();
+        let issues = check_settings_with(&cp);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].line, Some(3));
+        assert!(issues[0].column.is_some());
+        assert!(!issues[0].message.contains("at line"), "{}", issues[0]);
+
+        // Running code keeps the last good settings instead of the defaults.
+        assert_eq!(load_settings_with(&cp).listen_addr, s.listen_addr);
+        let err = save_settings_with(&cp, &default_settings()).unwrap_err();
+        assert!(err.contains("line 3, column"), "{err}");
+        assert_eq!(fs::read_to_string(settings_path(&cp)).unwrap(), broken);
+    }
+
+    #[test]
+    fn semantic_problems_name_the_field() {
+        let server = |name: &str, transport: TransportType| MCPServerConfig {
+            name: name.into(),
+            description: String::new(),
+            transport,
+            command: String::new(),
+            args: vec![],
+            env: Default::default(),
+            endpoint: String::new(),
+            headers: Default::default(),
+            enabled: true,
+            prefix: None,
+            limits: None,
+        };
+        let mut s = default_settings();
+        let mut ok = server("gh", TransportType::Stdio);
+        ok.command = "gh-mcp".into();
+        let mut bad_header = server("web", TransportType::StreamableHttp);
+        bad_header.endpoint = "http://localhost/mcp".into();
+        bad_header.headers.insert("X Token".into(), "v".into());
+        bad_header.headers.insert("X-Ok".into(), "v".into());
+        s.mcp_servers = vec![
+            ok.clone(),
+            ok,
+            server("fs", TransportType::Stdio),
+            server("remote", TransportType::Sse),
+            bad_header,
+        ];
+        let issues: Vec<String> = validate_settings(&s)
+            .iter()
+            .map(|i| i.to_string())
+            .collect();
+        assert_eq!(
+            issues,
+            vec![
+                "mcp_servers[1].name: duplicate server name 'gh'",
+                "mcp_servers[2].command: stdio server needs a command",
+                "mcp_servers[3].endpoint: http server needs an endpoint",
+                "mcp_servers[4].headers: invalid header name 'X Token'",
+            ]
+        );
+        assert!(validate_settings(&default_settings()).is_empty());
     }
 
     #[test]
@@ -282,4 +972,133 @@ mod tests {
         // unrelated tool defaults to true
         assert!(is_tool_enabled_with(&cp, "clientA", "other"));
     }
+
+    #[test]
+    fn prompts_toggle_is_separate_from_tools() {
+        let cp = TempConfigProvider::new();
+        assert!(is_prompt_enabled_with(&cp, "srv", "summarize"));
+        save_prompts_toggle_with(&cp, "srv", "summarize", false).unwrap();
+        assert!(!is_prompt_enabled_with(&cp, "srv", "summarize"));
+        assert!(is_tool_enabled_with(&cp, "srv", "summarize"));
+        assert!(prompts_state_path(&cp).exists());
+        assert!(!tools_state_path(&cp).exists());
+    }
+
+    #[test]
+    fn profile_allow_lists() {
+        let open = Profile {
+            name: "all".into(),
+            servers: vec![],
+            tools: vec![],
+        };
+        assert!(open.allows_tool("fs", "read"));
+        assert!(open.allows_macro("deploy"));
+
+        let coding = Profile {
+            name: "coding".into(),
+            servers: vec!["fs".into(), "git".into()],
+            tools: vec!["fs::read".into(), "git::commit".into(), "ship".into()],
+        };
+        assert!(coding.allows_server("git"));
+        assert!(!coding.allows_server("web"));
+        assert!(coding.allows_tool("fs", "read"));
+        assert!(!coding.allows_tool("fs", "write"));
+        assert!(!coding.allows_tool("web", "read"));
+        assert!(coding.allows_macro("ship"));
+        assert!(!coding.allows_macro("deploy"));
+    }
+
+    #[test]
+    fn glob_patterns() {
+        assert!(glob_match("*", ""));
+        assert!(glob_match("github::get_*", "github::get_issue"));
+        assert!(glob_match("*::list*", "fs::list_dir"));
+        assert!(glob_match("a*b*c", "axxbyyc"));
+        assert!(!glob_match("a*b*c", "axxc"));
+        assert!(!glob_match("ab*ba", "aba"));
+        assert!(!glob_match("fs", "fs2"));
+    }
+
+    #[test]
+    fn client_policy_rules() {
+        let policies = vec![
+            ClientPolicy {
+                name: "cursor-readonly".into(),
+                client: "cursor*".into(),
+                allow_servers: vec![],
+                deny_servers: vec!["shell".into()],
+                allow_tools: vec![],
+                deny_tools: vec!["github::delete_*".into()],
+                read_only: true,
+            },
+            ClientPolicy {
+                name: "desktop".into(),
+                client: "claude-ai".into(),
+                allow_servers: vec![],
+                deny_servers: vec![],
+                allow_tools: vec!["fs::*".into(), "ship".into()],
+                deny_tools: vec![],
+                read_only: false,
+            },
+        ];
+        let cursor = client_policy_for(&policies, "Cursor-VSCode").unwrap();
+        assert_eq!(cursor.name, "cursor-readonly");
+        assert!(cursor.allows_tool("github", "get_issue", true));
+        assert!(!cursor.allows_tool("github", "create_issue", false));
+        assert!(!cursor.allows_tool("github", "delete_repo", true));
+        assert!(!cursor.allows_tool("shell", "ls", true));
+        assert!(!cursor.allows_macro("ship"));
+
+        let desktop = client_policy_for(&policies, "claude-ai").unwrap();
+        assert!(desktop.allows_tool("fs", "write", false));
+        assert!(!desktop.allows_tool("github", "get_issue", true));
+        assert!(desktop.allows_macro("ship"));
+        assert!(!desktop.allows_macro("deploy"));
+
+        assert!(client_policy_for(&policies, "inspector").is_none());
+    }
+
+    #[test]
+    fn tool_overrides_persist_and_clear() {
+        let cp = TempConfigProvider::new();
+        let entry = ToolOverride {
+            name: Some("search".into()),
+            description: Some("Search the docs".into()),
+            ..Default::default()
+        };
+        save_tool_override_with(&cp, "docs", "find", Some(entry.clone()), &[]).unwrap();
+        assert_eq!(
+            load_tool_overrides_with(&cp).get("docs", "find"),
+            Some(&entry)
+        );
+        save_tool_override_with(&cp, "docs", "find", None, &[]).unwrap();
+        assert!(load_tool_overrides_with(&cp).0.is_empty());
+    }
+
+    #[test]
+    fn tool_override_collisions_are_rejected_deterministically() {
+        let cp = TempConfigProvider::new();
+        let named = |n: &str| {
+            Some(ToolOverride {
+                name: Some(n.into()),
+                ..Default::default()
+            })
+        };
+        save_tool_override_with(&cp, "b", "find", named("search"), &[]).unwrap();
+        let err = save_tool_override_with(&cp, "a", "lookup", named("search"), &[]).unwrap_err();
+        assert_eq!(
+            err,
+            "tool name 'search' is used by more than one tool: a::lookup, b::find"
+        );
+        assert!(load_tool_overrides_with(&cp).get("a", "lookup").is_none());
+        assert!(save_tool_override_with(&cp, "a", "lookup", named("bad name"), &[]).is_err());
+
+        let generated = [("c".to_string(), "find".to_string(), "c__find".to_string())];
+        let err =
+            save_tool_override_with(&cp, "a", "lookup", named("c__find"), &generated).unwrap_err();
+        assert_eq!(
+            err,
+            "tool name 'c__find' for a::lookup collides with c::find"
+        );
+    }
 }
@@ -0,0 +1,356 @@
+use std::collections::HashMap;
+
+use rmcp::model::{
+    LoggingLevel, LoggingMessageNotificationParam, ProgressNotificationParam, ProgressToken,
+};
+use rmcp::{Peer, RoleServer};
+use tokio::sync::mpsc;
+
+// Downstream sessions that completed initialization, keyed by the id of their
+// service instance. Used to fan out server-initiated notifications (e.g.
+// list_changed) to every connected client.
+struct Session {
+    key: u64,
+    peer: Peer<RoleServer>,
+    // Level last requested by this session through logging/setLevel.
+    log_level: Option<LoggingLevel>,
+}
+
+static DOWNSTREAM_PEERS: std::sync::OnceLock<tokio::sync::Mutex<Vec<Session>>> =
+    std::sync::OnceLock::new();
+
+fn sessions() -> &'static tokio::sync::Mutex<Vec<Session>> {
+    DOWNSTREAM_PEERS.get_or_init(|| tokio::sync::Mutex::new(Vec::new()))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ListChanged {
+    Tools,
+    Resources,
+    Prompts,
+}
+
+impl ListChanged {
+    pub const ALL: [ListChanged; 3] = [
+        ListChanged::Tools,
+        ListChanged::Resources,
+        ListChanged::Prompts,
+    ];
+}
+
+// Drops sessions whose transport has closed, along with the tool approvals
+// they were granted for the rest of the session.
+async fn prune_closed(g: &mut Vec<Session>) {
+    let closed: Vec<u64> = g
+        .iter()
+        .filter(|s| s.peer.is_transport_closed())
+        .map(|s| s.key)
+        .collect();
+    g.retain(|s| !closed.contains(&s.key));
+    crate::approvals::forget_sessions(&closed).await;
+}
+
+pub async fn register_peer(key: u64, peer: Peer<RoleServer>) {
+    let mut g = sessions().lock().await;
+    prune_closed(&mut g).await;
+    g.push(Session {
+        key,
+        peer,
+        log_level: None,
+    });
+}
+
+// Snapshot live peers so notifications are sent without holding the registry
+// lock, each with the log level its session asked for.
+async fn live_peers() -> Vec<(Peer<RoleServer>, Option<LoggingLevel>)> {
+    let mut g = sessions().lock().await;
+    prune_closed(&mut g).await;
+    g.iter().map(|s| (s.peer.clone(), s.log_level)).collect()
+}
+
+pub async fn notify_list_changed(kind: ListChanged) {
+    for (peer, _) in live_peers().await {
+        let res = match kind {
+            ListChanged::Tools => peer.notify_tool_list_changed().await,
+            ListChanged::Resources => peer.notify_resource_list_changed().await,
+            ListChanged::Prompts => peer.notify_prompt_list_changed().await,
+        };
+        if let Err(e) = res {
+            tracing::debug!(target = "downstream", ?kind, error = %e, "notify_list_changed_failed");
+        }
+    }
+}
+
+// Server added/removed/toggled: every aggregated list may have changed.
+pub async fn notify_all_lists_changed() {
+    for kind in ListChanged::ALL {
+        notify_list_changed(kind).await;
+    }
+}
+
+// Records the level one session asked for; other sessions keep theirs.
+pub async fn set_log_level(key: u64, level: LoggingLevel) {
+    let mut g = sessions().lock().await;
+    if let Some(session) = g.iter_mut().find(|s| s.key == key) {
+        session.log_level = Some(level);
+    }
+}
+
+// Upstreams are shared, so they log at the most verbose level any live session
+// asked for; each session is then sent only what it asked for. Upstreams that
+// connect later are brought to the same level when they start.
+pub async fn upstream_log_level() -> Option<LoggingLevel> {
+    let mut g = sessions().lock().await;
+    prune_closed(&mut g).await;
+    g.iter()
+        .filter_map(|s| s.log_level)
+        .min_by_key(|level| *level as u8)
+}
+
+// Sessions that never set a level receive everything.
+fn wants(session_level: Option<LoggingLevel>, level: LoggingLevel) -> bool {
+    session_level.is_none_or(|min| level as u8 >= min as u8)
+}
+
+// Upstream log messages go to every session that asked for their level, with
+// the logger prefixed by the server name so clients can tell sources apart
+// (`github`, `github/http`, ...).
+pub async fn notify_logging_message(server_name: &str, mut param: LoggingMessageNotificationParam) {
+    param.logger = Some(tag_logger(server_name, param.logger.take()));
+    for (peer, session_level) in live_peers().await {
+        if !wants(session_level, param.level) {
+            continue;
+        }
+        if let Err(e) = peer.notify_logging_message(param.clone()).await {
+            tracing::debug!(target = "downstream", server = %server_name, error = %e, "notify_logging_message_failed");
+        }
+    }
+}
+
+fn tag_logger(server_name: &str, logger: Option<String>) -> String {
+    match logger {
+        Some(logger) if !logger.is_empty() => format!("{server_name}/{logger}"),
+        _ => server_name.to_string(),
+    }
+}
+
+// Downstream sessions with a proxied request in flight, per upstream server. An
+// upstream that issues sampling/elicitation/roots requests while serving a call
+// is answered by the session whose call carries the progress token named in the
+// request's `_meta`, or else by the only session with a call in flight.
+struct InFlight {
+    id: u64,
+    session: u64,
+    peer: Peer<RoleServer>,
+    // Token the upstream was given for this call, if any.
+    progress_token: Option<ProgressToken>,
+}
+
+type ActiveCalls = HashMap<String, Vec<InFlight>>;
+
+static ACTIVE_CALLS: std::sync::OnceLock<std::sync::Mutex<ActiveCalls>> =
+    std::sync::OnceLock::new();
+static NEXT_CALL_ID: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+
+fn active_calls() -> &'static std::sync::Mutex<ActiveCalls> {
+    ACTIVE_CALLS.get_or_init(|| std::sync::Mutex::new(HashMap::new()))
+}
+
+/// Marks a downstream peer as waiting on `server_name`; cleared on drop.
+pub struct ActiveCall {
+    server_name: String,
+    id: u64,
+}
+
+impl Drop for ActiveCall {
+    fn drop(&mut self) {
+        let mut g = active_calls().lock().unwrap();
+        if let Some(calls) = g.get_mut(&self.server_name) {
+            calls.retain(|c| c.id != self.id);
+            if calls.is_empty() {
+                g.remove(&self.server_name);
+            }
+        }
+    }
+}
+
+pub fn track_active_call(
+    server_name: &str,
+    session: u64,
+    peer: Peer<RoleServer>,
+    progress_token: Option<ProgressToken>,
+) -> ActiveCall {
+    let id = NEXT_CALL_ID.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    active_calls()
+        .lock()
+        .unwrap()
+        .entry(server_name.to_string())
+        .or_default()
+        .push(InFlight {
+            id,
+            session,
+            peer,
+            progress_token,
+        });
+    ActiveCall {
+        server_name: server_name.to_string(),
+        id,
+    }
+}
+
+// The downstream session an upstream request belongs to, or why it cannot be
+// told. Guessing between sessions would leak one client's request to another.
+pub fn active_caller(
+    server_name: &str,
+    progress_token: Option<&ProgressToken>,
+) -> Result<Peer<RoleServer>, String> {
+    let g = active_calls().lock().unwrap();
+    let calls: Vec<&InFlight> = g
+        .get(server_name)
+        .map(|calls| {
+            calls
+                .iter()
+                .filter(|c| !c.peer.is_transport_closed())
+                .collect()
+        })
+        .unwrap_or_default();
+    let keys: Vec<(u64, Option<&ProgressToken>)> = calls
+        .iter()
+        .map(|c| (c.session, c.progress_token.as_ref()))
+        .collect();
+    let session = pick_session(&keys, progress_token)?;
+    calls
+        .iter()
+        .rev()
+        .find(|c| c.session == session)
+        .map(|c| c.peer.clone())
+        .ok_or_else(|| "no downstream session has a request in flight".to_string())
+}
+
+fn pick_session(
+    calls: &[(u64, Option<&ProgressToken>)],
+    progress_token: Option<&ProgressToken>,
+) -> Result<u64, String> {
+    if let Some(token) = progress_token
+        && let Some((session, _)) = calls.iter().find(|(_, t)| *t == Some(token))
+    {
+        return Ok(*session);
+    }
+    let mut sessions: Vec<u64> = calls.iter().map(|(session, _)| *session).collect();
+    sessions.sort_unstable();
+    sessions.dedup();
+    match sessions.as_slice() {
+        [] => Err("no downstream session has a request in flight".to_string()),
+        [session] => Ok(*session),
+        many => Err(format!(
+            "{} downstream sessions have requests in flight and the request does not say which it belongs to",
+            many.len()
+        )),
+    }
+}
+
+type ProgressKey = (String, ProgressToken);
+type ProgressSender = mpsc::UnboundedSender<ProgressNotificationParam>;
+
+// In-flight proxied requests keyed by (upstream server, upstream progress token).
+static PROGRESS_ROUTES: std::sync::OnceLock<
+    std::sync::Mutex<HashMap<ProgressKey, ProgressSender>>,
+> = std::sync::OnceLock::new();
+
+fn progress_routes() -> &'static std::sync::Mutex<HashMap<ProgressKey, ProgressSender>> {
+    PROGRESS_ROUTES.get_or_init(|| std::sync::Mutex::new(HashMap::new()))
+}
+
+/// Receives upstream progress for a single proxied request. The route is
+/// unregistered when this value is dropped.
+pub struct ProgressRoute {
+    key: ProgressKey,
+    rx: mpsc::UnboundedReceiver<ProgressNotificationParam>,
+}
+
+impl ProgressRoute {
+    pub async fn recv(&mut self) -> Option<ProgressNotificationParam> {
+        self.rx.recv().await
+    }
+}
+
+impl Drop for ProgressRoute {
+    fn drop(&mut self) {
+        progress_routes().lock().unwrap().remove(&self.key);
+    }
+}
+
+pub fn register_progress_route(server_name: &str, token: ProgressToken) -> ProgressRoute {
+    let (tx, rx) = mpsc::unbounded_channel();
+    let key = (server_name.to_string(), token);
+    progress_routes().lock().unwrap().insert(key.clone(), tx);
+    ProgressRoute { key, rx }
+}
+
+// Returns false when no in-flight request owns the token (late or unsolicited progress).
+pub fn route_progress(server_name: &str, param: ProgressNotificationParam) -> bool {
+    let key = (server_name.to_string(), param.progress_token.clone());
+    let guard = progress_routes().lock().unwrap();
+    match guard.get(&key) {
+        Some(tx) => tx.send(param).is_ok(),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rmcp::model::NumberOrString;
+
+    fn progress(token: ProgressToken, progress: f64) -> ProgressNotificationParam {
+        ProgressNotificationParam {
+            progress_token: token,
+            progress,
+            total: Some(10.0),
+            message: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn progress_is_routed_per_server_and_token() {
+        let token = ProgressToken(NumberOrString::Number(7));
+        let mut route = register_progress_route("srv-a", token.clone());
+
+        assert!(route_progress("srv-a", progress(token.clone(), 1.0)));
+        assert!(!route_progress("srv-b", progress(token.clone(), 2.0)));
+        let got = route.recv().await.unwrap();
+        assert_eq!(got.progress, 1.0);
+
+        drop(route);
+        assert!(!route_progress("srv-a", progress(token, 3.0)));
+    }
+
+    #[test]
+    fn sessions_only_get_levels_they_asked_for() {
+        assert!(wants(None, LoggingLevel::Debug));
+        assert!(wants(Some(LoggingLevel::Warning), LoggingLevel::Error));
+        assert!(wants(Some(LoggingLevel::Warning), LoggingLevel::Warning));
+        assert!(!wants(Some(LoggingLevel::Warning), LoggingLevel::Info));
+    }
+
+    #[test]
+    fn upstream_requests_pick_an_unambiguous_session() {
+        let token = ProgressToken(NumberOrString::String("bouncer-1".into()));
+        let other = ProgressToken(NumberOrString::String("bouncer-2".into()));
+        assert!(pick_session(&[], None).is_err());
+        assert_eq!(pick_session(&[(1, None), (1, Some(&token))], None), Ok(1));
+        let busy = [(1, Some(&token)), (2, Some(&other)), (2, None)];
+        assert_eq!(pick_session(&busy, Some(&other)), Ok(2));
+        let err = pick_session(&busy, None).unwrap_err();
+        assert!(err.contains("2 downstream sessions"), "{err}");
+        let unknown = ProgressToken(NumberOrString::Number(9));
+        assert!(pick_session(&busy, Some(&unknown)).is_err());
+    }
+
+    #[test]
+    fn logger_is_tagged_with_server_name() {
+        assert_eq!(tag_logger("github", None), "github");
+        assert_eq!(tag_logger("github", Some(String::new())), "github");
+        assert_eq!(tag_logger("github", Some("http".into())), "github/http");
+    }
+}
@@ -7,6 +7,8 @@ pub const EVENT_CLIENT_STATUS_CHANGED: &str = "mcp:client_status_changed";
 pub const EVENT_CLIENT_ERROR: &str = "mcp:client_error";
 pub const EVENT_INCOMING_CLIENTS_UPDATED: &str = "mcp:incoming_clients_updated";
 pub const EVENT_LOGS_RPC_EVENT: &str = "logs:rpc_event";
+pub const EVENT_APPROVAL_REQUESTED: &str = "approval:requested";
+pub const EVENT_APPROVAL_RESOLVED: &str = "approval:resolved";
 
 pub trait EventEmitter {
     fn emit(&self, event: &str, payload: &serde_json::Value);
@@ -55,6 +57,18 @@ pub fn settings_updated<E: EventEmitter>(emitter: &E) {
     emitter.emit(EVENT_SETTINGS_UPDATED, &json!({ "reason": "update" }));
 }
 
+// A tool call waiting for the user; answered through mcp_resolve_approval.
+pub fn approval_requested<E: EventEmitter>(emitter: &E, payload: &serde_json::Value) {
+    emitter.emit(EVENT_APPROVAL_REQUESTED, payload);
+}
+
+pub fn approval_resolved<E: EventEmitter>(emitter: &E, id: &str, outcome: &str) {
+    emitter.emit(
+        EVENT_APPROVAL_RESOLVED,
+        &json!({ "id": id, "outcome": outcome }),
+    );
+}
+
 // Logs: lightweight push of newly recorded RPC events.
 // Payload mirrors a subset of crate::logging::Event after redaction.
 pub fn logs_rpc_event<E: EventEmitter>(emitter: &E, evt: &crate::logging::Event) {
@@ -72,6 +86,9 @@ pub fn logs_rpc_event<E: EventEmitter>(emitter: &E, evt: &crate::logging::Event)
             "ok": evt.ok,
             "error": evt.error,
             "origin": evt.origin,
+            "parent_id": evt.parent_id,
+            "policy": evt.policy,
+            "identity": evt.identity,
             "request_json": evt.request_json,
             "response_json": evt.response_json,
         }),
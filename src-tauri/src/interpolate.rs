@@ -0,0 +1,185 @@
+use crate::config::MCPServerConfig;
+use crate::named_secrets;
+use crate::secrets::SecretStore;
+
+// Expands `${VAR}`, `${VAR:-default}` and `${secret:NAME}` in one pass, so
+// expanded values are never expanded again. `$${` yields a literal `${`.
+pub fn expand(
+    input: &str,
+    env: &dyn Fn(&str) -> Option<String>,
+    secret_store: &dyn SecretStore,
+) -> Result<String, String> {
+    let mut out = String::new();
+    let mut rest = input;
+    while let Some(start) = rest.find("${") {
+        if rest[..start].ends_with('$') {
+            out.push_str(&rest[..start - 1]);
+            out.push_str("${");
+            rest = &rest[start + 2..];
+            continue;
+        }
+        let after = &rest[start + 2..];
+        let end = after
+            .find('}')
+            .ok_or_else(|| "unclosed '${' placeholder".to_string())?;
+        let expr = &after[..end];
+        let value = match expr.strip_prefix("secret:") {
+            Some(name) => named_secrets::lookup(name.trim(), secret_store)?,
+            None => {
+                let (var, default) = match expr.split_once(":-") {
+                    Some((var, default)) => (var, Some(default)),
+                    None => (expr, None),
+                };
+                if !is_env_name(var) {
+                    return Err(format!("invalid placeholder '${{{expr}}}'"));
+                }
+                // Like the shell, `:-` also replaces a set but empty value.
+                match (env(var), default) {
+                    (Some(value), Some(default)) if value.is_empty() => default.to_string(),
+                    (Some(value), _) => value,
+                    (None, Some(default)) => default.to_string(),
+                    (None, None) => return Err(format!("environment variable {var} is not set")),
+                }
+            }
+        };
+        out.push_str(&rest[..start]);
+        out.push_str(&value);
+        rest = &after[end + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+fn is_env_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+// Copy of `cfg` with placeholders in command, args, env, endpoint and headers
+// expanded from the process environment and the OS keyring.
+pub fn resolve_config(cfg: &MCPServerConfig) -> Result<MCPServerConfig, String> {
+    resolve_config_with(
+        cfg,
+        &|var| std::env::var(var).ok(),
+        named_secrets::os_secret_store(),
+    )
+}
+
+// The server's endpoint with placeholders expanded, for OAuth discovery and
+// 401 probes that run outside a connected client.
+pub fn resolve_endpoint(cfg: &MCPServerConfig) -> Result<String, String> {
+    expand(
+        &cfg.endpoint,
+        &|var| std::env::var(var).ok(),
+        named_secrets::os_secret_store(),
+    )
+    .map_err(|e| format!("endpoint: {e}"))
+}
+
+pub fn resolve_config_with(
+    cfg: &MCPServerConfig,
+    env: &dyn Fn(&str) -> Option<String>,
+    secret_store: &dyn SecretStore,
+) -> Result<MCPServerConfig, String> {
+    let mut out = cfg.clone();
+    let fields = [
+        ("command".to_string(), &mut out.command),
+        ("endpoint".to_string(), &mut out.endpoint),
+    ]
+    .into_iter()
+    .chain(
+        out.args
+            .iter_mut()
+            .enumerate()
+            .map(|(i, v)| (format!("args[{i}]"), v)),
+    )
+    .chain(out.env.iter_mut().map(|(k, v)| (format!("env {k}"), v)))
+    .chain(
+        out.headers
+            .iter_mut()
+            .map(|(k, v)| (format!("header {k}"), v)),
+    );
+    for (field, value) in fields {
+        if value.contains("${") {
+            *value = expand(value, env, secret_store).map_err(|e| format!("{field}: {e}"))?;
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::secrets::{MemorySecretStore, SecretKey, SecretNamespace};
+
+    fn env(var: &str) -> Option<String> {
+        match var {
+            "HOME" => Some("/home/me".into()),
+            "EMPTY" => Some(String::new()),
+            _ => None,
+        }
+    }
+
+    fn cfg() -> MCPServerConfig {
+        MCPServerConfig {
+            name: "gh".into(),
+            description: String::new(),
+            transport: crate::config::TransportType::Stdio,
+            command: "${HOME}/bin/server".into(),
+            args: vec!["--token=${secret:token}".into()],
+            env: [("ROOT".to_string(), "${WORK_DIR:-~/work}".to_string())]
+                .into_iter()
+                .collect(),
+            endpoint: String::new(),
+            headers: [(
+                "Authorization".to_string(),
+                "Bearer ${secret:token}".to_string(),
+            )]
+            .into_iter()
+            .collect(),
+            enabled: true,
+            prefix: None,
+            limits: None,
+        }
+    }
+
+    #[test]
+    fn expands_env_defaults_and_secrets() {
+        let store = MemorySecretStore::new();
+        let key = SecretKey {
+            namespace: SecretNamespace::NamedSecret,
+            identifier: "token",
+        };
+        store.set(&key, "s3cret").unwrap();
+        let out = resolve_config_with(&cfg(), &env, &store).unwrap();
+        assert_eq!(out.command, "/home/me/bin/server");
+        assert_eq!(out.args, vec!["--token=s3cret"]);
+        assert_eq!(out.env["ROOT"], "~/work");
+        assert_eq!(out.headers["Authorization"], "Bearer s3cret");
+    }
+
+    #[test]
+    fn failures_name_the_placeholder() {
+        let store = MemorySecretStore::new();
+        let err = expand("${MISSING}/x", &env, &store).unwrap_err();
+        assert_eq!(err, "environment variable MISSING is not set");
+        assert_eq!(expand("${EMPTY:-d}", &env, &store).unwrap(), "d");
+        assert_eq!(expand("[${EMPTY}]", &env, &store).unwrap(), "[]");
+        assert!(
+            expand("${HOME", &env, &store)
+                .unwrap_err()
+                .contains("unclosed")
+        );
+        assert!(expand("${secret:nope}", &env, &store).is_err());
+        assert_eq!(expand("$${HOME}", &env, &store).unwrap(), "${HOME}");
+
+        let mut bad = cfg();
+        bad.env.insert("DB".into(), "${DB_URL}".into());
+        bad.args.clear();
+        bad.headers.clear();
+        bad.command = "server".into();
+        let err = resolve_config_with(&bad, &env, &store).unwrap_err();
+        assert_eq!(err, "env DB: environment variable DB_URL is not set");
+    }
+}
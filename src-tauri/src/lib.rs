@@ -1,17 +1,29 @@
+pub mod api_tokens;
+pub mod approvals;
 pub mod client;
 pub mod config;
+pub mod downstream;
 pub mod events;
 pub mod incoming;
+pub mod interpolate;
+pub mod limits;
 mod logging_core;
 mod logging_sqlite;
+pub mod macros;
+pub mod named_secrets;
 pub mod oauth;
+pub mod origin_guard;
 pub mod overlay;
+pub mod protocol;
 pub mod runtime;
 pub mod secrets;
 pub mod server;
 pub mod socket_bridge;
 pub mod status;
+pub mod tool_rules;
+pub mod tool_search;
 pub mod tools_cache;
+pub mod transform;
 pub mod transport;
 pub mod types;
 
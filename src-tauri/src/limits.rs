@@ -0,0 +1,407 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::config::{CallLimits, CallUsage};
+
+// Token bucket holding up to `capacity` calls, refilled at `per_sec`.
+struct Bucket {
+    capacity: f64,
+    per_sec: f64,
+    tokens: f64,
+    last: Instant,
+}
+
+impl Bucket {
+    fn new(per_minute: u32, burst: Option<u32>) -> Self {
+        let capacity = f64::from(burst.unwrap_or(per_minute).max(1));
+        Self {
+            capacity,
+            per_sec: f64::from(per_minute) / 60.0,
+            tokens: capacity,
+            last: Instant::now(),
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.per_sec).min(self.capacity);
+        self.last = now;
+    }
+
+    // Time until one token is available; zero when one is available now.
+    fn wait(&mut self, now: Instant) -> Duration {
+        self.refill(now);
+        if self.tokens >= 1.0 {
+            Duration::ZERO
+        } else if self.per_sec <= 0.0 {
+            Duration::MAX
+        } else {
+            Duration::from_secs_f64((1.0 - self.tokens) / self.per_sec)
+        }
+    }
+}
+
+// Limiter state for one server, or one tool of a server.
+struct Gate {
+    limits: CallLimits,
+    bucket: Option<Bucket>,
+    slots: Option<Arc<Semaphore>>,
+}
+
+impl Gate {
+    fn new(limits: &CallLimits) -> Self {
+        Self {
+            limits: limits.clone(),
+            bucket: bucket_for(limits),
+            slots: slot_count(limits).map(|n| Arc::new(Semaphore::new(n))),
+        }
+    }
+
+    // Applies edited limits in place. The semaphore is resized rather than
+    // replaced so calls already in flight still count against the new cap,
+    // and the bucket keeps its tokens unless the rate itself changed.
+    fn update(&mut self, limits: &CallLimits) {
+        if (self.limits.per_minute, self.limits.burst) != (limits.per_minute, limits.burst) {
+            self.bucket = bucket_for(limits);
+        }
+        self.slots = match (
+            self.slots.take(),
+            slot_count(&self.limits),
+            slot_count(limits),
+        ) {
+            (_, _, None) => None,
+            (Some(slots), Some(old), Some(new)) => {
+                resize(&slots, old, new);
+                Some(slots)
+            }
+            (_, _, Some(new)) => Some(Arc::new(Semaphore::new(new))),
+        };
+        self.limits = limits.clone();
+    }
+}
+
+fn bucket_for(limits: &CallLimits) -> Option<Bucket> {
+    limits
+        .per_minute
+        .filter(|n| *n > 0)
+        .map(|n| Bucket::new(n, limits.burst))
+}
+
+fn slot_count(limits: &CallLimits) -> Option<usize> {
+    limits.max_in_flight.map(|n| n.max(1) as usize)
+}
+
+// Permits held by calls in flight cannot be forgotten yet, so a shrink takes
+// the rest back as those calls finish.
+fn resize(slots: &Arc<Semaphore>, old: usize, new: usize) {
+    if new >= old {
+        slots.add_permits(new - old);
+        return;
+    }
+    let excess = old - new;
+    let rest = excess - slots.forget_permits(excess);
+    if rest > 0 {
+        let slots = slots.clone();
+        tokio::spawn(async move {
+            if let Ok(permits) = slots.acquire_many_owned(rest as u32).await {
+                permits.forget();
+            }
+        });
+    }
+}
+
+type GateKey = (String, Option<String>);
+
+#[derive(Default)]
+struct Registry {
+    gates: HashMap<GateKey, Gate>,
+    usage: HashMap<String, CallUsage>,
+}
+
+static REGISTRY: OnceLock<Mutex<Registry>> = OnceLock::new();
+
+fn registry() -> &'static Mutex<Registry> {
+    REGISTRY.get_or_init(|| Mutex::new(Registry::default()))
+}
+
+impl Registry {
+    // Gate for `key`, updated when its limits were edited.
+    fn gate(&mut self, key: &GateKey, limits: &CallLimits) -> &mut Gate {
+        let gate = self
+            .gates
+            .entry(key.clone())
+            .or_insert_with(|| Gate::new(limits));
+        if gate.limits != *limits {
+            gate.update(limits);
+        }
+        gate
+    }
+}
+
+// Held while a call is in flight; releases its slots when dropped.
+pub struct CallPermit {
+    server_name: String,
+    _slots: Vec<OwnedSemaphorePermit>,
+}
+
+impl Drop for CallPermit {
+    fn drop(&mut self) {
+        let mut reg = registry().lock().unwrap();
+        if let Some(usage) = reg.usage.get_mut(&self.server_name) {
+            usage.in_flight = usage.in_flight.saturating_sub(1);
+        }
+    }
+}
+
+pub struct Admission {
+    pub permit: CallPermit,
+    // How long the call queued for capacity, if it had to.
+    pub waited: Option<Duration>,
+}
+
+fn scope(key: &GateKey) -> String {
+    match &key.1 {
+        Some(tool) => format!("tool '{}::{tool}'", key.0),
+        None => format!("server '{}'", key.0),
+    }
+}
+
+// Gives back the rate tokens of a call that was then refused a slot.
+fn refund(gates: &[(GateKey, &CallLimits)]) {
+    let mut reg = registry().lock().unwrap();
+    for (key, limits) in gates {
+        if let Some(bucket) = reg.gate(key, limits).bucket.as_mut() {
+            bucket.tokens = (bucket.tokens + 1.0).min(bucket.capacity);
+        }
+    }
+}
+
+fn record_throttled(server_name: &str) {
+    let mut reg = registry().lock().unwrap();
+    reg.usage
+        .entry(server_name.to_string())
+        .or_default()
+        .throttled += 1;
+}
+
+// Waits for a rate token and an in-flight slot at both the server and tool
+// level, up to the queue timeout. Returns a descriptive error when the call
+// is over a limit and cannot wait any longer.
+pub async fn acquire(
+    server_name: &str,
+    tool_name: &str,
+    server_limits: Option<&CallLimits>,
+    tool_limits: Option<&CallLimits>,
+) -> Result<Admission, String> {
+    let gates: Vec<(GateKey, &CallLimits)> = [
+        ((server_name.to_string(), None), server_limits),
+        (
+            (server_name.to_string(), Some(tool_name.to_string())),
+            tool_limits,
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(key, limits)| Some((key, limits?)))
+    .collect();
+    let started = Instant::now();
+    let queue_timeout = tool_limits
+        .and_then(|l| l.queue_timeout_ms)
+        .or(server_limits.and_then(|l| l.queue_timeout_ms))
+        .map(Duration::from_millis)
+        .unwrap_or(Duration::ZERO);
+    let deadline = started + queue_timeout;
+    let mut queued = false;
+
+    loop {
+        let now = Instant::now();
+        let (wait, blocking) = {
+            let mut reg = registry().lock().unwrap();
+            let mut longest: (Duration, Option<GateKey>) = (Duration::ZERO, None);
+            for (key, limits) in &gates {
+                if let Some(bucket) = reg.gate(key, limits).bucket.as_mut() {
+                    let wait = bucket.wait(now);
+                    if wait > longest.0 {
+                        longest = (wait, Some(key.clone()));
+                    }
+                }
+            }
+            if longest.1.is_none() {
+                for (key, limits) in &gates {
+                    if let Some(bucket) = reg.gate(key, limits).bucket.as_mut() {
+                        bucket.tokens -= 1.0;
+                    }
+                }
+            }
+            longest
+        };
+        let Some(key) = blocking else {
+            break;
+        };
+        if now.checked_add(wait).is_none_or(|ready| ready > deadline) {
+            record_throttled(server_name);
+            let per_minute = gates
+                .iter()
+                .find(|(k, _)| *k == key)
+                .and_then(|(_, l)| l.per_minute)
+                .unwrap_or_default();
+            return Err(format!(
+                "rate limit reached for {}: {per_minute} calls per minute",
+                scope(&key)
+            ));
+        }
+        queued = true;
+        tokio::time::sleep(wait).await;
+    }
+
+    let mut slots = Vec::new();
+    for (key, limits) in &gates {
+        let semaphore = registry().lock().unwrap().gate(key, limits).slots.clone();
+        let Some(semaphore) = semaphore else {
+            continue;
+        };
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        let permit = match semaphore.clone().try_acquire_owned() {
+            Ok(permit) => Ok(permit),
+            Err(_) if remaining.is_zero() => Err(()),
+            Err(_) => {
+                queued = true;
+                tokio::time::timeout(remaining, semaphore.acquire_owned())
+                    .await
+                    .map_err(|_| ())
+                    .and_then(|r| r.map_err(|_| ()))
+            }
+        };
+        match permit {
+            Ok(permit) => slots.push(permit),
+            Err(()) => {
+                refund(&gates);
+                record_throttled(server_name);
+                return Err(format!(
+                    "too many calls in flight for {} (max {})",
+                    scope(key),
+                    limits.max_in_flight.unwrap_or_default()
+                ));
+            }
+        }
+    }
+
+    let waited = queued.then(|| started.elapsed());
+    let mut reg = registry().lock().unwrap();
+    let usage = reg.usage.entry(server_name.to_string()).or_default();
+    usage.in_flight += 1;
+    if waited.is_some() {
+        usage.throttled += 1;
+    }
+    Ok(Admission {
+        permit: CallPermit {
+            server_name: server_name.to_string(),
+            _slots: slots,
+        },
+        waited,
+    })
+}
+
+// Current load on a server for ClientStatus.
+pub fn usage(server_name: &str) -> CallUsage {
+    let mut reg = registry().lock().unwrap();
+    let tokens = reg
+        .gates
+        .get_mut(&(server_name.to_string(), None))
+        .and_then(|g| g.bucket.as_mut())
+        .map(|b| {
+            b.refill(Instant::now());
+            b.tokens.max(0.0) as u32
+        });
+    let mut usage = reg.usage.get(server_name).cloned().unwrap_or_default();
+    usage.tokens_available = tokens;
+    usage
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits(per_minute: Option<u32>, max_in_flight: Option<u32>) -> CallLimits {
+        CallLimits {
+            per_minute,
+            max_in_flight,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn rate_limit_fails_fast_without_queue_timeout() {
+        let server = limits(Some(60), None);
+        let tool = CallLimits {
+            burst: Some(1),
+            ..limits(Some(1), None)
+        };
+        let first = acquire("rl", "a", Some(&server), Some(&tool)).await;
+        assert!(first.is_ok());
+        let err = acquire("rl", "a", Some(&server), Some(&tool))
+            .await
+            .err()
+            .unwrap();
+        assert!(err.contains("tool 'rl::a'"), "{err}");
+        assert!(acquire("rl", "b", Some(&server), None).await.is_ok());
+        let usage = usage("rl");
+        assert_eq!(usage.throttled, 1);
+        assert_eq!(usage.tokens_available, Some(58));
+    }
+
+    #[tokio::test]
+    async fn in_flight_cap_queues_until_a_slot_frees() {
+        let queued = CallLimits {
+            queue_timeout_ms: Some(2_000),
+            ..limits(None, Some(1))
+        };
+        let held = acquire("cap", "t", Some(&queued), None).await.unwrap();
+        assert_eq!(usage("cap").in_flight, 1);
+        let err = acquire("cap", "u", Some(&limits(None, Some(1))), None)
+            .await
+            .err()
+            .unwrap();
+        assert!(err.contains("max 1"), "{err}");
+
+        let waiter = tokio::spawn(async move {
+            acquire("cap", "t", Some(&queued), None)
+                .await
+                .map(|a| a.waited.is_some())
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        drop(held);
+        assert_eq!(waiter.await.unwrap(), Ok(true));
+        assert_eq!(usage("cap").in_flight, 0);
+    }
+
+    #[tokio::test]
+    async fn refused_slot_gives_back_rate_token() {
+        let capped = CallLimits {
+            burst: Some(2),
+            ..limits(Some(60), Some(1))
+        };
+        let _held = acquire("rf", "t", Some(&capped), None).await.unwrap();
+        assert!(acquire("rf", "t", Some(&capped), None).await.is_err());
+        assert_eq!(usage("rf").tokens_available, Some(1));
+    }
+
+    #[tokio::test]
+    async fn editing_limits_keeps_in_flight_calls_counted() {
+        let one = limits(None, Some(1));
+        let two = limits(None, Some(2));
+        let first = acquire("ed", "t", Some(&one), None).await.unwrap();
+        let second = acquire("ed", "t", Some(&two), None).await.unwrap();
+        assert!(acquire("ed", "t", Some(&two), None).await.is_err());
+
+        // Shrinking below the calls in flight waits for them to finish.
+        assert!(acquire("ed", "t", Some(&one), None).await.is_err());
+        drop(first);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(acquire("ed", "t", Some(&one), None).await.is_err());
+        drop(second);
+        assert!(acquire("ed", "t", Some(&one), None).await.is_ok());
+    }
+}
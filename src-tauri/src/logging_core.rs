@@ -29,6 +29,12 @@ pub struct Event {
     pub client_version: Option<String>,
     pub client_protocol: Option<String>,
     pub origin: Option<String>,
+    // Event this one was derived from (e.g. relayed progress for a tools/call)
+    pub parent_id: Option<Uuid>,
+    // Client policy that applied to this request, if any
+    pub policy: Option<String>,
+    // Label of the API token the request authenticated with
+    pub identity: Option<String>,
 }
 
 impl Event {
@@ -50,6 +56,9 @@ impl Event {
             client_version: None,
             client_protocol: None,
             origin: None,
+            parent_id: None,
+            policy: None,
+            identity: None,
         }
     }
 }
@@ -17,6 +17,9 @@ const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(1);
 
 const MIGRATION_SQL: &str = include_str!("sql/migrations/0001_logging_init.sql");
 const MIGRATION_ADD_ORIGIN: &str = include_str!("sql/migrations/0002_logging_add_origin.sql");
+const MIGRATION_ADD_PARENT_ID: &str = include_str!("sql/migrations/0003_logging_add_parent_id.sql");
+const MIGRATION_ADD_POLICY: &str = include_str!("sql/migrations/0004_logging_add_policy.sql");
+const MIGRATION_ADD_IDENTITY: &str = include_str!("sql/migrations/0005_logging_add_identity.sql");
 
 #[derive(Clone)]
 pub struct LoggerCfg {
@@ -234,7 +237,7 @@ async fn ensure_schema(conn: &mut SqliteConnection) -> Result<(), sqlx::Error> {
     for stmt in migration_statements() {
         if stmt.starts_with("ALTER TABLE") {
             match sqlx::query(stmt).execute(&mut *conn).await {
-                Err(e) if stmt.contains("origin") && is_duplicate_column_error(&e) => {}
+                Err(e) if is_duplicate_column_error(&e) => {}
                 Err(e) => return Err(e),
                 Ok(_) => {}
             }
@@ -254,12 +257,13 @@ async fn flush_events(conn: &mut SqliteConnection, events: &[Event]) -> Result<(
         let created_at_ms = event.ts_ms;
         let last_seen_ms = event.ts_ms;
         sqlx::query(
-            "INSERT INTO sessions (session_id, created_at_ms, client_name, client_version, client_protocol, last_seen_at_ms)
-             VALUES (?, ?, ?, ?, ?, ?)
+            "INSERT INTO sessions (session_id, created_at_ms, client_name, client_version, client_protocol, identity, last_seen_at_ms)
+             VALUES (?, ?, ?, ?, ?, ?, ?)
              ON CONFLICT(session_id) DO UPDATE SET
-                 client_name = excluded.client_name,
-                 client_version = excluded.client_version,
-                 client_protocol = excluded.client_protocol,
+                 client_name = COALESCE(excluded.client_name, sessions.client_name),
+                 client_version = COALESCE(excluded.client_version, sessions.client_version),
+                 client_protocol = COALESCE(excluded.client_protocol, sessions.client_protocol),
+                 identity = COALESCE(excluded.identity, sessions.identity),
                  last_seen_at_ms = excluded.last_seen_at_ms",
         )
         .bind(&event.session_id)
@@ -267,6 +271,7 @@ async fn flush_events(conn: &mut SqliteConnection, events: &[Event]) -> Result<(
         .bind(event.client_name.as_deref())
         .bind(event.client_version.as_deref())
         .bind(event.client_protocol.as_deref())
+        .bind(event.identity.as_deref())
         .bind(last_seen_ms)
         .execute(&mut *tx)
         .await?;
@@ -281,8 +286,8 @@ async fn flush_events(conn: &mut SqliteConnection, events: &[Event]) -> Result<(
             .map(|v| serde_json::to_string(v).unwrap_or_default());
 
         sqlx::query(
-            "INSERT INTO rpc_events (id, ts_ms, session_id, method, server_name, server_version, server_protocol, duration_ms, ok, error, origin, request_json, response_json)
-             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            "INSERT INTO rpc_events (id, ts_ms, session_id, method, server_name, server_version, server_protocol, duration_ms, ok, error, origin, parent_id, policy, identity, request_json, response_json)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
         )
         .bind(event.id.to_string())
         .bind(event.ts_ms)
@@ -295,6 +300,9 @@ async fn flush_events(conn: &mut SqliteConnection, events: &[Event]) -> Result<(
         .bind(event.ok)
         .bind(event.error.as_deref())
         .bind(event.origin.as_deref())
+        .bind(event.parent_id.map(|id| id.to_string()))
+        .bind(event.policy.as_deref())
+        .bind(event.identity.as_deref())
         .bind(request_json.as_deref())
         .bind(response_json.as_deref())
         .execute(&mut *tx)
@@ -353,6 +361,9 @@ fn migration_statements() -> impl Iterator<Item = &'static str> {
     MIGRATION_SQL
         .split(';')
         .chain(MIGRATION_ADD_ORIGIN.split(';'))
+        .chain(MIGRATION_ADD_PARENT_ID.split(';'))
+        .chain(MIGRATION_ADD_POLICY.split(';'))
+        .chain(MIGRATION_ADD_IDENTITY.split(';'))
         .map(str::trim)
         .filter(|stmt| !stmt.is_empty())
 }
@@ -378,6 +389,24 @@ pub fn migrations() -> Vec<Migration> {
             sql: MIGRATION_ADD_ORIGIN,
             kind: MigrationKind::Up,
         },
+        Migration {
+            version: 3,
+            description: "add_parent_id_column",
+            sql: MIGRATION_ADD_PARENT_ID,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 4,
+            description: "add_policy_column",
+            sql: MIGRATION_ADD_POLICY,
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 5,
+            description: "add_identity_columns",
+            sql: MIGRATION_ADD_IDENTITY,
+            kind: MigrationKind::Up,
+        },
     ]
 }
 
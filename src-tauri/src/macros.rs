@@ -0,0 +1,171 @@
+use std::future::Future;
+
+use rmcp::model::{CallToolResult, Content, JsonObject, Tool};
+use serde_json::{Value, json};
+
+use crate::config::MacroTool;
+use crate::transform::{lookup_env, render};
+
+// Why a macro cannot be exposed, if anything.
+pub fn validate(def: &MacroTool) -> Result<(), String> {
+    if !crate::config::is_valid_tool_name(&def.name) {
+        return Err(format!(
+            "invalid macro name '{}': use letters, digits, '_' or '-'",
+            def.name
+        ));
+    }
+    if def.steps.is_empty() {
+        return Err(format!("macro '{}' has no steps", def.name));
+    }
+    Ok(())
+}
+
+pub fn to_tool(def: &MacroTool) -> Tool {
+    let schema = def
+        .input_schema
+        .as_ref()
+        .and_then(|s| s.as_object().cloned())
+        .unwrap_or_else(|| {
+            json!({ "type": "object" })
+                .as_object()
+                .cloned()
+                .unwrap_or_default()
+        });
+    Tool::new(def.name.clone(), def.description.clone(), schema)
+}
+
+// Resolves a dotted placeholder against the macro state, e.g. `input.repo` or
+// `steps.0.json.number`.
+fn lookup(state: &Value, key: &str) -> Result<Value, String> {
+    if key.starts_with("env.") {
+        return lookup_env(key);
+    }
+    let mut cur = state;
+    for seg in key.split('.') {
+        let next = match cur {
+            Value::Object(map) => map.get(seg),
+            Value::Array(items) => seg.parse::<usize>().ok().and_then(|i| items.get(i)),
+            _ => None,
+        };
+        cur = next.ok_or_else(|| format!("unknown placeholder {{{{{key}}}}}"))?;
+    }
+    Ok(cur.clone())
+}
+
+// What later steps can reference from a finished step.
+fn step_output(result: &CallToolResult) -> Value {
+    let text = result
+        .content
+        .iter()
+        .filter_map(|c| c.as_text().map(|t| t.text.as_str()))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let parsed = serde_json::from_str::<Value>(&text).unwrap_or(Value::Null);
+    json!({
+        "text": text,
+        "json": parsed,
+        "structured": result.structured_content.clone().unwrap_or(Value::Null),
+    })
+}
+
+fn failure(def: &MacroTool, index: usize, msg: impl std::fmt::Display) -> CallToolResult {
+    CallToolResult::error(vec![Content::text(format!(
+        "macro '{}' step {} failed: {msg}",
+        def.name,
+        index + 1
+    ))])
+}
+
+// Sends one step upstream. The server implements this so a step passes the
+// same gates as a direct tools/call from the macro's caller.
+pub trait StepRunner {
+    fn call(
+        &self,
+        server: &str,
+        tool: &str,
+        arguments: JsonObject,
+    ) -> impl Future<Output = CallToolResult> + Send;
+}
+
+// Runs every step in order and returns the last step's result. The first
+// failing step stops the macro.
+pub async fn run<R: StepRunner + Sync>(
+    def: &MacroTool,
+    input: Option<JsonObject>,
+    runner: &R,
+) -> CallToolResult {
+    let mut state = json!({ "input": input.unwrap_or_default(), "steps": [] });
+    let mut last = CallToolResult::success(vec![]);
+    for (index, step) in def.steps.iter().enumerate() {
+        let arguments = step
+            .arguments
+            .iter()
+            .map(|(k, v)| Ok((k.clone(), render(v, &|key| lookup(&state, key))?)))
+            .collect::<Result<JsonObject, String>>();
+        let arguments = match arguments {
+            Ok(args) => args,
+            Err(e) => return failure(def, index, e),
+        };
+        let result = runner.call(&step.server, &step.tool, arguments).await;
+        if result.is_error == Some(true) {
+            let text = step_output(&result)["text"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string();
+            return failure(def, index, text);
+        }
+        if let Some(steps) = state["steps"].as_array_mut() {
+            steps.push(step_output(&result));
+        }
+        last = result;
+    }
+    last
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn placeholders_read_input_and_earlier_steps() {
+        let state = json!({
+            "input": { "repo": "acme/app" },
+            "steps": [{ "text": "{\"number\":7}", "json": { "number": 7 } }]
+        });
+        let args = json!({
+            "repo": "{{input.repo}}",
+            "branch": "issue-{{steps.0.json.number}}",
+            "issue": "{{steps.0.json.number}}"
+        });
+        let out = render(&args, &|key| lookup(&state, key)).unwrap();
+        assert_eq!(
+            out,
+            json!({ "repo": "acme/app", "branch": "issue-7", "issue": 7 })
+        );
+        assert!(render(&json!("{{steps.3.text}}"), &|key| lookup(&state, key)).is_err());
+    }
+
+    #[test]
+    fn step_output_parses_json_text() {
+        let res = CallToolResult::success(vec![Content::text("{\"id\":1}")]);
+        let out = step_output(&res);
+        assert_eq!(out["json"]["id"], 1);
+        assert_eq!(out["structured"], Value::Null);
+    }
+
+    #[test]
+    fn rejects_invalid_macros() {
+        let def = MacroTool {
+            name: "bad name".into(),
+            description: String::new(),
+            input_schema: None,
+            steps: vec![],
+        };
+        assert!(validate(&def).is_err());
+        let def = MacroTool {
+            name: "ok".into(),
+            ..def
+        };
+        assert!(validate(&def).unwrap_err().contains("no steps"));
+    }
+}
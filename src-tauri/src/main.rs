@@ -109,9 +109,19 @@ fn main() {
             commands::mcp_refresh_client_tools,
             commands::mcp_debug_call_tool,
             commands::mcp_toggle_tool,
+            commands::mcp_set_tool_override,
+            commands::mcp_toggle_prompt,
+            commands::mcp_list_api_tokens,
+            commands::mcp_create_api_token,
+            commands::mcp_revoke_api_token,
+            commands::mcp_list_secrets,
+            commands::mcp_set_secret,
+            commands::mcp_delete_secret,
+            commands::mcp_resolve_approval,
             commands::settings_get_settings,
             commands::settings_open_config_directory,
-            commands::settings_update_settings
+            commands::settings_update_settings,
+            commands::settings_validate_settings
         ]);
 
         let export_result = builder.export(Typescript::default(), "../src/tauri/bindings.ts");
@@ -188,9 +198,19 @@ fn main() {
             commands::mcp_refresh_client_tools,
             commands::mcp_debug_call_tool,
             commands::mcp_toggle_tool,
+            commands::mcp_set_tool_override,
+            commands::mcp_toggle_prompt,
+            commands::mcp_list_api_tokens,
+            commands::mcp_create_api_token,
+            commands::mcp_revoke_api_token,
+            commands::mcp_list_secrets,
+            commands::mcp_set_secret,
+            commands::mcp_delete_secret,
+            commands::mcp_resolve_approval,
             commands::settings_get_settings,
             commands::settings_open_config_directory,
-            commands::settings_update_settings
+            commands::settings_update_settings,
+            commands::settings_validate_settings
         ])
         .manage(runtime_state)
         .run(tauri::generate_context!());
@@ -0,0 +1,174 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use crate::config::ConfigProvider;
+use crate::secrets::{KeyringSecretStore, SecretKey, SecretNamespace, SecretStore};
+
+// Metadata for a named secret; the value only lives in the keyring.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Type)]
+pub struct NamedSecretInfo {
+    pub name: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SecretsFile(BTreeMap<String, NamedSecretInfo>);
+
+fn secrets_path(cp: &dyn ConfigProvider) -> PathBuf {
+    cp.base_dir().join("named_secrets.json")
+}
+
+fn secret_key(name: &str) -> SecretKey<'_> {
+    SecretKey {
+        namespace: SecretNamespace::NamedSecret,
+        identifier: name,
+    }
+}
+
+pub(crate) fn os_secret_store() -> &'static KeyringSecretStore {
+    static STORE: OnceLock<KeyringSecretStore> = OnceLock::new();
+    STORE.get_or_init(KeyringSecretStore::default)
+}
+
+fn load_file(cp: &dyn ConfigProvider) -> SecretsFile {
+    fs::read_to_string(secrets_path(cp))
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+fn save_file(cp: &dyn ConfigProvider, file: &SecretsFile) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(file).map_err(|e| format!("to json: {e}"))?;
+    fs::create_dir_all(cp.base_dir()).map_err(|e| format!("create dir: {e}"))?;
+    fs::write(secrets_path(cp), content).map_err(|e| format!("write named secrets: {e}"))
+}
+
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+}
+
+pub fn list_secrets(cp: &dyn ConfigProvider) -> Vec<NamedSecretInfo> {
+    load_file(cp).0.into_values().collect()
+}
+
+// Creates the secret, or replaces the value of an existing one.
+pub fn set_secret(
+    cp: &dyn ConfigProvider,
+    name: &str,
+    value: &str,
+) -> Result<NamedSecretInfo, String> {
+    set_secret_with_store(cp, os_secret_store(), name, value)
+}
+
+pub fn set_secret_with_store(
+    cp: &dyn ConfigProvider,
+    secret_store: &dyn SecretStore,
+    name: &str,
+    value: &str,
+) -> Result<NamedSecretInfo, String> {
+    let name = name.trim();
+    if !is_valid_name(name) {
+        return Err(format!(
+            "invalid secret name '{name}': use letters, digits, '_', '-' or '.'"
+        ));
+    }
+    if value.is_empty() {
+        return Err("secret value must not be empty".into());
+    }
+    secret_store
+        .set(&secret_key(name), value)
+        .map_err(|e| format!("store secret in keyring: {e}"))?;
+    let info = NamedSecretInfo {
+        name: name.to_string(),
+        updated_at: chrono::Utc::now().to_rfc3339(),
+    };
+    let mut file = load_file(cp);
+    file.0.insert(name.to_string(), info.clone());
+    save_file(cp, &file)?;
+    Ok(info)
+}
+
+pub fn delete_secret(cp: &dyn ConfigProvider, name: &str) -> Result<(), String> {
+    delete_secret_with_store(cp, os_secret_store(), name)
+}
+
+pub fn delete_secret_with_store(
+    cp: &dyn ConfigProvider,
+    secret_store: &dyn SecretStore,
+    name: &str,
+) -> Result<(), String> {
+    let mut file = load_file(cp);
+    if file.0.remove(name).is_none() {
+        return Err(format!("secret '{name}' not found"));
+    }
+    secret_store
+        .delete(&secret_key(name))
+        .map_err(|e| format!("delete secret from keyring: {e}"))?;
+    save_file(cp, &file)
+}
+
+// Stored value of the secret `name`; a missing secret is an error.
+pub fn lookup(name: &str, secret_store: &dyn SecretStore) -> Result<String, String> {
+    secret_store
+        .get(&secret_key(name))
+        .map_err(|e| format!("read secret '{name}' from keyring: {e}"))?
+        .ok_or_else(|| format!("secret '{name}' is not set"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::secrets::MemorySecretStore;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    struct TempProvider(PathBuf);
+
+    impl TempProvider {
+        fn new() -> Self {
+            let dir = std::env::temp_dir().join(format!(
+                "mcp-bouncer-named-secrets-{}-{}",
+                std::process::id(),
+                SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap()
+                    .as_nanos()
+            ));
+            fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+    }
+
+    impl ConfigProvider for TempProvider {
+        fn base_dir(&self) -> PathBuf {
+            self.0.clone()
+        }
+    }
+
+    #[test]
+    fn secrets_are_listed_without_values() {
+        let cp = TempProvider::new();
+        let store = MemorySecretStore::new();
+        set_secret_with_store(&cp, &store, "GITHUB_TOKEN", "ghp_123").unwrap();
+        assert!(set_secret_with_store(&cp, &store, "bad name", "x").is_err());
+
+        let raw = fs::read_to_string(secrets_path(&cp)).unwrap();
+        assert!(
+            !raw.contains("ghp_123"),
+            "value must not be written to disk"
+        );
+        let names: Vec<String> = list_secrets(&cp).into_iter().map(|s| s.name).collect();
+        assert_eq!(names, vec!["GITHUB_TOKEN"]);
+
+        delete_secret_with_store(&cp, &store, "GITHUB_TOKEN").unwrap();
+        assert!(list_secrets(&cp).is_empty());
+        assert!(delete_secret_with_store(&cp, &store, "GITHUB_TOKEN").is_err());
+    }
+}
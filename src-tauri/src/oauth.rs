@@ -7,7 +7,7 @@ use axum::response::Html;
 use axum::{Router, extract::Query, http::StatusCode, routing::get};
 use rmcp::transport::auth::{OAuthState, OAuthTokenResponse};
 
-use crate::client::ensure_rmcp_client;
+use crate::client::{ensure_rmcp_client, list_all_upstream_tools};
 use crate::config::{ClientConnectionState, ConfigProvider, OsConfigProvider, load_settings_with};
 use crate::events::{EventEmitter, client_error, client_status_changed};
 use crate::logging::RpcEventPublisher;
@@ -537,17 +537,11 @@ where
             overlay::set_state(name, ClientConnectionState::Connecting).await;
             client_status_changed(emitter, name, "connecting");
             match ensure_rmcp_client(name, &cfg, emitter, logger).await {
-                Ok(client) => match client.list_all_tools().await {
+                Ok(client) => match list_all_upstream_tools(&client).await {
                     Ok(tools) => {
                         // cache tools list and update count
-                        let mapped: Vec<crate::types::ToolInfo> = tools
-                            .iter()
-                            .map(|t| crate::types::ToolInfo {
-                                name: t.name.to_string(),
-                                description: t.description.clone().map(|s| s.to_string()),
-                                input_schema: None,
-                            })
-                            .collect();
+                        let mapped: Vec<crate::types::ToolInfo> =
+                            tools.iter().map(crate::types::ToolInfo::from).collect();
                         crate::tools_cache::set(name, mapped.clone()).await;
                         overlay::set_error(name, None).await;
                         overlay::set_state(name, ClientConnectionState::Connected).await;
@@ -0,0 +1,78 @@
+// Host and Origin checks for the TCP listener. A page on another site can
+// resolve its own hostname to 127.0.0.1 (DNS rebinding), so requests are only
+// served when both headers name this machine or an explicitly allowed entry.
+
+const LOCAL_HOSTS: [&str; 3] = ["localhost", "127.0.0.1", "[::1]"];
+
+// Hostname part of a Host header value or URL authority, lowercased.
+fn hostname(authority: &str) -> String {
+    let authority = authority.trim().to_ascii_lowercase();
+    if authority.starts_with('[') {
+        return match authority.find(']') {
+            Some(end) => authority[..=end].to_string(),
+            None => authority,
+        };
+    }
+    match authority.rsplit_once(':') {
+        Some((host, port)) if port.chars().all(|c| c.is_ascii_digit()) => host.to_string(),
+        _ => authority,
+    }
+}
+
+// `extra` entries match the hostname (any port) or the full `host:port`.
+pub fn host_allowed(host: &str, extra: &[String]) -> bool {
+    let name = hostname(host);
+    let full = host.trim().to_ascii_lowercase();
+    LOCAL_HOSTS.contains(&name.as_str())
+        || extra.iter().any(|e| {
+            let e = e.to_ascii_lowercase();
+            e == name || e == full
+        })
+}
+
+// Requests without an Origin header come from non-browser clients and pass.
+// `extra` entries are full origins such as `https://app.example.com`.
+pub fn origin_allowed(origin: Option<&str>, extra: &[String]) -> bool {
+    let Some(origin) = origin else {
+        return true;
+    };
+    let origin = origin.trim().trim_end_matches('/').to_ascii_lowercase();
+    if extra
+        .iter()
+        .any(|e| e.trim_end_matches('/').to_ascii_lowercase() == origin)
+    {
+        return true;
+    }
+    let authority = origin
+        .strip_prefix("http://")
+        .or_else(|| origin.strip_prefix("https://"));
+    authority.is_some_and(|a| LOCAL_HOSTS.contains(&hostname(a).as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hosts_default_to_localhost_forms() {
+        assert!(host_allowed("127.0.0.1:8091", &[]));
+        assert!(host_allowed("localhost", &[]));
+        assert!(host_allowed("[::1]:8091", &[]));
+        assert!(!host_allowed("evil.example:8091", &[]));
+        assert!(!host_allowed("127.0.0.1.evil.example", &[]));
+        let extra = vec!["devbox.lan".to_string()];
+        assert!(host_allowed("devbox.lan:8091", &extra));
+    }
+
+    #[test]
+    fn origins_must_be_local_or_listed() {
+        assert!(origin_allowed(None, &[]));
+        assert!(origin_allowed(Some("http://localhost:5173"), &[]));
+        assert!(origin_allowed(Some("http://127.0.0.1"), &[]));
+        assert!(!origin_allowed(Some("https://evil.example"), &[]));
+        assert!(!origin_allowed(Some("null"), &[]));
+        let extra = vec!["https://app.example.com".to_string()];
+        assert!(origin_allowed(Some("https://app.example.com"), &extra));
+        assert!(!origin_allowed(Some("https://app.example.com:444"), &extra));
+    }
+}
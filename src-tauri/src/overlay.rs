@@ -9,6 +9,7 @@ pub struct OverlayEntry {
     pub authorization_required: bool,
     pub oauth_authenticated: bool,
     pub tools: u32,
+    pub name_conflicts: Vec<String>,
 }
 
 static OVERLAY: std::sync::OnceLock<tokio::sync::Mutex<HashMap<String, OverlayEntry>>> =
@@ -25,6 +26,7 @@ fn default_entry() -> OverlayEntry {
         authorization_required: false,
         oauth_authenticated: false,
         tools: 0,
+        name_conflicts: Vec::new(),
     }
 }
 
@@ -62,6 +64,17 @@ pub async fn set_tools(name: &str, tools: u32) {
     e.tools = tools;
 }
 
+// Returns true when the recorded conflicts changed.
+pub async fn set_name_conflicts(name: &str, conflicts: Vec<String>) -> bool {
+    let mut g = overlay_map().lock().await;
+    let e = entry_mut(&mut g, name);
+    if e.name_conflicts == conflicts {
+        return false;
+    }
+    e.name_conflicts = conflicts;
+    true
+}
+
 pub async fn clear_all() {
     let mut g = overlay_map().lock().await;
     g.clear();
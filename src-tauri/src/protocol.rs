@@ -0,0 +1,117 @@
+use std::collections::HashSet;
+
+use rmcp::model::ProtocolVersion;
+use rmcp::{Peer, RoleServer};
+
+// Protocol versions the bouncer speaks, oldest first.
+pub const SUPPORTED: [ProtocolVersion; 3] = [
+    ProtocolVersion::V_2024_11_05,
+    ProtocolVersion::V_2025_03_26,
+    ProtocolVersion::V_2025_06_18,
+];
+
+pub fn latest() -> ProtocolVersion {
+    SUPPORTED[SUPPORTED.len() - 1].clone()
+}
+
+// Highest supported version not newer than the one the client asked for. A
+// client older than everything we support is offered our latest, per spec.
+pub fn negotiate(requested: &ProtocolVersion) -> ProtocolVersion {
+    SUPPORTED
+        .iter()
+        .rev()
+        .find(|v| *v <= requested)
+        .cloned()
+        .unwrap_or_else(latest)
+}
+
+// Version agreed with a downstream session, derived from its initialize request.
+pub fn for_session(peer: &Peer<RoleServer>) -> ProtocolVersion {
+    peer.peer_info()
+        .map(|info| negotiate(&info.protocol_version))
+        .unwrap_or_else(latest)
+}
+
+// Structured tool output (outputSchema / structuredContent) arrived in 2025-06-18.
+pub fn supports_structured_content(version: &ProtocolVersion) -> bool {
+    *version >= ProtocolVersion::V_2025_06_18
+}
+
+// Server-initiated elicitation arrived in 2025-06-18.
+pub fn supports_elicitation(version: &ProtocolVersion) -> bool {
+    *version >= ProtocolVersion::V_2025_06_18
+}
+
+static WARNED: std::sync::Mutex<Option<HashSet<(String, String, String)>>> =
+    std::sync::Mutex::new(None);
+
+// Logs once per (server, upstream version, session version) when an upstream
+// speaks an older protocol than the downstream session that is calling it.
+pub fn warn_if_upstream_older(
+    server_name: &str,
+    upstream: &ProtocolVersion,
+    session: &ProtocolVersion,
+) -> bool {
+    if upstream >= session {
+        return false;
+    }
+    let key = (
+        server_name.to_string(),
+        upstream.to_string(),
+        session.to_string(),
+    );
+    let first = WARNED
+        .lock()
+        .unwrap()
+        .get_or_insert_with(HashSet::new)
+        .insert(key);
+    if first {
+        tracing::warn!(
+            target = "server",
+            server = %server_name,
+            upstream = %upstream,
+            session = %session,
+            "upstream_protocol_older_than_session"
+        );
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn negotiates_highest_common_version() {
+        assert_eq!(
+            negotiate(&ProtocolVersion::V_2025_03_26),
+            ProtocolVersion::V_2025_03_26
+        );
+        assert_eq!(
+            negotiate(&ProtocolVersion::V_2025_06_18),
+            ProtocolVersion::V_2025_06_18
+        );
+        let future: ProtocolVersion = serde_json::from_str("\"2099-01-01\"").unwrap();
+        assert_eq!(negotiate(&future), latest());
+        let between: ProtocolVersion = serde_json::from_str("\"2025-05-01\"").unwrap();
+        assert_eq!(negotiate(&between), ProtocolVersion::V_2025_03_26);
+        let ancient: ProtocolVersion = serde_json::from_str("\"2023-01-01\"").unwrap();
+        assert_eq!(negotiate(&ancient), latest());
+    }
+
+    #[test]
+    fn features_follow_version() {
+        assert!(!supports_structured_content(&ProtocolVersion::V_2025_03_26));
+        assert!(supports_structured_content(&ProtocolVersion::V_2025_06_18));
+        assert!(!supports_elicitation(&ProtocolVersion::V_2024_11_05));
+    }
+
+    #[test]
+    fn warns_only_for_older_upstreams() {
+        let old = ProtocolVersion::V_2024_11_05;
+        let new = ProtocolVersion::V_2025_06_18;
+        assert!(warn_if_upstream_older("srv", &old, &new));
+        assert!(!warn_if_upstream_older("srv", &new, &old));
+        assert!(!warn_if_upstream_older("srv", &new, &new));
+    }
+}
@@ -1,8 +1,6 @@
 use std::fmt;
 
-#[cfg(test)]
 use std::collections::HashMap;
-#[cfg(test)]
 use std::sync::Mutex;
 
 use keyring::{Entry, Error as KeyringError};
@@ -14,6 +12,7 @@ use keyring::{Entry, Error as KeyringError};
 pub enum SecretNamespace {
     OAuthToken,
     NamedSecret,
+    ApiToken,
 }
 
 impl SecretNamespace {
@@ -21,6 +20,7 @@ impl SecretNamespace {
         match self {
             SecretNamespace::OAuthToken => "oauth",
             SecretNamespace::NamedSecret => "named",
+            SecretNamespace::ApiToken => "api-token",
         }
     }
 }
@@ -117,20 +117,17 @@ impl SecretStore for KeyringSecretStore {
 }
 
 /// Simple in-memory store for tests so we do not touch the real OS keychain.
-#[cfg(test)]
 #[derive(Default)]
 pub struct MemorySecretStore {
     secrets: Mutex<HashMap<String, String>>,
 }
 
-#[cfg(test)]
 impl MemorySecretStore {
     pub fn new() -> Self {
         Self::default()
     }
 }
 
-#[cfg(test)]
 impl SecretStore for MemorySecretStore {
     fn set(&self, key: &SecretKey<'_>, value: &str) -> Result<(), SecretStoreError> {
         let mut guard = self.secrets.lock().unwrap();
@@ -5,7 +5,7 @@ use rmcp::transport::streamable_http_server::{
     StreamableHttpServerConfig, StreamableHttpService, session::local::LocalSessionManager,
 };
 use rmcp::{RoleServer, Service as McpService};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
@@ -15,13 +15,20 @@ use {
     tokio::net::UnixListener,
 };
 
-use crate::client::{apply_log_context_from_client, ensure_rmcp_client, fetch_tools_for_cfg};
-use crate::config::{ConfigProvider, MCPServerConfig, load_settings_with};
+use crate::client::{
+    apply_log_context_from_client, ensure_rmcp_client, fetch_prompts_for_cfg,
+    fetch_resource_templates_for_cfg, fetch_resources_for_cfg, fetch_tools_for_cfg,
+    set_level_for_cfg,
+};
+use crate::config::{
+    ClientPolicy, ConfigProvider, MCPServerConfig, ToolApproval, ToolOverride, load_settings_with,
+};
 use crate::events::EventEmitter;
 use crate::events::client_status_changed;
 use crate::logging::RpcEventPublisher;
 use crate::oauth;
 use crate::transport::intercepting::{InterceptingSessionManager, RequestLogContext};
+use crate::types::ToolInfo;
 
 // Runtime-bound listen address storage
 static RUNTIME_ADDR: std::sync::OnceLock<std::net::SocketAddr> = std::sync::OnceLock::new();
@@ -45,6 +52,168 @@ where
     pub cp: CP,
     logger: L,
     tool_aliases: Arc<RwLock<HashMap<String, (String, String)>>>,
+    resource_aliases: Arc<RwLock<HashMap<String, (String, String)>>>,
+    prompt_aliases: Arc<RwLock<HashMap<String, (String, String)>>>,
+    tool_snapshots: Arc<RwLock<VecDeque<ToolsSnapshot>>>,
+    // Alias map of the last tools/list this session was served. Its names keep
+    // resolving to what that listing meant after the shared map is rebuilt.
+    listed_aliases: Arc<RwLock<Option<ToolAliases>>>,
+    // Profile whose endpoint this service is mounted on; None for plain /mcp.
+    profile: Option<String>,
+    // Distinct per downstream session; a service instance is built per session.
+    session: u64,
+}
+
+static NEXT_SESSION: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(1);
+
+// What a service may expose, resolved from its profile on every request so
+// settings edits apply without a restart.
+#[derive(Clone)]
+enum Scope {
+    Everything,
+    Profile(crate::config::Profile),
+    // The profile was removed while a session was still open.
+    Nothing,
+}
+
+impl Scope {
+    fn allows_server(&self, server_name: &str) -> bool {
+        match self {
+            Scope::Everything => true,
+            Scope::Profile(p) => p.allows_server(server_name),
+            Scope::Nothing => false,
+        }
+    }
+
+    fn allows_tool(&self, server_name: &str, tool_name: &str) -> bool {
+        match self {
+            Scope::Everything => true,
+            Scope::Profile(p) => p.allows_tool(server_name, tool_name),
+            Scope::Nothing => false,
+        }
+    }
+
+    // A macro is only exposed when every tool it calls is in scope too.
+    fn allows_macro(&self, def: &crate::config::MacroTool) -> bool {
+        let allowed = match self {
+            Scope::Everything => true,
+            Scope::Profile(p) => p.allows_macro(&def.name),
+            Scope::Nothing => false,
+        };
+        allowed
+            && def.steps.iter().all(|step| {
+                self.allows_server(&step.server) && self.allows_tool(&step.server, &step.tool)
+            })
+    }
+}
+
+// One tools/call on its way upstream, made directly or as a macro step.
+struct ToolCall<'a> {
+    server_name: String,
+    tool_name: String,
+    // Name the caller used; error messages quote it.
+    label: &'a str,
+    arguments: Option<mcp::JsonObject>,
+    // Macro steps are logged as children of the macro call instead of filling
+    // in the request's own log entry.
+    macro_step: bool,
+}
+
+enum GatedCall {
+    // A gate refused the call; nothing was sent upstream.
+    Rejected(mcp::CallToolResult),
+    // The upstream's answer, or the error from trying to reach it.
+    Upstream(mcp::CallToolResult),
+}
+
+// Runs a macro's steps through the call gates on behalf of the macro's caller.
+struct MacroSteps<'a, E, CP, L>
+where
+    E: EventEmitter + Clone + Send + Sync + 'static,
+    CP: ConfigProvider + Clone + Send + Sync + 'static,
+    L: RpcEventPublisher,
+{
+    service: &'a BouncerService<E, CP, L>,
+    policy: Option<&'a ClientPolicy>,
+    context: &'a rmcp::service::RequestContext<RoleServer>,
+    log_ctx: Option<&'a RequestLogContext<E, L>>,
+}
+
+impl<E, CP, L> crate::macros::StepRunner for MacroSteps<'_, E, CP, L>
+where
+    E: EventEmitter + Clone + Send + Sync + 'static,
+    CP: ConfigProvider + Clone + Send + Sync + 'static,
+    L: RpcEventPublisher,
+{
+    // Each step is logged as a child of the macro call.
+    async fn call(
+        &self,
+        server: &str,
+        tool: &str,
+        arguments: mcp::JsonObject,
+    ) -> mcp::CallToolResult {
+        let cp = &self.service.cp;
+        let enabled = load_settings_with(cp)
+            .mcp_servers
+            .iter()
+            .any(|c| c.name == server && c.enabled);
+        if !enabled {
+            return mcp::CallToolResult::error(vec![mcp::Content::text(format!(
+                "server not available: {server}"
+            ))]);
+        }
+        if !crate::config::is_tool_enabled_with(cp, server, tool) {
+            return mcp::CallToolResult::error(vec![mcp::Content::text(format!(
+                "tool disabled: {tool}"
+            ))]);
+        }
+        let label = format!("{server}::{tool}");
+        let request_json = serde_json::to_value(mcp::CallToolRequestParam {
+            name: tool.to_string().into(),
+            arguments: Some(arguments.clone()),
+        })
+        .ok();
+        let started = std::time::Instant::now();
+        let call = ToolCall {
+            server_name: server.to_string(),
+            tool_name: tool.to_string(),
+            label: &label,
+            arguments: Some(arguments),
+            macro_step: true,
+        };
+        let result = match self
+            .service
+            .call_through_gates(call, self.policy, self.context, self.log_ctx)
+            .await
+        {
+            GatedCall::Upstream(result) | GatedCall::Rejected(result) => result,
+        };
+        if let Some(ctx) = self.log_ctx {
+            let error =
+                (result.is_error == Some(true)).then(|| "tool returned an error".to_string());
+            ctx.log_linked_call(
+                "tools/call",
+                Some(server.to_string()),
+                request_json,
+                serde_json::to_value(&result).ok(),
+                error,
+                started.elapsed().as_millis() as i64,
+            )
+            .await;
+        }
+        result
+    }
+}
+
+type ToolAliases = Arc<HashMap<String, (String, String)>>;
+
+// Full tools/list result and the alias map it was built with, captured when a
+// listing starts.
+#[derive(Clone)]
+struct ToolsSnapshot {
+    id: String,
+    tools: Arc<Vec<mcp::Tool>>,
+    aliases: ToolAliases,
 }
 
 impl<E, CP, L> McpService<RoleServer> for BouncerService<E, CP, L>
@@ -60,29 +229,53 @@ where
     ) -> Result<mcp::ServerResult, mcp::ErrorData> {
         let log_ctx = context.extensions.get::<RequestLogContext<E, L>>().cloned();
         match request {
-            mcp::ClientRequest::InitializeRequest(_req) => self.respond_initialize(log_ctx).await,
-            mcp::ClientRequest::ListToolsRequest(_req) => self.respond_list_tools(log_ctx).await,
-            mcp::ClientRequest::CallToolRequest(req) => self.respond_call_tool(req, log_ctx).await,
+            mcp::ClientRequest::InitializeRequest(req) => {
+                self.respond_initialize(req, log_ctx).await
+            }
+            mcp::ClientRequest::ListToolsRequest(req) => {
+                self.respond_list_tools(req, &context, log_ctx).await
+            }
+            mcp::ClientRequest::CallToolRequest(req) => {
+                self.respond_call_tool(req, &context, log_ctx).await
+            }
+            mcp::ClientRequest::ListResourcesRequest(_req) => {
+                self.respond_list_resources(log_ctx).await
+            }
+            mcp::ClientRequest::ListResourceTemplatesRequest(_req) => {
+                self.respond_list_resource_templates(log_ctx).await
+            }
+            mcp::ClientRequest::ReadResourceRequest(req) => {
+                self.respond_read_resource(req, &context, log_ctx).await
+            }
+            mcp::ClientRequest::ListPromptsRequest(_req) => {
+                self.respond_list_prompts(log_ctx).await
+            }
+            mcp::ClientRequest::GetPromptRequest(req) => {
+                self.respond_get_prompt(req, &context, log_ctx).await
+            }
+            mcp::ClientRequest::CompleteRequest(req) => {
+                self.respond_complete(req, &context, log_ctx).await
+            }
+            mcp::ClientRequest::SetLevelRequest(req) => self.respond_set_level(req, log_ctx).await,
             _other => self.respond_other(log_ctx).await,
         }
     }
 
     async fn handle_notification(
         &self,
-        _notification: mcp::ClientNotification,
-        _context: rmcp::service::NotificationContext<RoleServer>,
+        notification: mcp::ClientNotification,
+        context: rmcp::service::NotificationContext<RoleServer>,
     ) -> Result<(), mcp::ErrorData> {
+        if let mcp::ClientNotification::InitializedNotification(_) = notification {
+            crate::downstream::register_peer(self.session, context.peer).await;
+        }
         Ok(())
     }
 
     fn get_info(&self) -> mcp::ServerInfo {
         mcp::ServerInfo {
-            protocol_version: mcp::ProtocolVersion::V_2025_03_26,
-            capabilities: mcp::ServerCapabilities::builder()
-                .enable_logging()
-                .enable_tools()
-                .enable_tool_list_changed()
-                .build(),
+            protocol_version: crate::protocol::latest(),
+            capabilities: server_capabilities(),
             server_info: mcp::Implementation {
                 name: "MCP Bouncer".into(),
                 title: None,
@@ -101,18 +294,88 @@ where
     CP: ConfigProvider + Clone + Send + Sync + 'static,
     L: RpcEventPublisher,
 {
+    fn scope(&self) -> Scope {
+        let Some(name) = self.profile.as_ref() else {
+            return Scope::Everything;
+        };
+        load_settings_with(&self.cp)
+            .profiles
+            .into_iter()
+            .find(|p| &p.name == name)
+            .map(Scope::Profile)
+            .unwrap_or(Scope::Nothing)
+    }
+
+    // Enabled servers visible through this service's profile.
+    fn servers(&self) -> Vec<MCPServerConfig> {
+        let scope = self.scope();
+        enabled_servers(&self.cp)
+            .into_iter()
+            .filter(|cfg| scope.allows_server(&cfg.name))
+            .collect()
+    }
+
+    // Policy for the session's client, recorded on the request's log entry.
+    async fn client_policy(
+        &self,
+        peer: &rmcp::Peer<RoleServer>,
+        log_ctx: Option<&RequestLogContext<E, L>>,
+    ) -> Option<ClientPolicy> {
+        let client = peer.peer_info().map(|info| info.client_info.name.clone())?;
+        let policies = load_settings_with(&self.cp).client_policies;
+        let policy = crate::config::client_policy_for(&policies, &client).cloned()?;
+        if let Some(ctx) = log_ctx {
+            ctx.set_policy(policy.name.clone()).await;
+        }
+        Some(policy)
+    }
+
+    // The aggregated tool list narrowed to what `policy` allows.
+    async fn tools_for_client(
+        &self,
+        cached: bool,
+        policy: Option<&ClientPolicy>,
+    ) -> Vec<mcp::Tool> {
+        let mut tools = self.build_tool_list(cached).await;
+        let Some(policy) = policy else {
+            return tools;
+        };
+        let aliases = self.tool_aliases.read().await.clone();
+        // Read-only hints come from the same upstream definitions the call
+        // gate checks, so a listed tool is never denied when called.
+        let mut upstream: HashMap<String, Vec<ToolInfo>> = HashMap::new();
+        if policy.read_only {
+            for cfg in self.servers() {
+                let list = self.upstream_tools(&cfg).await;
+                upstream.insert(cfg.name, list);
+            }
+        }
+        tools.retain(|tool| match aliases.get(tool.name.as_ref()) {
+            Some((server_name, tool_name)) => {
+                let read_only = upstream
+                    .get(server_name)
+                    .is_some_and(|list| hint_of(list, tool_name, "readOnlyHint"));
+                policy.allows_tool(server_name, tool_name, read_only)
+            }
+            None => policy.allows_macro(&tool.name),
+        });
+        tools
+    }
+
+    fn select_server(&self, server_name: &str) -> Result<Option<MCPServerConfig>, String> {
+        let scope = self.scope();
+        select_target_server(&self.cp, server_name)
+            .map(|opt| opt.filter(|cfg| scope.allows_server(&cfg.name)))
+    }
+
     async fn respond_initialize(
         &self,
+        req: mcp::InitializeRequest,
         log_ctx: Option<RequestLogContext<E, L>>,
     ) -> Result<mcp::ServerResult, mcp::ErrorData> {
-        let capabilities = mcp::ServerCapabilities::builder()
-            .enable_logging()
-            .enable_tools()
-            .enable_tool_list_changed()
-            .build();
         let result = mcp::InitializeResult {
-            protocol_version: mcp::ProtocolVersion::V_2025_03_26,
-            capabilities,
+            protocol_version: crate::protocol::negotiate(&req.params.protocol_version),
+            capabilities: server_capabilities(),
             server_info: mcp::Implementation {
                 name: "MCP Bouncer".into(),
                 title: None,
@@ -131,251 +394,1555 @@ where
 
     async fn respond_list_tools(
         &self,
+        req: mcp::ListToolsRequest,
+        context: &rmcp::service::RequestContext<RoleServer>,
         log_ctx: Option<RequestLogContext<E, L>>,
     ) -> Result<mcp::ServerResult, mcp::ErrorData> {
         let settings = load_settings_with(&self.cp);
-        let servers: Vec<_> = settings
-            .mcp_servers
-            .into_iter()
-            .filter(|c| c.enabled)
-            .collect();
-        const TOOL_LIST_TIMEOUT_SECS: u64 = 6;
+        let policy = self.client_policy(&context.peer, log_ctx.as_ref()).await;
+        if settings.tool_search {
+            let out = mcp::ServerResult::ListToolsResult(mcp::ListToolsResult {
+                tools: crate::tool_search::meta_tools(),
+                next_cursor: None,
+            });
+            if let Some(ctx) = log_ctx.as_ref() {
+                ctx.log_local_result(&out).await;
+            }
+            return Ok(out);
+        }
+        let page_size = settings
+            .tools_page_size
+            .filter(|n| *n > 0)
+            .map(|n| n as usize);
+        let cursor = req.params.and_then(|p| p.cursor);
+
+        // Later pages are served from the snapshot taken for the first page, so
+        // aliases never shift between pages even if upstream lists change.
+        let (snapshot, offset) = match cursor {
+            Some(cursor) => {
+                let Some((id, offset)) = decode_tools_cursor(&cursor) else {
+                    return Err(mcp::ErrorData::invalid_params(
+                        format!("invalid cursor: {cursor}"),
+                        None,
+                    ));
+                };
+                let snapshot = {
+                    let guard = self.tool_snapshots.read().await;
+                    guard.iter().find(|s| s.id == id).cloned()
+                };
+                let Some(snapshot) = snapshot else {
+                    return Err(mcp::ErrorData::invalid_params(
+                        format!("expired cursor: {cursor}"),
+                        None,
+                    ));
+                };
+                (snapshot, offset)
+            }
+            None => {
+                let tools = self.tools_for_client(false, policy.as_ref()).await;
+                let snapshot = ToolsSnapshot {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    tools: Arc::new(tools),
+                    aliases: Arc::new(self.tool_aliases.read().await.clone()),
+                };
+                if page_size.is_some_and(|n| snapshot.tools.len() > n) {
+                    let mut guard = self.tool_snapshots.write().await;
+                    if guard.len() >= TOOL_SNAPSHOTS_KEPT {
+                        guard.pop_front();
+                    }
+                    guard.push_back(snapshot.clone());
+                }
+                (snapshot, 0)
+            }
+        };
+        *self.listed_aliases.write().await = Some(snapshot.aliases.clone());
+
+        let (mut tools, next_offset) = page_of(&snapshot.tools, offset, page_size);
+        if !crate::protocol::supports_structured_content(&crate::protocol::for_session(
+            &context.peer,
+        )) {
+            for tool in tools.iter_mut() {
+                tool.output_schema = None;
+            }
+        }
+        let out = mcp::ServerResult::ListToolsResult(mcp::ListToolsResult {
+            tools,
+            next_cursor: next_offset.map(|next| encode_tools_cursor(&snapshot.id, next)),
+        });
+        if let Some(ctx) = log_ctx.as_ref() {
+            ctx.log_local_result(&out).await;
+        }
+        Ok(out)
+    }
+
+    // Aggregates enabled tools from every enabled server and replaces the alias
+    // map used to route tools/call. With `cached`, servers already in the tools
+    // cache are not asked again.
+    async fn build_tool_list(&self, cached: bool) -> Vec<mcp::Tool> {
+        let servers = self.servers();
+        let server_names: Vec<String> = servers.iter().map(|cfg| cfg.name.clone()).collect();
         let tool_records = aggregate_tools(
             servers,
-            std::time::Duration::from_secs(TOOL_LIST_TIMEOUT_SECS),
+            std::time::Duration::from_secs(LIST_TIMEOUT_SECS),
             self.emitter.clone(),
             self.logger.clone(),
+            cached,
         )
         .await;
 
         let state = crate::config::load_tools_state_with(&self.cp);
-        let mut alias_counts: HashMap<String, usize> = HashMap::new();
-        let mut alias_map: HashMap<String, (String, String)> = HashMap::new();
-        let mut tools: Vec<mcp::Tool> = Vec::new();
-        for record in tool_records.into_iter() {
-            let enabled = state
-                .0
-                .get(&record.server_name)
-                .and_then(|m| m.get(&record.tool_name))
-                .copied()
-                .unwrap_or(true);
-            if !enabled {
-                continue;
+        let scope = self.scope();
+        let records: Vec<AggregatedTool> = tool_records
+            .into_iter()
+            .filter(|record| {
+                state
+                    .0
+                    .get(&record.server_name)
+                    .and_then(|m| m.get(&record.tool_name))
+                    .copied()
+                    .unwrap_or(true)
+            })
+            .filter(|record| scope.allows_tool(&record.server_name, &record.tool_name))
+            .collect();
+
+        let overrides = crate::config::load_tool_overrides_with(&self.cp);
+        let (mut custom_names, errors) =
+            crate::config::plan_tool_names(&overrides, &generated_aliases(&records));
+        for error in errors {
+            tracing::warn!(target = "server", error = %error, "tool_override_rejected");
+        }
+        // Macros are defined by hand in settings, so their names win over overrides.
+        let mut macros = exposed_macros(load_settings_with(&self.cp).macros);
+        macros.retain(|m| scope.allows_macro(m));
+        custom_names.retain(|_, name| {
+            let clash = macros.iter().any(|m| &m.name == name);
+            if clash {
+                tracing::warn!(target = "server", name = %name, "tool_override_shadowed_by_macro");
             }
+            !clash
+        });
 
-            let base = build_sanitized_tool_name(&record.server_name, &record.tool_name);
-            let entry = alias_counts.entry(base.clone()).or_insert(0);
-            *entry += 1;
-            let sanitized_name = if *entry == 1 {
-                base
-            } else {
-                format!("{base}-{}", *entry)
-            };
+        let generated: Vec<(String, String)> = records
+            .iter()
+            .filter(|r| !custom_names.contains_key(&(r.server_name.clone(), r.tool_name.clone())))
+            .map(|r| {
+                let name = build_sanitized_tool_name(&r.prefix, &r.tool_name);
+                (r.server_name.clone(), name)
+            })
+            .collect();
+        let mut conflicts =
+            cross_server_conflicts(generated.iter().map(|(s, n)| (s.as_str(), n.as_str())));
+        // Conflicts are reported for the full tool set only; a profile's subset
+        // would otherwise overwrite them.
+        let server_names = if self.profile.is_none() {
+            server_names
+        } else {
+            Vec::new()
+        };
+        for server_name in server_names {
+            let list = conflicts.remove(&server_name).unwrap_or_default();
+            if !list.is_empty() {
+                tracing::warn!(target = "server", server = %server_name, conflicts = ?list, "tool_name_conflicts");
+            }
+            // Only changes are emitted so a stable conflict does not spam the UI.
+            if crate::overlay::set_name_conflicts(&server_name, list).await {
+                client_status_changed(&self.emitter, &server_name, "name_conflicts");
+            }
+        }
 
-            alias_map.insert(
-                sanitized_name.clone(),
-                (record.server_name.clone(), record.tool_name.clone()),
-            );
+        // Custom and macro names are reserved up front so generated aliases
+        // never reuse them.
+        let mut alias_counts: HashMap<String, usize> = custom_names
+            .values()
+            .chain(macros.iter().map(|m| &m.name))
+            .map(|name| (name.clone(), 1))
+            .collect();
+        let mut alias_map: HashMap<String, (String, String)> = HashMap::new();
+        let mut tools: Vec<mcp::Tool> = Vec::new();
+        for record in records.into_iter() {
+            let key = (record.server_name.clone(), record.tool_name.clone());
+            let exposed_name = match custom_names.get(&key) {
+                Some(name) => name.clone(),
+                None => {
+                    let base = build_sanitized_tool_name(&record.prefix, &record.tool_name);
+                    next_alias(&mut alias_counts, base)
+                }
+            };
 
-            tools.push(mcp::Tool::new(
-                sanitized_name,
-                record.description.clone().unwrap_or_default(),
-                record.input_schema.clone(),
-            ));
+            let mut tool = record.tool;
+            if let Some(ov) = overrides.get(&record.server_name, &record.tool_name) {
+                if let Some(description) = ov.description.clone() {
+                    tool.description = Some(description.into());
+                }
+                crate::transform::apply_to_tool(&mut tool, ov);
+            }
+            tool.name = exposed_name.clone().into();
+            alias_map.insert(exposed_name, key);
+            tools.push(tool);
         }
+        tools.extend(macros.iter().map(crate::macros::to_tool));
 
         {
             let mut aliases = self.tool_aliases.write().await;
             aliases.clear();
-            aliases.extend(alias_map.into_iter());
-        }
-
-        let out = mcp::ServerResult::ListToolsResult(mcp::ListToolsResult {
-            tools,
-            next_cursor: None,
-        });
-        if let Some(ctx) = log_ctx.as_ref() {
-            ctx.log_local_result(&out).await;
+            aliases.extend(alias_map);
         }
-        Ok(out)
+        tools
     }
 
     async fn respond_call_tool(
         &self,
-        req: mcp::CallToolRequest,
+        mut req: mcp::CallToolRequest,
+        context: &rmcp::service::RequestContext<RoleServer>,
         log_ctx: Option<RequestLogContext<E, L>>,
     ) -> Result<mcp::ServerResult, mcp::ErrorData> {
-        let name = req.params.name.to_string();
-        let (server_name, tool_name) = self.resolve_tool_target(&name).await;
-        let args_obj = req
-            .params
-            .arguments
-            .clone()
-            .map(serde_json::Value::Object)
-            .and_then(|v| v.as_object().cloned());
-        let cfg_opt = match select_target_server(&self.cp, &server_name) {
-            Ok(opt) => opt,
-            Err(msg) => {
-                let out = mcp::ServerResult::CallToolResult(mcp::CallToolResult {
-                    content: vec![mcp::Content::text(msg)],
-                    structured_content: None,
-                    is_error: Some(true),
-                    meta: None,
-                });
-                if let Some(ctx) = log_ctx.as_ref() {
-                    ctx.log_local_result(&out).await;
+        let policy = self.client_policy(&context.peer, log_ctx.as_ref()).await;
+        if load_settings_with(&self.cp).tool_search {
+            let args = req.params.arguments.as_ref();
+            let unwrapped = match &*req.params.name {
+                crate::tool_search::SEARCH_TOOLS => {
+                    return self
+                        .respond_search_tools(args, policy.as_ref(), log_ctx)
+                        .await;
                 }
-                return Ok(out);
-            }
-        };
-        if let Some(cfg) = cfg_opt {
-            if let Some(ctx) = log_ctx.as_ref() {
-                ctx.set_server_name(cfg.name.clone()).await;
-            }
-            match ensure_rmcp_client(&cfg.name, &cfg, &self.emitter, &self.logger).await {
-                Ok(client) => {
-                    if let Some(ctx) = log_ctx.as_ref() {
-                        apply_log_context_from_client(&client, &cfg, ctx).await;
-                    }
-                    match client
-                        .call_tool(mcp::CallToolRequestParam {
-                            name: tool_name.into(),
-                            arguments: args_obj,
-                        })
-                        .await
-                    {
-                        Ok(res) => Ok(mcp::ServerResult::CallToolResult(res)),
-                        Err(e) => {
-                            if matches!(cfg.transport, crate::config::TransportType::StreamableHttp)
-                            {
-                                oauth::on_possible_unauthorized(&cfg.name, Some(&cfg.endpoint))
-                                    .await;
-                                client_status_changed(
-                                    &self.emitter,
-                                    &cfg.name,
-                                    "requires_authorization",
-                                );
-                            }
-                            let out = mcp::ServerResult::CallToolResult(mcp::CallToolResult {
-                                content: vec![mcp::Content::text(format!("error: {e}"))],
-                                structured_content: None,
-                                is_error: Some(true),
-                                meta: None,
-                            });
-                            Ok(out)
-                        }
+                crate::tool_search::CALL_TOOL => crate::tool_search::parse_call(args).map(Some),
+                _ => Ok(None),
+            };
+            match unwrapped {
+                Ok(Some((name, arguments))) => {
+                    // A session may call a name it found earlier without listing
+                    // in this process; fill the alias map from the cache first.
+                    if !self.tool_aliases.read().await.contains_key(&name) {
+                        self.build_tool_list(true).await;
                     }
+                    req.params.name = name.into();
+                    req.params.arguments = arguments;
                 }
-                Err(e) => {
+                Ok(None) => {}
+                Err(msg) => {
                     let out = mcp::ServerResult::CallToolResult(mcp::CallToolResult {
-                        content: vec![mcp::Content::text(format!("error: {e}"))],
+                        content: vec![mcp::Content::text(msg)],
                         structured_content: None,
                         is_error: Some(true),
                         meta: None,
                     });
-                    Ok(out)
+                    if let Some(ctx) = log_ctx.as_ref() {
+                        ctx.log_local_result(&out).await;
+                    }
+                    return Ok(out);
                 }
             }
-        } else {
-            let out = mcp::ServerResult::CallToolResult(mcp::CallToolResult {
-                content: vec![mcp::Content::text("no server".to_string())],
-                structured_content: None,
-                is_error: Some(true),
-                meta: None,
-            });
+        }
+        let name = req.params.name.to_string();
+        if let Some(def) = exposed_macros(load_settings_with(&self.cp).macros)
+            .into_iter()
+            .find(|m| m.name == name && self.scope().allows_macro(m))
+        {
+            let result = match policy.as_ref().filter(|p| !p.allows_macro(&def.name)) {
+                Some(policy) => self.deny_by_policy(policy, &name),
+                None => {
+                    let steps = MacroSteps {
+                        service: self,
+                        policy: policy.as_ref(),
+                        context,
+                        log_ctx: log_ctx.as_ref(),
+                    };
+                    crate::macros::run(&def, req.params.arguments.clone(), &steps).await
+                }
+            };
+            let out = mcp::ServerResult::CallToolResult(result);
             if let Some(ctx) = log_ctx.as_ref() {
                 ctx.log_local_result(&out).await;
             }
-            Ok(out)
+            return Ok(out);
+        }
+        let (server_name, tool_name) = self.resolve_tool_target(&name).await;
+        let call = ToolCall {
+            server_name,
+            tool_name,
+            label: &name,
+            arguments: req.params.arguments.clone(),
+            macro_step: false,
+        };
+        match self
+            .call_through_gates(call, policy.as_ref(), context, log_ctx.as_ref())
+            .await
+        {
+            GatedCall::Upstream(result) => Ok(mcp::ServerResult::CallToolResult(result)),
+            GatedCall::Rejected(result) => {
+                let out = mcp::ServerResult::CallToolResult(result);
+                if let Some(ctx) = log_ctx.as_ref() {
+                    ctx.log_local_result(&out).await;
+                }
+                Ok(out)
+            }
         }
     }
 
-    async fn respond_other(
+    // Every upstream tools/call passes these gates in order: profile scope,
+    // client policy, argument overrides, tool rules, approval and call limits.
+    // Rejections are returned for the caller to log.
+    async fn call_through_gates(
         &self,
-        log_ctx: Option<RequestLogContext<E, L>>,
-    ) -> Result<mcp::ServerResult, mcp::ErrorData> {
-        let out = mcp::ServerResult::empty(());
-        if let Some(ctx) = log_ctx.as_ref() {
-            ctx.log_local_result(&out).await;
+        call: ToolCall<'_>,
+        policy: Option<&ClientPolicy>,
+        context: &rmcp::service::RequestContext<RoleServer>,
+        log_ctx: Option<&RequestLogContext<E, L>>,
+    ) -> GatedCall {
+        let reject = |msg: String| {
+            GatedCall::Rejected(mcp::CallToolResult::error(vec![mcp::Content::text(msg)]))
+        };
+        let ToolCall {
+            server_name,
+            tool_name,
+            label,
+            arguments,
+            macro_step,
+        } = call;
+        let cfg = match self.select_server(&server_name) {
+            Ok(Some(cfg)) => cfg,
+            Ok(None) => return reject("no server".to_string()),
+            Err(msg) => return reject(msg),
+        };
+        if !self.scope().allows_tool(&cfg.name, &tool_name) {
+            return reject(format!("tool not available in this profile: {label}"));
+        }
+        if let Some(policy) = policy
+            && !policy.allows_tool(
+                &cfg.name,
+                &tool_name,
+                // Unknown tools count as writable so a read-only policy fails closed.
+                self.tool_hint(&cfg, &tool_name, "readOnlyHint").await,
+            )
+        {
+            return GatedCall::Rejected(self.deny_by_policy(policy, label));
+        }
+        let overrides = crate::config::load_tool_overrides_with(&self.cp);
+        let tool_override = overrides.get(&cfg.name, &tool_name);
+        let arguments = match tool_override {
+            Some(ov) => {
+                let client = context.peer.peer_info().map(|info| &info.client_info);
+                let template = crate::transform::TemplateContext::from_client(client);
+                match crate::transform::apply_to_arguments(arguments, ov, &template) {
+                    Ok(args) => args,
+                    Err(msg) => return reject(format!("error: {msg}")),
+                }
+            }
+            None => arguments,
+        };
+        if let Some(result) = self
+            .check_tool_rules(&cfg.name, &tool_name, arguments.as_ref(), log_ctx)
+            .await
+        {
+            return GatedCall::Rejected(result);
+        }
+        if self.needs_approval(&cfg, &tool_name, tool_override).await
+            && let Some(result) = self
+                .await_approval(&cfg.name, &tool_name, arguments.as_ref(), context, log_ctx)
+                .await
+        {
+            return GatedCall::Rejected(result);
+        }
+        let tool_limits = tool_override.and_then(|ov| ov.limits.as_ref());
+        let _call_permit = match self
+            .admit_call(&cfg, &tool_name, tool_limits, log_ctx)
+            .await
+        {
+            Ok(permit) => permit,
+            Err(result) => return GatedCall::Rejected(result),
+        };
+
+        let entry = log_ctx.filter(|_| !macro_step);
+        if let Some(ctx) = entry {
+            ctx.set_server_name(cfg.name.clone()).await;
+        }
+        let client = match ensure_rmcp_client(&cfg.name, &cfg, &self.emitter, &self.logger).await {
+            Ok(client) => client,
+            Err(e) => {
+                return GatedCall::Upstream(mcp::CallToolResult::error(vec![mcp::Content::text(
+                    format!("error: {e}"),
+                )]));
+            }
+        };
+        if let Some(ctx) = entry {
+            apply_log_context_from_client(&client, &cfg, ctx).await;
+        }
+        let param = mcp::CallToolRequestParam {
+            name: tool_name.into(),
+            arguments,
+        };
+        warn_if_upstream_older(&client, &cfg, &context.peer);
+        match call_upstream_tool(&client, &cfg.name, self.session, param, context, log_ctx).await {
+            // Step results feed later steps, so they keep structuredContent.
+            Ok(res) if macro_step => GatedCall::Upstream(res),
+            Ok(res) => GatedCall::Upstream(adapt_call_result_for_session(res, &context.peer)),
+            Err(e) => {
+                if matches!(cfg.transport, crate::config::TransportType::StreamableHttp) {
+                    oauth::on_possible_unauthorized(
+                        &cfg.name,
+                        crate::interpolate::resolve_endpoint(&cfg).ok().as_deref(),
+                    )
+                    .await;
+                    client_status_changed(&self.emitter, &cfg.name, "requires_authorization");
+                }
+                GatedCall::Upstream(mcp::CallToolResult::error(vec![mcp::Content::text(
+                    format!("error: {e}"),
+                )]))
+            }
         }
-        Ok(out)
     }
 
-    async fn resolve_tool_target(&self, alias: &str) -> (String, String) {
-        if let Some(mapped) = {
-            let guard = self.tool_aliases.read().await;
-            guard.get(alias).cloned()
-        } {
-            return mapped;
+    fn deny_by_policy(&self, policy: &ClientPolicy, name: &str) -> mcp::CallToolResult {
+        tracing::info!(target = "server", policy = %policy.name, tool = %name, "tool_denied_by_client_policy");
+        mcp::CallToolResult {
+            content: vec![mcp::Content::text(format!(
+                "tool not allowed by client policy '{}': {name}",
+                policy.name
+            ))],
+            structured_content: None,
+            is_error: Some(true),
+            meta: None,
+        }
+    }
+
+    // Evaluates tool_rules.json against the final arguments. Returns the error
+    // result when a rule blocks the call; dry-run matches are only logged.
+    async fn check_tool_rules(
+        &self,
+        server_name: &str,
+        tool_name: &str,
+        arguments: Option<&mcp::JsonObject>,
+        log_ctx: Option<&RequestLogContext<E, L>>,
+    ) -> Option<mcp::CallToolResult> {
+        let (verdict, msg) = match crate::tool_rules::load_tool_rules_with(&self.cp) {
+            Ok(rules) => {
+                let verdict = rules.evaluate(server_name, tool_name, arguments)?;
+                if verdict.action == crate::tool_rules::RuleAction::Allow {
+                    return None;
+                }
+                let msg = format!(
+                    "tool call blocked by rule '{}': {server_name}::{tool_name}",
+                    verdict.rule
+                );
+                (Some(verdict), msg)
+            }
+            Err(e) => (None, format!("tool call blocked: {e}")),
+        };
+        if let Some(verdict) = verdict.as_ref().filter(|v| !v.blocks()) {
+            tracing::info!(target = "server", rule = %verdict.rule, server = %server_name, tool = %tool_name, "tool_rule_dry_run_deny");
+            if let Some(ctx) = log_ctx {
+                ctx.log_linked(
+                    "rules/would_deny",
+                    Some(server_name.to_string()),
+                    Some(serde_json::json!({
+                        "rule": verdict.rule,
+                        "tool": tool_name,
+                        "arguments": arguments,
+                    })),
+                )
+                .await;
+            }
+            return None;
+        }
+        tracing::info!(target = "server", server = %server_name, tool = %tool_name, "{msg}");
+        Some(mcp::CallToolResult {
+            content: vec![mcp::Content::text(msg)],
+            structured_content: Some(serde_json::json!({
+                "blocked_by": verdict.map(|v| v.rule),
+                "server": server_name,
+                "tool": tool_name,
+            })),
+            is_error: Some(true),
+            meta: None,
+        })
+    }
+
+    // Applies the server and tool call limits. Queued and rejected calls are
+    // logged as `limits/throttled` under the call.
+    async fn admit_call(
+        &self,
+        cfg: &MCPServerConfig,
+        tool_name: &str,
+        tool_limits: Option<&crate::config::CallLimits>,
+        log_ctx: Option<&RequestLogContext<E, L>>,
+    ) -> Result<Option<crate::limits::CallPermit>, mcp::CallToolResult> {
+        if cfg.limits.is_none() && tool_limits.is_none() {
+            return Ok(None);
+        }
+        let started = std::time::Instant::now();
+        let admission =
+            crate::limits::acquire(&cfg.name, tool_name, cfg.limits.as_ref(), tool_limits).await;
+        let error = admission.as_ref().err().cloned();
+        let queued = admission.as_ref().is_ok_and(|a| a.waited.is_some());
+        if let Some(ctx) = log_ctx
+            && (queued || error.is_some())
+        {
+            ctx.log_linked_call(
+                "limits/throttled",
+                Some(cfg.name.clone()),
+                Some(serde_json::json!({ "tool": tool_name })),
+                Some(serde_json::json!({ "queued": queued })),
+                error.clone(),
+                started.elapsed().as_millis() as i64,
+            )
+            .await;
+        }
+        match admission {
+            Ok(admission) => Ok(Some(admission.permit)),
+            Err(msg) => {
+                tracing::info!(target = "server", server = %cfg.name, tool = %tool_name, "{msg}");
+                Err(mcp::CallToolResult {
+                    content: vec![mcp::Content::text(msg)],
+                    structured_content: None,
+                    is_error: Some(true),
+                    meta: None,
+                })
+            }
+        }
+    }
+
+    async fn needs_approval(
+        &self,
+        cfg: &MCPServerConfig,
+        tool_name: &str,
+        tool_override: Option<&ToolOverride>,
+    ) -> bool {
+        match tool_override.and_then(|ov| ov.approval) {
+            Some(ToolApproval::Ask) => true,
+            Some(ToolApproval::Auto) => false,
+            None => {
+                load_settings_with(&self.cp).approve_destructive_tools
+                    && self.tool_hint(cfg, tool_name, "destructiveHint").await
+            }
+        }
+    }
+
+    // Tools of `cfg` for annotation lookups; the cache is cold e.g. after a
+    // list_changed notification.
+    async fn upstream_tools(&self, cfg: &MCPServerConfig) -> Vec<ToolInfo> {
+        cached_or_listed_tools(cfg, &self.emitter, &self.logger)
+            .await
+            .unwrap_or_else(|e| {
+                tracing::warn!(target = "server", server = %cfg.name, error = %e, "tool_hints_unavailable");
+                Vec::new()
+            })
+    }
+
+    // Boolean annotation (readOnlyHint, destructiveHint, ...) from the upstream
+    // listing; unknown tools and missing hints read as false.
+    async fn tool_hint(&self, cfg: &MCPServerConfig, tool_name: &str, hint: &str) -> bool {
+        hint_of(&self.upstream_tools(cfg).await, tool_name, hint)
+    }
+
+    // Holds the call until the user decides; returns the error result to send
+    // back when the call was denied or timed out.
+    async fn await_approval(
+        &self,
+        server_name: &str,
+        tool_name: &str,
+        arguments: Option<&mcp::JsonObject>,
+        context: &rmcp::service::RequestContext<RoleServer>,
+        log_ctx: Option<&RequestLogContext<E, L>>,
+    ) -> Option<mcp::CallToolResult> {
+        let session_id = match log_ctx {
+            Some(ctx) => ctx.session_id().await,
+            None => "anon".to_string(),
+        };
+        let client_name = context.peer.peer_info().map(|i| i.client_info.name.clone());
+        let timeout = load_settings_with(&self.cp)
+            .approval_timeout_secs
+            .map(u64::from)
+            .unwrap_or(crate::approvals::DEFAULT_TIMEOUT_SECS);
+        let started = std::time::Instant::now();
+        let outcome = crate::approvals::request(
+            &self.emitter,
+            crate::approvals::ApprovalRequest {
+                session: self.session,
+                session_id: &session_id,
+                client_name: client_name.as_deref(),
+                server_name,
+                tool_name,
+                arguments,
+            },
+            std::time::Duration::from_secs(timeout),
+        )
+        .await;
+        tracing::info!(target = "server", server = %server_name, tool = %tool_name, outcome = outcome.as_str(), "tool_call_approval");
+        if let Some(ctx) = log_ctx {
+            let error = (!outcome.allowed()).then(|| format!("approval {}", outcome.as_str()));
+            ctx.log_linked_call(
+                "approval/decision",
+                Some(server_name.to_string()),
+                Some(serde_json::json!({ "tool": tool_name, "arguments": arguments })),
+                Some(serde_json::json!({ "outcome": outcome.as_str() })),
+                error,
+                started.elapsed().as_millis() as i64,
+            )
+            .await;
+        }
+        if outcome.allowed() {
+            return None;
+        }
+        let msg = match outcome {
+            crate::approvals::Outcome::TimedOut => {
+                format!("tool call was not approved within {timeout}s: {tool_name}")
+            }
+            _ => format!("tool call denied by user: {tool_name}"),
+        };
+        Some(mcp::CallToolResult {
+            content: vec![mcp::Content::text(msg)],
+            structured_content: None,
+            is_error: Some(true),
+            meta: None,
+        })
+    }
+
+    // search_tools: ranks the aggregated (cached) tool list against a query and
+    // returns the matches with their schemas.
+    async fn respond_search_tools(
+        &self,
+        args: Option<&mcp::JsonObject>,
+        policy: Option<&ClientPolicy>,
+        log_ctx: Option<RequestLogContext<E, L>>,
+    ) -> Result<mcp::ServerResult, mcp::ErrorData> {
+        let result = match crate::tool_search::parse_search(args) {
+            Ok((query, limit)) => {
+                let tools = self.tools_for_client(true, policy).await;
+                let hits = crate::tool_search::search(&tools, &query, limit);
+                let body = crate::tool_search::describe(&hits);
+                mcp::CallToolResult {
+                    content: vec![mcp::Content::text(body.to_string())],
+                    structured_content: None,
+                    is_error: Some(false),
+                    meta: None,
+                }
+            }
+            Err(msg) => mcp::CallToolResult {
+                content: vec![mcp::Content::text(msg)],
+                structured_content: None,
+                is_error: Some(true),
+                meta: None,
+            },
+        };
+        let out = mcp::ServerResult::CallToolResult(result);
+        if let Some(ctx) = log_ctx.as_ref() {
+            ctx.log_local_result(&out).await;
+        }
+        Ok(out)
+    }
+
+    async fn respond_list_resources(
+        &self,
+        log_ctx: Option<RequestLogContext<E, L>>,
+    ) -> Result<mcp::ServerResult, mcp::ErrorData> {
+        let per_server =
+            aggregate_per_server(
+                self.servers(),
+                std::time::Duration::from_secs(LIST_TIMEOUT_SECS),
+                self.emitter.clone(),
+                self.logger.clone(),
+                |cfg, emitter, logger| async move {
+                    fetch_resources_for_cfg(&cfg, &emitter, &logger).await
+                },
+            )
+            .await;
+
+        let mut alias_map: HashMap<String, (String, String)> = HashMap::new();
+        let mut resources: Vec<mcp::Resource> = Vec::new();
+        for (server_name, list) in per_server {
+            for mut resource in list {
+                let namespaced = build_namespaced_uri(&server_name, &resource.uri);
+                alias_map.insert(
+                    namespaced.clone(),
+                    (server_name.clone(), resource.uri.clone()),
+                );
+                resource.raw.uri = namespaced;
+                resources.push(resource);
+            }
+        }
+
+        {
+            let mut aliases = self.resource_aliases.write().await;
+            aliases.clear();
+            aliases.extend(alias_map);
+        }
+
+        let out = mcp::ServerResult::ListResourcesResult(mcp::ListResourcesResult {
+            resources,
+            next_cursor: None,
+        });
+        if let Some(ctx) = log_ctx.as_ref() {
+            ctx.log_local_result(&out).await;
+        }
+        Ok(out)
+    }
+
+    async fn respond_list_resource_templates(
+        &self,
+        log_ctx: Option<RequestLogContext<E, L>>,
+    ) -> Result<mcp::ServerResult, mcp::ErrorData> {
+        let per_server = aggregate_per_server(
+            self.servers(),
+            std::time::Duration::from_secs(LIST_TIMEOUT_SECS),
+            self.emitter.clone(),
+            self.logger.clone(),
+            |cfg, emitter, logger| async move {
+                fetch_resource_templates_for_cfg(&cfg, &emitter, &logger).await
+            },
+        )
+        .await;
+
+        let mut resource_templates: Vec<mcp::ResourceTemplate> = Vec::new();
+        for (server_name, list) in per_server {
+            for mut template in list {
+                template.raw.uri_template =
+                    build_namespaced_uri(&server_name, &template.uri_template);
+                resource_templates.push(template);
+            }
+        }
+
+        let out =
+            mcp::ServerResult::ListResourceTemplatesResult(mcp::ListResourceTemplatesResult {
+                resource_templates,
+                next_cursor: None,
+            });
+        if let Some(ctx) = log_ctx.as_ref() {
+            ctx.log_local_result(&out).await;
+        }
+        Ok(out)
+    }
+
+    async fn respond_read_resource(
+        &self,
+        req: mcp::ReadResourceRequest,
+        context: &rmcp::service::RequestContext<RoleServer>,
+        log_ctx: Option<RequestLogContext<E, L>>,
+    ) -> Result<mcp::ServerResult, mcp::ErrorData> {
+        let namespaced = req.params.uri;
+        let Some((server_name, uri)) = self.resolve_resource_target(&namespaced).await else {
+            return Err(mcp::ErrorData::resource_not_found(
+                format!("unknown resource: {namespaced}"),
+                None,
+            ));
+        };
+        let cfg = match self.select_server(&server_name) {
+            Ok(Some(cfg)) if cfg.enabled => cfg,
+            Ok(_) => {
+                return Err(mcp::ErrorData::resource_not_found(
+                    format!("server not available: {server_name}"),
+                    None,
+                ));
+            }
+            Err(msg) => return Err(mcp::ErrorData::invalid_request(msg, None)),
+        };
+        if let Some(ctx) = log_ctx.as_ref() {
+            ctx.set_server_name(cfg.name.clone()).await;
+        }
+        let client = ensure_rmcp_client(&cfg.name, &cfg, &self.emitter, &self.logger)
+            .await
+            .map_err(|e| mcp::ErrorData::internal_error(format!("error: {e}"), None))?;
+        if let Some(ctx) = log_ctx.as_ref() {
+            apply_log_context_from_client(&client, &cfg, ctx).await;
+        }
+        warn_if_upstream_older(&client, &cfg, &context.peer);
+        let _active = crate::downstream::track_active_call(
+            &cfg.name,
+            self.session,
+            context.peer.clone(),
+            None,
+        );
+        match client
+            .read_resource(mcp::ReadResourceRequestParam { uri })
+            .await
+        {
+            Ok(mut res) => {
+                for contents in res.contents.iter_mut() {
+                    let uri = match contents {
+                        mcp::ResourceContents::TextResourceContents { uri, .. } => uri,
+                        mcp::ResourceContents::BlobResourceContents { uri, .. } => uri,
+                    };
+                    *uri = build_namespaced_uri(&cfg.name, uri);
+                }
+                Ok(mcp::ServerResult::ReadResourceResult(res))
+            }
+            Err(rmcp::ServiceError::McpError(err)) => Err(err),
+            Err(e) => {
+                if matches!(cfg.transport, crate::config::TransportType::StreamableHttp) {
+                    oauth::on_possible_unauthorized(
+                        &cfg.name,
+                        crate::interpolate::resolve_endpoint(&cfg).ok().as_deref(),
+                    )
+                    .await;
+                    client_status_changed(&self.emitter, &cfg.name, "requires_authorization");
+                }
+                Err(mcp::ErrorData::internal_error(format!("error: {e}"), None))
+            }
+        }
+    }
+
+    async fn respond_list_prompts(
+        &self,
+        log_ctx: Option<RequestLogContext<E, L>>,
+    ) -> Result<mcp::ServerResult, mcp::ErrorData> {
+        let servers = self.servers();
+        let prefixes = exposure_prefixes(&servers);
+        let per_server =
+            aggregate_per_server(
+                servers,
+                std::time::Duration::from_secs(LIST_TIMEOUT_SECS),
+                self.emitter.clone(),
+                self.logger.clone(),
+                |cfg, emitter, logger| async move {
+                    fetch_prompts_for_cfg(&cfg, &emitter, &logger).await
+                },
+            )
+            .await;
+
+        let state = crate::config::load_prompts_state_with(&self.cp);
+        let mut alias_counts: HashMap<String, usize> = HashMap::new();
+        let mut alias_map: HashMap<String, (String, String)> = HashMap::new();
+        let mut prompts: Vec<mcp::Prompt> = Vec::new();
+        for (server_name, list) in per_server {
+            for mut prompt in list {
+                let enabled = state
+                    .0
+                    .get(&server_name)
+                    .and_then(|m| m.get(&prompt.name))
+                    .copied()
+                    .unwrap_or(true);
+                if !enabled {
+                    continue;
+                }
+
+                let prefix = prefixes.get(&server_name).unwrap_or(&server_name);
+                let base = build_sanitized_tool_name(prefix, &prompt.name);
+                let sanitized_name = next_alias(&mut alias_counts, base);
+                alias_map.insert(
+                    sanitized_name.clone(),
+                    (server_name.clone(), prompt.name.clone()),
+                );
+                prompt.name = sanitized_name;
+                prompts.push(prompt);
+            }
+        }
+
+        {
+            let mut aliases = self.prompt_aliases.write().await;
+            aliases.clear();
+            aliases.extend(alias_map);
+        }
+
+        let out = mcp::ServerResult::ListPromptsResult(mcp::ListPromptsResult {
+            prompts,
+            next_cursor: None,
+        });
+        if let Some(ctx) = log_ctx.as_ref() {
+            ctx.log_local_result(&out).await;
+        }
+        Ok(out)
+    }
+
+    async fn respond_get_prompt(
+        &self,
+        req: mcp::GetPromptRequest,
+        context: &rmcp::service::RequestContext<RoleServer>,
+        log_ctx: Option<RequestLogContext<E, L>>,
+    ) -> Result<mcp::ServerResult, mcp::ErrorData> {
+        let alias = req.params.name;
+        let (server_name, prompt_name) = self.resolve_prompt_target(&alias).await;
+        let cfg = match self.select_server(&server_name) {
+            Ok(Some(cfg)) if cfg.enabled => cfg,
+            Ok(_) => {
+                return Err(mcp::ErrorData::invalid_params(
+                    format!("unknown prompt: {alias}"),
+                    None,
+                ));
+            }
+            Err(msg) => return Err(mcp::ErrorData::invalid_request(msg, None)),
+        };
+        if !crate::config::is_prompt_enabled_with(&self.cp, &cfg.name, &prompt_name) {
+            return Err(mcp::ErrorData::invalid_params(
+                format!("prompt disabled: {alias}"),
+                None,
+            ));
+        }
+        if let Some(ctx) = log_ctx.as_ref() {
+            ctx.set_server_name(cfg.name.clone()).await;
+        }
+        let client = ensure_rmcp_client(&cfg.name, &cfg, &self.emitter, &self.logger)
+            .await
+            .map_err(|e| mcp::ErrorData::internal_error(format!("error: {e}"), None))?;
+        if let Some(ctx) = log_ctx.as_ref() {
+            apply_log_context_from_client(&client, &cfg, ctx).await;
+        }
+        warn_if_upstream_older(&client, &cfg, &context.peer);
+        let _active = crate::downstream::track_active_call(
+            &cfg.name,
+            self.session,
+            context.peer.clone(),
+            None,
+        );
+        match client
+            .get_prompt(mcp::GetPromptRequestParam {
+                name: prompt_name,
+                arguments: req.params.arguments,
+            })
+            .await
+        {
+            Ok(res) => Ok(mcp::ServerResult::GetPromptResult(res)),
+            Err(rmcp::ServiceError::McpError(err)) => Err(err),
+            Err(e) => {
+                if matches!(cfg.transport, crate::config::TransportType::StreamableHttp) {
+                    oauth::on_possible_unauthorized(
+                        &cfg.name,
+                        crate::interpolate::resolve_endpoint(&cfg).ok().as_deref(),
+                    )
+                    .await;
+                    client_status_changed(&self.emitter, &cfg.name, "requires_authorization");
+                }
+                Err(mcp::ErrorData::internal_error(format!("error: {e}"), None))
+            }
+        }
+    }
+
+    // Resolves the namespaced prompt or resource template reference back to its
+    // upstream and forwards the completion with the upstream's own name or URI.
+    async fn respond_complete(
+        &self,
+        req: mcp::CompleteRequest,
+        context: &rmcp::service::RequestContext<RoleServer>,
+        log_ctx: Option<RequestLogContext<E, L>>,
+    ) -> Result<mcp::ServerResult, mcp::ErrorData> {
+        let mcp::CompleteRequestParam {
+            r#ref,
+            argument,
+            context: completion_context,
+        } = req.params;
+        let (server_name, upstream_ref) = match r#ref {
+            mcp::Reference::Prompt(prompt) => {
+                let (server_name, name) = self.resolve_prompt_target(&prompt.name).await;
+                (
+                    server_name,
+                    mcp::Reference::Prompt(mcp::PromptReference { name, ..prompt }),
+                )
+            }
+            mcp::Reference::Resource(resource) => {
+                let Some((server_name, uri)) = self.resolve_resource_target(&resource.uri).await
+                else {
+                    return Err(mcp::ErrorData::invalid_params(
+                        format!("unknown resource reference: {}", resource.uri),
+                        None,
+                    ));
+                };
+                (server_name, mcp::Reference::for_resource(uri))
+            }
+        };
+        let cfg = match self.select_server(&server_name) {
+            Ok(Some(cfg)) if cfg.enabled => cfg,
+            Ok(_) => {
+                return Err(mcp::ErrorData::invalid_params(
+                    format!("server not available: {server_name}"),
+                    None,
+                ));
+            }
+            Err(msg) => return Err(mcp::ErrorData::invalid_request(msg, None)),
+        };
+        if let Some(ctx) = log_ctx.as_ref() {
+            ctx.set_server_name(cfg.name.clone()).await;
+        }
+        let client = ensure_rmcp_client(&cfg.name, &cfg, &self.emitter, &self.logger)
+            .await
+            .map_err(|e| mcp::ErrorData::internal_error(format!("error: {e}"), None))?;
+        if let Some(ctx) = log_ctx.as_ref() {
+            apply_log_context_from_client(&client, &cfg, ctx).await;
+        }
+        warn_if_upstream_older(&client, &cfg, &context.peer);
+        let supports_completions = client
+            .peer()
+            .peer_info()
+            .is_some_and(|info| info.capabilities.completions.is_some());
+        if !supports_completions {
+            let out = mcp::ServerResult::CompleteResult(mcp::CompleteResult::default());
+            if let Some(ctx) = log_ctx.as_ref() {
+                ctx.log_local_result(&out).await;
+            }
+            return Ok(out);
+        }
+        match client
+            .complete(mcp::CompleteRequestParam {
+                r#ref: upstream_ref,
+                argument,
+                context: completion_context,
+            })
+            .await
+        {
+            Ok(res) => Ok(mcp::ServerResult::CompleteResult(res)),
+            Err(rmcp::ServiceError::McpError(err)) => Err(err),
+            Err(e) => {
+                if matches!(cfg.transport, crate::config::TransportType::StreamableHttp) {
+                    oauth::on_possible_unauthorized(
+                        &cfg.name,
+                        crate::interpolate::resolve_endpoint(&cfg).ok().as_deref(),
+                    )
+                    .await;
+                    client_status_changed(&self.emitter, &cfg.name, "requires_authorization");
+                }
+                Err(mcp::ErrorData::internal_error(format!("error: {e}"), None))
+            }
+        }
+    }
+
+    // Fans logging/setLevel out to every enabled upstream that supports logging.
+    // Failures are logged but never fail the downstream request.
+    async fn respond_set_level(
+        &self,
+        req: mcp::SetLevelRequest,
+        log_ctx: Option<RequestLogContext<E, L>>,
+    ) -> Result<mcp::ServerResult, mcp::ErrorData> {
+        crate::downstream::set_log_level(self.session, req.params.level).await;
+        let level = crate::downstream::upstream_log_level()
+            .await
+            .unwrap_or(req.params.level);
+        let timeout = std::time::Duration::from_secs(LIST_TIMEOUT_SECS);
+        let tasks = self.servers().into_iter().map(|cfg| {
+            let emitter = self.emitter.clone();
+            let logger = self.logger.clone();
+            async move {
+                let fut = set_level_for_cfg(&cfg, level, &emitter, &logger);
+                match tokio::time::timeout(timeout, fut).await {
+                    Ok(Ok(())) => {}
+                    Ok(Err(e)) => {
+                        tracing::warn!(target = "server", server = %cfg.name, error = %e, "set_level_failed")
+                    }
+                    Err(_) => {
+                        tracing::warn!(target = "server", server = %cfg.name, "set_level_timeout")
+                    }
+                }
+            }
+        });
+        join_all(tasks).await;
+
+        let out = mcp::ServerResult::empty(());
+        if let Some(ctx) = log_ctx.as_ref() {
+            ctx.log_local_result(&out).await;
+        }
+        Ok(out)
+    }
+
+    async fn respond_other(
+        &self,
+        log_ctx: Option<RequestLogContext<E, L>>,
+    ) -> Result<mcp::ServerResult, mcp::ErrorData> {
+        let out = mcp::ServerResult::empty(());
+        if let Some(ctx) = log_ctx.as_ref() {
+            ctx.log_local_result(&out).await;
+        }
+        Ok(out)
+    }
+
+    async fn resolve_tool_target(&self, alias: &str) -> (String, String) {
+        let listed = self.listed_aliases.read().await.clone();
+        if let Some(mapped) = listed.and_then(|aliases| aliases.get(alias).cloned()) {
+            return mapped;
+        }
+        if let Some(mapped) = {
+            let guard = self.tool_aliases.read().await;
+            guard.get(alias).cloned()
+        } {
+            return mapped;
+        }
+
+        // A custom name can be called before this session has listed tools.
+        let overrides = crate::config::load_tool_overrides_with(&self.cp);
+        let mut matches: Vec<(String, String)> = overrides
+            .0
+            .iter()
+            .flat_map(|(server, tools)| {
+                tools
+                    .iter()
+                    .filter(|(_, o)| o.name.as_deref() == Some(alias))
+                    .map(move |(tool, _)| (server.clone(), tool.clone()))
+            })
+            .collect();
+        if matches.len() == 1 {
+            return matches.remove(0);
+        }
+
+        alias
+            .split_once("::")
+            .map(|(a, b)| (a.to_string(), b.to_string()))
+            .unwrap_or_else(|| (String::new(), alias.to_string()))
+    }
+
+    async fn resolve_prompt_target(&self, alias: &str) -> (String, String) {
+        if let Some(mapped) = {
+            let guard = self.prompt_aliases.read().await;
+            guard.get(alias).cloned()
+        } {
+            return mapped;
+        }
+
+        alias
+            .split_once("::")
+            .map(|(a, b)| (a.to_string(), b.to_string()))
+            .unwrap_or_else(|| (String::new(), alias.to_string()))
+    }
+
+    // Listed URIs resolve through the alias map; URIs expanded from a namespaced
+    // template are matched against the sanitized names of the enabled servers.
+    async fn resolve_resource_target(&self, namespaced: &str) -> Option<(String, String)> {
+        if let Some(mapped) = {
+            let guard = self.resource_aliases.read().await;
+            guard.get(namespaced).cloned()
+        } {
+            return Some(mapped);
+        }
+
+        let (component, uri) = split_namespaced_uri(namespaced)?;
+        self.servers()
+            .into_iter()
+            .find(|c| sanitize_component(&c.name).as_deref() == Some(component))
+            .map(|c| (c.name, uri.to_string()))
+    }
+}
+
+const LIST_TIMEOUT_SECS: u64 = 6;
+// Paginated tools/list snapshots kept per listener; older cursors expire.
+const TOOL_SNAPSHOTS_KEPT: usize = 16;
+const RESOURCE_URI_SCHEME: &str = "bouncer://";
+
+fn server_capabilities() -> mcp::ServerCapabilities {
+    mcp::ServerCapabilities::builder()
+        .enable_logging()
+        .enable_completions()
+        .enable_prompts()
+        .enable_prompts_list_changed()
+        .enable_resources()
+        .enable_resources_list_changed()
+        .enable_tools()
+        .enable_tool_list_changed()
+        .build()
+}
+
+fn enabled_servers<CP: ConfigProvider>(cp: &CP) -> Vec<MCPServerConfig> {
+    load_settings_with(cp)
+        .mcp_servers
+        .into_iter()
+        .filter(|c| c.enabled)
+        .collect()
+}
+
+#[derive(Clone, Debug)]
+struct AggregatedTool {
+    server_name: String,
+    tool_name: String,
+    // Exposure prefix from the server config; the server name unless overridden.
+    prefix: String,
+    // Upstream definition as listed; only the name and any overridden
+    // description are rewritten when exposed.
+    tool: mcp::Tool,
+}
+
+fn to_aggregated_tool(server: &str, v: &serde_json::Value) -> Option<AggregatedTool> {
+    let name = v.get("name")?.as_str()?.to_string();
+    let mut v = v.clone();
+    if let Some(obj) = v.as_object_mut()
+        && !obj.contains_key("inputSchema")
+    {
+        let schema = obj
+            .remove("input_schema")
+            .filter(|s| s.is_object())
+            .unwrap_or_else(|| serde_json::json!({}));
+        obj.insert("inputSchema".into(), schema);
+    }
+    let tool = serde_json::from_value::<mcp::Tool>(v.clone()).unwrap_or_else(|_| {
+        let description = v
+            .get("description")
+            .and_then(|d| d.as_str())
+            .map(|s| s.to_string())
+            .unwrap_or_default();
+        let schema_obj = v
+            .get("inputSchema")
+            .and_then(|s| s.as_object().cloned())
+            .unwrap_or_default();
+        mcp::Tool::new(name.clone(), description, schema_obj)
+    });
+    Some(AggregatedTool {
+        server_name: server.to_string(),
+        tool_name: name,
+        prefix: server.to_string(),
+        tool,
+    })
+}
+
+fn sanitize_component(input: &str) -> Option<String> {
+    if input.is_empty() {
+        return None;
+    }
+
+    let sanitized: String = input
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let trimmed = sanitized.trim_matches('_');
+    if trimmed.is_empty() {
+        None
+    } else {
+        Some(trimmed.to_string())
+    }
+}
+
+// Prefix each server's names are exposed under; an empty prefix means the
+// upstream names are exposed as-is.
+fn exposure_prefixes(servers: &[MCPServerConfig]) -> HashMap<String, String> {
+    servers
+        .iter()
+        .map(|cfg| {
+            let prefix = cfg.prefix.clone().unwrap_or_else(|| cfg.name.clone());
+            (cfg.name.clone(), prefix)
+        })
+        .collect()
+}
+
+// For each server, the exposed names it shares with at least one other server,
+// e.g. two flat servers that both expose `search`.
+fn cross_server_conflicts<'a>(
+    names: impl IntoIterator<Item = (&'a str, &'a str)>,
+) -> BTreeMap<String, Vec<String>> {
+    let mut owners: BTreeMap<&str, std::collections::BTreeSet<&str>> = BTreeMap::new();
+    for (server, name) in names {
+        owners.entry(name).or_default().insert(server);
+    }
+    let mut conflicts: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for (name, servers) in owners.into_iter().filter(|(_, s)| s.len() > 1) {
+        for server in servers.iter() {
+            let others: Vec<&str> = servers.iter().copied().filter(|o| o != server).collect();
+            conflicts
+                .entry(server.to_string())
+                .or_default()
+                .push(format!("'{name}' is also exposed by {}", others.join(", ")));
+        }
+    }
+    conflicts
+}
+
+fn build_sanitized_tool_name(server: &str, tool: &str) -> String {
+    let server_part = sanitize_component(server);
+    let tool_part = sanitize_component(tool);
+
+    match (server_part, tool_part) {
+        (Some(server), Some(tool)) => format!("{server}__{tool}"),
+        (Some(server), None) => server,
+        (None, Some(tool)) => tool,
+        (None, None) => "tool".to_string(),
+    }
+}
+
+// Upstream resource URIs are prefixed with the sanitized server name so that
+// resources from different servers never collide, e.g. `bouncer://fs/file:///a.txt`.
+fn build_namespaced_uri(server: &str, uri: &str) -> String {
+    let server_part = sanitize_component(server).unwrap_or_else(|| "server".to_string());
+    format!("{RESOURCE_URI_SCHEME}{server_part}/{uri}")
+}
+
+fn split_namespaced_uri(namespaced: &str) -> Option<(&str, &str)> {
+    namespaced
+        .strip_prefix(RESOURCE_URI_SCHEME)?
+        .split_once('/')
+        .filter(|(server, uri)| !server.is_empty() && !uri.is_empty())
+}
+
+// Warns (once per combination) when the upstream negotiated an older protocol
+// than the downstream session forwarding to it.
+fn warn_if_upstream_older(
+    client: &crate::client::ClientService,
+    cfg: &MCPServerConfig,
+    peer: &rmcp::Peer<RoleServer>,
+) {
+    if let Some(info) = client.peer().peer_info() {
+        crate::protocol::warn_if_upstream_older(
+            &cfg.name,
+            &info.protocol_version,
+            &crate::protocol::for_session(peer),
+        );
+    }
+}
+
+// Sessions older than 2025-06-18 do not understand structuredContent, so fold
+// it into a text block when the upstream returned nothing else.
+fn adapt_call_result_for_session(
+    mut res: mcp::CallToolResult,
+    peer: &rmcp::Peer<RoleServer>,
+) -> mcp::CallToolResult {
+    if crate::protocol::supports_structured_content(&crate::protocol::for_session(peer)) {
+        return res;
+    }
+    if let Some(structured) = res.structured_content.take()
+        && res.content.is_empty()
+    {
+        res.content.push(mcp::Content::text(structured.to_string()));
+    }
+    res
+}
+
+// Proxies a tools/call upstream while relaying progress notifications back to the
+// caller (when it asked for them) and forwarding a downstream cancellation.
+async fn call_upstream_tool<E, L>(
+    client: &crate::client::ClientService,
+    server_name: &str,
+    session: u64,
+    param: mcp::CallToolRequestParam,
+    context: &rmcp::service::RequestContext<RoleServer>,
+    log_ctx: Option<&RequestLogContext<E, L>>,
+) -> Result<mcp::CallToolResult, rmcp::ServiceError>
+where
+    E: EventEmitter + Clone + Send + Sync + 'static,
+    L: RpcEventPublisher,
+{
+    // The route is registered under our own token before sending, so progress
+    // from a fast upstream cannot arrive ahead of it. The token in `meta`
+    // replaces the one rmcp would pick.
+    let downstream_token = context.meta.get_progress_token();
+    let mut options = rmcp::service::PeerRequestOptions::no_options();
+    let upstream_token = downstream_token.as_ref().map(|_| {
+        mcp::ProgressToken(mcp::NumberOrString::String(
+            format!("bouncer-{}", uuid::Uuid::new_v4()).into(),
+        ))
+    });
+    let mut progress = upstream_token.clone().map(|token| {
+        let mut meta = mcp::Meta::new();
+        meta.set_progress_token(token.clone());
+        options.meta = Some(meta);
+        crate::downstream::register_progress_route(server_name, token)
+    });
+    // Upstream requests that name this token are answered by this session.
+    let _active = crate::downstream::track_active_call(
+        server_name,
+        session,
+        context.peer.clone(),
+        upstream_token,
+    );
+    let handle = client
+        .send_cancellable_request(
+            mcp::ClientRequest::CallToolRequest(mcp::Request::new(param)),
+            options,
+        )
+        .await?;
+    let mut response = handle.rx;
+    loop {
+        tokio::select! {
+            res = &mut response => {
+                let res = res.map_err(|_| rmcp::ServiceError::TransportClosed)??;
+                return match res {
+                    mcp::ServerResult::CallToolResult(result) => Ok(result),
+                    _ => Err(rmcp::ServiceError::UnexpectedResponse),
+                };
+            }
+            Some(upstream) = async {
+                match progress.as_mut() {
+                    Some(route) => route.recv().await,
+                    None => std::future::pending().await,
+                }
+            } => {
+                let Some(token) = downstream_token.clone() else { continue };
+                let relayed = mcp::ProgressNotificationParam {
+                    progress_token: token,
+                    ..upstream
+                };
+                if let Some(ctx) = log_ctx {
+                    ctx.log_linked(
+                        "notifications/progress",
+                        Some(server_name.to_string()),
+                        Some(serde_json::json!({
+                            "jsonrpc": "2.0",
+                            "method": "notifications/progress",
+                            "params": &relayed,
+                        })),
+                    )
+                    .await;
+                }
+                let _ = context.peer.notify_progress(relayed).await;
+            }
+            _ = context.ct.cancelled() => {
+                let cancelled = mcp::CancelledNotificationParam {
+                    request_id: handle.id.clone(),
+                    reason: Some("cancelled by downstream client".into()),
+                };
+                if let Some(ctx) = log_ctx {
+                    ctx.log_linked(
+                        "notifications/cancelled",
+                        Some(server_name.to_string()),
+                        Some(serde_json::json!({
+                            "jsonrpc": "2.0",
+                            "method": "notifications/cancelled",
+                            "params": &cancelled,
+                        })),
+                    )
+                    .await;
+                }
+                let _ = handle.peer.notify_cancelled(cancelled).await;
+                return Ok(mcp::CallToolResult {
+                    content: vec![mcp::Content::text("cancelled")],
+                    structured_content: None,
+                    is_error: Some(true),
+                    meta: None,
+                });
+            }
         }
-
-        alias
-            .split_once("::")
-            .map(|(a, b)| (a.to_string(), b.to_string()))
-            .unwrap_or_else(|| (String::new(), alias.to_string()))
     }
 }
 
-#[derive(Clone, Debug)]
-struct AggregatedTool {
-    server_name: String,
-    tool_name: String,
-    description: Option<String>,
-    input_schema: serde_json::Map<String, serde_json::Value>,
+fn hint_of(tools: &[ToolInfo], tool_name: &str, hint: &str) -> bool {
+    tools
+        .iter()
+        .find(|t| t.name == tool_name)
+        .and_then(|t| t.annotations.as_ref())
+        .and_then(|a| a.get(hint).and_then(|v| v.as_bool()))
+        .unwrap_or(false)
 }
 
-fn to_aggregated_tool(server: &str, v: &serde_json::Value) -> Option<AggregatedTool> {
-    let name = v.get("name")?.as_str()?.to_string();
-    let description = v
-        .get("description")
-        .and_then(|d| d.as_str())
-        .map(|s| s.to_string());
-    let schema_obj = v
-        .get("inputSchema")
-        .or_else(|| v.get("input_schema"))
-        .and_then(|s| s.as_object().cloned())
-        .unwrap_or_default();
-    Some(AggregatedTool {
-        server_name: server.to_string(),
-        tool_name: name,
-        description,
-        input_schema: schema_obj,
-    })
+// Cursors are `<snapshot id>:<offset>`; they are opaque to clients.
+fn encode_tools_cursor(snapshot_id: &str, offset: usize) -> String {
+    format!("{snapshot_id}:{offset}")
 }
 
-fn sanitize_component(input: &str) -> Option<String> {
-    if input.is_empty() {
+fn decode_tools_cursor(cursor: &str) -> Option<(&str, usize)> {
+    let (id, offset) = cursor.rsplit_once(':')?;
+    if id.is_empty() {
         return None;
     }
+    Some((id, offset.parse().ok()?))
+}
 
-    let sanitized: String = input
-        .chars()
-        .map(|c| {
-            if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
-                c
-            } else {
-                '_'
+// Returns the page starting at `offset` and the offset of the next page, if any.
+// Without a page size everything from `offset` onwards is one page.
+fn page_of<T: Clone>(
+    items: &[T],
+    offset: usize,
+    page_size: Option<usize>,
+) -> (Vec<T>, Option<usize>) {
+    let start = offset.min(items.len());
+    let end = match page_size {
+        Some(size) => start.saturating_add(size).min(items.len()),
+        None => items.len(),
+    };
+    let next = (end < items.len()).then_some(end);
+    (items[start..end].to_vec(), next)
+}
+
+// Macros from settings that can be exposed; invalid ones and repeated names
+// are logged and skipped.
+fn exposed_macros(defs: Vec<crate::config::MacroTool>) -> Vec<crate::config::MacroTool> {
+    let mut seen = std::collections::HashSet::new();
+    defs.into_iter()
+        .filter(|def| {
+            let checked = crate::macros::validate(def).and_then(|()| {
+                if seen.insert(def.name.clone()) {
+                    Ok(())
+                } else {
+                    Err(format!("duplicate macro name '{}'", def.name))
+                }
+            });
+            match checked {
+                Ok(()) => true,
+                Err(error) => {
+                    tracing::warn!(target = "server", error = %error, "macro_rejected");
+                    false
+                }
             }
         })
-        .collect();
-    let trimmed = sanitized.trim_matches('_');
-    if trimmed.is_empty() {
-        None
+        .collect()
+}
+
+// Repeated names get a numeric suffix: `srv__tool`, `srv__tool-2`, ...
+fn next_alias(counts: &mut HashMap<String, usize>, base: String) -> String {
+    let entry = counts.entry(base.clone()).or_insert(0);
+    *entry += 1;
+    if *entry == 1 {
+        base
     } else {
-        Some(trimmed.to_string())
+        format!("{base}-{}", *entry)
     }
 }
 
-fn build_sanitized_tool_name(server: &str, tool: &str) -> String {
-    let server_part = sanitize_component(server);
-    let tool_part = sanitize_component(tool);
+// Listed tools as (server, tool, generated alias), the shape custom names are
+// checked against.
+fn generated_aliases(records: &[AggregatedTool]) -> Vec<(String, String, String)> {
+    records
+        .iter()
+        .map(|r| {
+            let alias = build_sanitized_tool_name(&r.prefix, &r.tool_name);
+            (r.server_name.clone(), r.tool_name.clone(), alias)
+        })
+        .collect()
+}
 
-    match (server_part, tool_part) {
-        (Some(server), Some(tool)) => format!("{server}__{tool}"),
-        (Some(server), None) => server,
-        (None, Some(tool)) => tool,
-        (None, None) => "tool".to_string(),
+// Upstream definitions of `cfg`'s tools from the tools cache, listed again
+// (and cached) when it is cold.
+async fn cached_or_listed_tools<E, L>(
+    cfg: &MCPServerConfig,
+    emitter: &E,
+    logger: &L,
+) -> Result<Vec<ToolInfo>, String>
+where
+    E: EventEmitter + Clone + Send + Sync + 'static,
+    L: RpcEventPublisher,
+{
+    if let Some(tools) = crate::tools_cache::get(&cfg.name).await {
+        return Ok(tools);
+    }
+    let raw = tokio::time::timeout(
+        std::time::Duration::from_secs(LIST_TIMEOUT_SECS),
+        fetch_tools_for_cfg(cfg, emitter, logger),
+    )
+    .await
+    .map_err(|_| "timed out listing tools".to_string())?
+    .map_err(|e| e.to_string())?;
+    let infos: Vec<ToolInfo> = raw.iter().map(ToolInfo::from_tool_json).collect();
+    crate::tools_cache::set(&cfg.name, infos.clone()).await;
+    Ok(infos)
+}
+
+// Generated alias of every tool of the enabled servers, as (server, tool,
+// alias), so custom names can be checked against them before saving. Servers
+// whose tools could not be listed come back in the second list, since the
+// check is incomplete without them.
+pub async fn generated_tool_aliases<E, CP, L>(
+    emitter: &E,
+    cp: &CP,
+    logger: &L,
+) -> (Vec<(String, String, String)>, Vec<String>)
+where
+    E: EventEmitter + Clone + Send + Sync + 'static,
+    CP: ConfigProvider,
+    L: RpcEventPublisher,
+{
+    let servers = enabled_servers(cp);
+    let prefixes = exposure_prefixes(&servers);
+    let mut out = Vec::new();
+    let mut unlisted = Vec::new();
+    for cfg in &servers {
+        let tools = match cached_or_listed_tools(cfg, emitter, logger).await {
+            Ok(tools) => tools,
+            Err(e) => {
+                tracing::warn!(target = "server", server = %cfg.name, error = %e, "tool_aliases_unavailable");
+                unlisted.push(cfg.name.clone());
+                continue;
+            }
+        };
+        let prefix = &prefixes[&cfg.name];
+        out.extend(tools.into_iter().map(|t| {
+            let alias = build_sanitized_tool_name(prefix, &t.name);
+            (cfg.name.clone(), t.name, alias)
+        }));
     }
+    (out, unlisted)
 }
 
 fn select_target_server<CP: ConfigProvider>(
@@ -407,33 +1974,69 @@ async fn aggregate_tools<E, L>(
     timeout: std::time::Duration,
     emitter: E,
     logger: L,
+    cached: bool,
 ) -> Vec<AggregatedTool>
 where
     E: EventEmitter + Clone + Send + Sync + 'static,
     L: RpcEventPublisher,
+{
+    let prefixes = exposure_prefixes(&servers);
+    let per_server = aggregate_per_server(
+        servers,
+        timeout,
+        emitter,
+        logger,
+        |cfg, emitter, logger| async move {
+            if cached && let Some(list) = crate::tools_cache::get(&cfg.name).await {
+                return Ok(list.iter().map(ToolInfo::to_tool_json).collect());
+            }
+            // Every fresh listing refreshes the cache that call gating reads
+            // annotations from.
+            let raw = fetch_tools_for_cfg(&cfg, &emitter, &logger).await?;
+            let infos = raw.iter().map(ToolInfo::from_tool_json).collect();
+            crate::tools_cache::set(&cfg.name, infos).await;
+            Ok(raw)
+        },
+    )
+    .await;
+    let mut tools: Vec<AggregatedTool> = Vec::new();
+    for (server_name, list) in per_server {
+        for item in list {
+            if let Some(mut t) = to_aggregated_tool(&server_name, &item) {
+                if let Some(prefix) = prefixes.get(&server_name) {
+                    t.prefix = prefix.clone();
+                }
+                tools.push(t);
+            }
+        }
+    }
+    tools
+}
+
+async fn aggregate_per_server<E, L, T, F, Fut>(
+    servers: Vec<MCPServerConfig>,
+    timeout: std::time::Duration,
+    emitter: E,
+    logger: L,
+    fetch: F,
+) -> Vec<(String, Vec<T>)>
+where
+    E: EventEmitter + Clone + Send + Sync + 'static,
+    L: RpcEventPublisher,
+    F: Fn(MCPServerConfig, E, L) -> Fut,
+    Fut: std::future::Future<Output = anyhow::Result<Vec<T>>>,
 {
     let tasks = servers.into_iter().map(|cfg| {
         let name = cfg.name.clone();
-        let emitter = emitter.clone();
-        let logger = logger.clone();
+        let fut = fetch(cfg, emitter.clone(), logger.clone());
         async move {
-            let fut = async { fetch_tools_for_cfg(&cfg, &emitter, &logger).await };
             match tokio::time::timeout(timeout, fut).await {
                 Ok(Ok(list)) => Some((name, list)),
                 _ => None,
             }
         }
     });
-    let mut tools: Vec<AggregatedTool> = Vec::new();
-    for res in join_all(tasks).await.into_iter().flatten() {
-        let (server_name, list) = res;
-        for item in list {
-            if let Some(t) = to_aggregated_tool(&server_name, &item) {
-                tools.push(t);
-            }
-        }
-    }
-    tools
+    join_all(tasks).await.into_iter().flatten().collect()
 }
 
 /* test module moved to end of file to satisfy clippy::items-after-test-module */
@@ -501,6 +2104,8 @@ mod tests {
             endpoint: String::new(),
             headers: Default::default(),
             enabled: true,
+            prefix: None,
+            limits: None,
         });
         s.mcp_servers.push(MCPServerConfig {
             name: "b".into(),
@@ -512,6 +2117,8 @@ mod tests {
             endpoint: String::new(),
             headers: Default::default(),
             enabled: true,
+            prefix: None,
+            limits: None,
         });
         save_settings_with(&cp, &s).unwrap();
         let sel = super::select_target_server(&cp, "");
@@ -527,10 +2134,28 @@ mod tests {
         let t2 = super::to_aggregated_tool("srv", &v2).unwrap();
         assert_eq!(t1.server_name, "srv");
         assert_eq!(t1.tool_name, "echo");
-        assert!(t1.input_schema.contains_key("type"));
-        assert_eq!(t1.description.as_deref(), Some("d"));
+        assert!(t1.tool.input_schema.contains_key("type"));
+        assert_eq!(t1.tool.description.as_deref(), Some("d"));
         assert_eq!(t2.tool_name, "ping");
-        assert!(t2.input_schema.contains_key("type"));
+        assert!(t2.tool.input_schema.contains_key("type"));
+    }
+
+    #[test]
+    fn aggregated_tool_keeps_upstream_metadata() {
+        let v = serde_json::json!({
+            "name": "delete",
+            "title": "Delete file",
+            "description": "d",
+            "inputSchema": { "type": "object" },
+            "outputSchema": { "type": "object", "properties": { "ok": { "type": "boolean" } } },
+            "annotations": { "readOnlyHint": false, "destructiveHint": true },
+        });
+        let t = super::to_aggregated_tool("srv", &v).unwrap();
+        assert_eq!(t.tool.title.as_deref(), Some("Delete file"));
+        assert!(t.tool.output_schema.is_some());
+        let annotations = t.tool.annotations.expect("annotations");
+        assert_eq!(annotations.destructive_hint, Some(true));
+        assert_eq!(annotations.read_only_hint, Some(false));
     }
 
     #[test]
@@ -546,6 +2171,148 @@ mod tests {
         assert_eq!(fallback, "tool");
     }
 
+    #[test]
+    fn next_alias_suffixes_duplicates() {
+        let mut counts = HashMap::new();
+        assert_eq!(super::next_alias(&mut counts, "a__b".into()), "a__b");
+        assert_eq!(super::next_alias(&mut counts, "a__b".into()), "a__b-2");
+        assert_eq!(super::next_alias(&mut counts, "a__c".into()), "a__c");
+    }
+
+    #[test]
+    fn custom_tool_names_reject_collisions_regardless_of_order() {
+        use crate::config::{ToolOverride, ToolOverrides};
+        let tool = |server: &str, name: &str| {
+            super::to_aggregated_tool(server, &serde_json::json!({ "name": name })).unwrap()
+        };
+        let named = |n: &str| ToolOverride {
+            name: Some(n.into()),
+            ..Default::default()
+        };
+        let mut overrides = ToolOverrides::default();
+        overrides
+            .0
+            .entry("a".into())
+            .or_default()
+            .insert("find".into(), named("search"));
+        overrides
+            .0
+            .entry("b".into())
+            .or_default()
+            .insert("find".into(), named("search"));
+        overrides
+            .0
+            .entry("c".into())
+            .or_default()
+            .insert("grep".into(), named("d__ls"));
+        overrides
+            .0
+            .entry("c".into())
+            .or_default()
+            .insert("cat".into(), named("read"));
+
+        let mut records = vec![tool("a", "find"), tool("b", "find"), tool("c", "grep")];
+        records.extend([tool("c", "cat"), tool("d", "ls")]);
+        let plan = |records: &[super::AggregatedTool]| {
+            crate::config::plan_tool_names(&overrides, &super::generated_aliases(records))
+        };
+        let (names, errors) = plan(&records);
+        records.reverse();
+        let (names_rev, errors_rev) = plan(&records);
+
+        assert_eq!(names, names_rev);
+        assert_eq!(errors, errors_rev);
+        assert_eq!(names.len(), 1);
+        assert_eq!(names[&("c".to_string(), "cat".to_string())], "read");
+        assert_eq!(
+            errors,
+            vec![
+                "tool name 'd__ls' for c::grep collides with d::ls".to_string(),
+                "tool name 'search' is used by more than one tool: a::find, b::find".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn prefixes_default_to_server_name_and_allow_flat() {
+        let cfg = |name: &str, prefix: Option<&str>| MCPServerConfig {
+            name: name.into(),
+            description: String::new(),
+            transport: crate::config::TransportType::Stdio,
+            command: String::new(),
+            args: vec![],
+            env: Default::default(),
+            endpoint: String::new(),
+            headers: Default::default(),
+            enabled: true,
+            prefix: prefix.map(|p| p.to_string()),
+            limits: None,
+        };
+        let prefixes = super::exposure_prefixes(&[
+            cfg("github", Some("gh")),
+            cfg("docs", Some("")),
+            cfg("Fs Server", None),
+        ]);
+        let name =
+            |server: &str, tool: &str| super::build_sanitized_tool_name(&prefixes[server], tool);
+        assert_eq!(name("github", "search"), "gh__search");
+        assert_eq!(name("docs", "search"), "search");
+        assert_eq!(name("Fs Server", "read"), "Fs_Server__read");
+    }
+
+    #[test]
+    fn cross_server_conflicts_name_every_owner() {
+        let conflicts = super::cross_server_conflicts([
+            ("b", "search"),
+            ("a", "search"),
+            ("a", "a__only"),
+            ("c", "search"),
+        ]);
+        assert_eq!(
+            conflicts["a"],
+            vec!["'search' is also exposed by b, c".to_string()]
+        );
+        assert_eq!(
+            conflicts["c"],
+            vec!["'search' is also exposed by a, b".to_string()]
+        );
+        assert_eq!(conflicts.len(), 3);
+    }
+
+    #[test]
+    fn tools_cursor_round_trips() {
+        let cursor = super::encode_tools_cursor("snap-1", 20);
+        assert_eq!(super::decode_tools_cursor(&cursor), Some(("snap-1", 20)));
+        assert_eq!(super::decode_tools_cursor("snap-1"), None);
+        assert_eq!(super::decode_tools_cursor(":3"), None);
+        assert_eq!(super::decode_tools_cursor("snap-1:x"), None);
+    }
+
+    #[test]
+    fn page_of_splits_items_into_pages() {
+        let items = vec![1, 2, 3, 4, 5];
+        assert_eq!(super::page_of(&items, 0, Some(2)), (vec![1, 2], Some(2)));
+        assert_eq!(super::page_of(&items, 2, Some(2)), (vec![3, 4], Some(4)));
+        assert_eq!(super::page_of(&items, 4, Some(2)), (vec![5], None));
+        assert_eq!(super::page_of(&items, 9, Some(2)), (vec![], None));
+        assert_eq!(super::page_of(&items, 0, None), (items.clone(), None));
+    }
+
+    #[test]
+    fn namespaced_uri_round_trips() {
+        let namespaced = super::build_namespaced_uri("File System", "file:///tmp/a.txt");
+        assert_eq!(namespaced, "bouncer://File_System/file:///tmp/a.txt");
+        let (server, uri) = super::split_namespaced_uri(&namespaced).unwrap();
+        assert_eq!(server, "File_System");
+        assert_eq!(uri, "file:///tmp/a.txt");
+
+        let templated = super::build_namespaced_uri("", "db://{table}");
+        assert_eq!(templated, "bouncer://server/db://{table}");
+
+        assert!(super::split_namespaced_uri("file:///tmp/a.txt").is_none());
+        assert!(super::split_namespaced_uri("bouncer://fs").is_none());
+    }
+
     #[tokio::test]
     async fn stop_server_aborts_task() {
         let emitter = crate::events::BufferingEventEmitter::default();
@@ -618,6 +2385,249 @@ mod tests {
     }
 }
 
+type EndpointService<E, CP, L> = StreamableHttpService<
+    BouncerService<E, CP, L>,
+    InterceptingSessionManager<LocalSessionManager, E, L>,
+>;
+
+// One streamable HTTP endpoint. Each gets its own sessions, alias maps and
+// tools/list snapshots so profiles never route through each other's names.
+fn build_mcp_service<E, CP, L>(
+    emitter: E,
+    cp: CP,
+    logger: L,
+    profile: Option<String>,
+) -> EndpointService<E, CP, L>
+where
+    E: EventEmitter + Clone + Send + Sync + 'static,
+    CP: ConfigProvider + Clone + Send + Sync + 'static,
+    L: RpcEventPublisher,
+{
+    let session_manager = Arc::new(InterceptingSessionManager::new(
+        LocalSessionManager::default(),
+        emitter.clone(),
+        logger.clone(),
+    ));
+    let tool_aliases: Arc<RwLock<HashMap<String, (String, String)>>> =
+        Arc::new(RwLock::new(HashMap::new()));
+    let resource_aliases: Arc<RwLock<HashMap<String, (String, String)>>> =
+        Arc::new(RwLock::new(HashMap::new()));
+    let prompt_aliases: Arc<RwLock<HashMap<String, (String, String)>>> =
+        Arc::new(RwLock::new(HashMap::new()));
+    let tool_snapshots: Arc<RwLock<VecDeque<ToolsSnapshot>>> =
+        Arc::new(RwLock::new(VecDeque::new()));
+    StreamableHttpService::new(
+        move || {
+            Ok(BouncerService {
+                emitter: emitter.clone(),
+                cp: cp.clone(),
+                logger: logger.clone(),
+                tool_aliases: tool_aliases.clone(),
+                resource_aliases: resource_aliases.clone(),
+                prompt_aliases: prompt_aliases.clone(),
+                tool_snapshots: tool_snapshots.clone(),
+                listed_aliases: Arc::new(RwLock::new(None)),
+                profile: profile.clone(),
+                session: NEXT_SESSION.fetch_add(1, std::sync::atomic::Ordering::Relaxed),
+            })
+        },
+        session_manager,
+        StreamableHttpServerConfig {
+            stateful_mode: true,
+            sse_keep_alive: Some(std::time::Duration::from_secs(15)),
+        },
+    )
+}
+
+// Profile endpoints, created the first time each profile is requested.
+struct ProfileServices<E, CP, L>
+where
+    E: EventEmitter + Clone + Send + Sync + 'static,
+    CP: ConfigProvider + Clone + Send + Sync + 'static,
+    L: RpcEventPublisher,
+{
+    emitter: E,
+    cp: CP,
+    logger: L,
+    services: Arc<std::sync::Mutex<HashMap<String, EndpointService<E, CP, L>>>>,
+}
+
+impl<E, CP, L> Clone for ProfileServices<E, CP, L>
+where
+    E: EventEmitter + Clone + Send + Sync + 'static,
+    CP: ConfigProvider + Clone + Send + Sync + 'static,
+    L: RpcEventPublisher,
+{
+    fn clone(&self) -> Self {
+        Self {
+            emitter: self.emitter.clone(),
+            cp: self.cp.clone(),
+            logger: self.logger.clone(),
+            services: self.services.clone(),
+        }
+    }
+}
+
+// Routes /mcp to the full tool set and /mcp/<profile> to a configured profile.
+fn mcp_router<E, CP, L>(emitter: E, cp: CP, logger: L) -> Router
+where
+    E: EventEmitter + Clone + Send + Sync + 'static,
+    CP: ConfigProvider + Clone + Send + Sync + 'static,
+    L: RpcEventPublisher,
+{
+    let default = build_mcp_service(emitter.clone(), cp.clone(), logger.clone(), None);
+    let profiles = ProfileServices {
+        emitter,
+        cp,
+        logger,
+        services: Arc::new(std::sync::Mutex::new(HashMap::new())),
+    };
+    Router::new()
+        .route_service("/mcp", default)
+        .route(
+            "/mcp/{profile}",
+            axum::routing::any(serve_profile::<E, CP, L>),
+        )
+        .with_state(profiles)
+}
+
+async fn serve_profile<E, CP, L>(
+    axum::extract::State(profiles): axum::extract::State<ProfileServices<E, CP, L>>,
+    axum::extract::Path(profile): axum::extract::Path<String>,
+    request: axum::extract::Request,
+) -> axum::response::Response
+where
+    E: EventEmitter + Clone + Send + Sync + 'static,
+    CP: ConfigProvider + Clone + Send + Sync + 'static,
+    L: RpcEventPublisher,
+{
+    use axum::response::IntoResponse;
+    let known = load_settings_with(&profiles.cp)
+        .profiles
+        .iter()
+        .any(|p| p.name == profile);
+    if !known {
+        return (
+            axum::http::StatusCode::NOT_FOUND,
+            format!("unknown profile: {profile}"),
+        )
+            .into_response();
+    }
+    let service = profiles
+        .services
+        .lock()
+        .unwrap()
+        .entry(profile.clone())
+        .or_insert_with(|| {
+            build_mcp_service(
+                profiles.emitter.clone(),
+                profiles.cp.clone(),
+                profiles.logger.clone(),
+                Some(profile),
+            )
+        })
+        .clone();
+    service.handle(request).await.map(axum::body::Body::new)
+}
+
+#[derive(Clone)]
+struct GuardState<E, CP, L> {
+    emitter: E,
+    cp: CP,
+    logger: L,
+}
+
+// Rejects requests whose Host or Origin is not this machine (or allow-listed
+// in settings), logging each rejection with the offending headers.
+async fn validate_host_and_origin<E, CP, L>(
+    axum::extract::State(state): axum::extract::State<GuardState<E, CP, L>>,
+    request: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> axum::response::Response
+where
+    E: EventEmitter + Clone + Send + Sync + 'static,
+    CP: ConfigProvider + Clone + Send + Sync + 'static,
+    L: RpcEventPublisher,
+{
+    use axum::http::{StatusCode, header};
+    use axum::response::IntoResponse;
+    let settings = load_settings_with(&state.cp);
+    let header_str = |name: header::HeaderName| {
+        request
+            .headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_string)
+    };
+    let host = header_str(header::HOST);
+    let origin = header_str(header::ORIGIN);
+    let reason = if !host
+        .as_deref()
+        .is_some_and(|h| crate::origin_guard::host_allowed(h, &settings.allowed_hosts))
+    {
+        "host not allowed"
+    } else if !crate::origin_guard::origin_allowed(origin.as_deref(), &settings.allowed_origins) {
+        "origin not allowed"
+    } else {
+        return next.run(request).await;
+    };
+    tracing::warn!(target = "server", host = ?host, origin = ?origin, reason, "http_request_rejected");
+    let mut event = crate::logging::Event::new("http/rejected", "anon");
+    event.origin = Some("external".into());
+    event.ok = false;
+    event.error = Some(reason.to_string());
+    event.request_json = Some(serde_json::json!({
+        "method": request.method().as_str(),
+        "path": request.uri().path(),
+        "host": host,
+        "origin": origin,
+    }));
+    state.logger.log_and_emit(&state.emitter, event);
+    (StatusCode::FORBIDDEN, reason).into_response()
+}
+
+// Other local processes (including browser pages) can reach the TCP listener,
+// so when enabled every request must carry an API token. The token's label is
+// passed on to the session as its identity.
+async fn require_api_token<CP>(
+    axum::extract::State(cp): axum::extract::State<CP>,
+    mut request: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> axum::response::Response
+where
+    CP: ConfigProvider + Clone + Send + Sync + 'static,
+{
+    use axum::http::{StatusCode, header};
+    use axum::response::IntoResponse;
+    if !load_settings_with(&cp).require_api_token {
+        return next.run(request).await;
+    }
+    let presented = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.split_once(' '))
+        .filter(|(scheme, _)| scheme.eq_ignore_ascii_case("Bearer"))
+        .map(|(_, token)| token.trim());
+    match presented.and_then(|token| crate::api_tokens::verify(&cp, token)) {
+        Some(info) => {
+            request
+                .extensions_mut()
+                .insert(crate::api_tokens::TokenIdentity(info.label));
+            next.run(request).await
+        }
+        None => {
+            tracing::warn!(target = "server", path = %request.uri().path(), "api_token_rejected");
+            (
+                StatusCode::UNAUTHORIZED,
+                [(header::WWW_AUTHENTICATE, "Bearer")],
+                "missing or invalid API token",
+            )
+                .into_response()
+        }
+    }
+}
+
 pub async fn start_server<E, CP, L>(
     emitter: E,
     cp: CP,
@@ -669,38 +2679,22 @@ where
     // Initialize logging (idempotent)
     let settings = load_settings_with(&cp);
     logger.init_with(&cp, &settings);
-    let session_manager = Arc::new(InterceptingSessionManager::new(
-        LocalSessionManager::default(),
-        emitter.clone(),
-        logger.clone(),
-    ));
-    let tool_aliases: Arc<RwLock<HashMap<String, (String, String)>>> =
-        Arc::new(RwLock::new(HashMap::new()));
-    let service: StreamableHttpService<
-        BouncerService<E, CP, L>,
-        InterceptingSessionManager<LocalSessionManager, E, L>,
-    > = StreamableHttpService::new(
-        {
-            let emitter = emitter.clone();
-            let cp = cp.clone();
-            let logger = logger.clone();
-            let tool_aliases = tool_aliases.clone();
-            move || {
-                Ok(BouncerService {
-                    emitter: emitter.clone(),
-                    cp: cp.clone(),
-                    logger: logger.clone(),
-                    tool_aliases: tool_aliases.clone(),
-                })
-            }
-        },
-        session_manager,
-        StreamableHttpServerConfig {
-            stateful_mode: true,
-            sse_keep_alive: Some(std::time::Duration::from_secs(15)),
-        },
-    );
-    let router = Router::new().nest_service("/mcp", service);
+    let guard = GuardState {
+        emitter: emitter.clone(),
+        cp: cp.clone(),
+        logger: logger.clone(),
+    };
+    // Host/Origin validation is the outer layer so rebinding attempts are
+    // refused before any token check.
+    let router = mcp_router(emitter, cp.clone(), logger)
+        .layer(axum::middleware::from_fn_with_state(
+            cp,
+            require_api_token::<CP>,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            guard,
+            validate_host_and_origin::<E, CP, L>,
+        ));
     let listener = tokio::net::TcpListener::bind(addr)
         .await
         .map_err(|e| e.to_string())?;
@@ -729,38 +2723,7 @@ where
     // Initialize logging (idempotent)
     let settings = load_settings_with(&cp);
     logger.init_with(&cp, &settings);
-    let session_manager = Arc::new(InterceptingSessionManager::new(
-        LocalSessionManager::default(),
-        emitter.clone(),
-        logger.clone(),
-    ));
-    let tool_aliases: Arc<RwLock<HashMap<String, (String, String)>>> =
-        Arc::new(RwLock::new(HashMap::new()));
-    let service: StreamableHttpService<
-        BouncerService<E, CP, L>,
-        InterceptingSessionManager<LocalSessionManager, E, L>,
-    > = StreamableHttpService::new(
-        {
-            let emitter = emitter.clone();
-            let cp = cp.clone();
-            let logger = logger.clone();
-            let tool_aliases = tool_aliases.clone();
-            move || {
-                Ok(BouncerService {
-                    emitter: emitter.clone(),
-                    cp: cp.clone(),
-                    logger: logger.clone(),
-                    tool_aliases: tool_aliases.clone(),
-                })
-            }
-        },
-        session_manager,
-        StreamableHttpServerConfig {
-            stateful_mode: true,
-            sse_keep_alive: Some(std::time::Duration::from_secs(15)),
-        },
-    );
-    let router = Router::new().nest_service("/mcp", service);
+    let router = mcp_router(emitter, cp, logger);
 
     // Remove existing socket file if it exists
     if std::fs::metadata(&path).is_ok() {
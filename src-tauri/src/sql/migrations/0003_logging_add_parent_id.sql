@@ -0,0 +1 @@
+ALTER TABLE rpc_events ADD COLUMN parent_id TEXT;
@@ -0,0 +1 @@
+ALTER TABLE rpc_events ADD COLUMN policy TEXT;
@@ -0,0 +1,2 @@
+ALTER TABLE sessions ADD COLUMN identity TEXT;
+ALTER TABLE rpc_events ADD COLUMN identity TEXT;
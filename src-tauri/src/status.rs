@@ -3,7 +3,7 @@ use std::collections::HashMap;
 use crate::config::{
     ClientConnectionState, ClientStatus, ConfigProvider, OsConfigProvider, load_settings_with,
 };
-use crate::overlay;
+use crate::{limits, overlay};
 
 pub async fn compute_client_status_map_with(
     cp: &dyn ConfigProvider,
@@ -21,6 +21,8 @@ pub async fn compute_client_status_map_with(
                 last_error: None,
                 authorization_required: false,
                 oauth_authenticated: false,
+                name_conflicts: Vec::new(),
+                usage: limits::usage(&name),
             },
         );
     }
@@ -32,6 +34,7 @@ pub async fn compute_client_status_map_with(
             cs.authorization_required = entry.authorization_required;
             cs.oauth_authenticated = entry.oauth_authenticated;
             cs.tools = entry.tools;
+            cs.name_conflicts = entry.name_conflicts;
         }
     }
     map
@@ -98,6 +101,8 @@ mod tests {
             endpoint: "http://127.0.0.1".into(),
             headers: Default::default(),
             enabled: true,
+            prefix: None,
+            limits: None,
         });
         save_settings_with(&cp, &s).unwrap();
 
@@ -129,6 +134,8 @@ mod tests {
             endpoint: "http://127.0.0.1".into(),
             headers: Default::default(),
             enabled: true,
+            prefix: None,
+            limits: None,
         });
         save_settings_with(&cp, &s).unwrap();
 
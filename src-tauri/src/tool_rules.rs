@@ -0,0 +1,241 @@
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use rmcp::model::JsonObject;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::config::{ConfigProvider, glob_match};
+
+// Argument-aware rules for tools/call, kept in tool_rules.json next to
+// settings.json. Rules are checked in order and the first match decides.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ToolRules {
+    // Log would-be denials but forward every call.
+    #[serde(default)]
+    pub dry_run: bool,
+    #[serde(default)]
+    pub rules: Vec<ToolRule>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolRule {
+    pub name: String,
+    // Server and upstream tool name patterns; `*` is a wildcard.
+    #[serde(default = "any")]
+    pub server: String,
+    #[serde(default = "any")]
+    pub tool: String,
+    // Every condition must hold for the rule to match.
+    #[serde(default)]
+    pub when: Vec<ArgCondition>,
+    #[serde(default)]
+    pub action: RuleAction,
+    // Dry-run this rule only, even when the file is enforced.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+fn any() -> String {
+    "*".into()
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleAction {
+    Allow,
+    #[default]
+    Deny,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArgCondition {
+    // Argument name; nested values use dots, e.g. `options.mode`.
+    pub arg: String,
+    #[serde(flatten)]
+    pub test: ArgTest,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArgTest {
+    Equals(Value),
+    // Case-insensitive substring of the value (non-strings compare as JSON).
+    Contains(String),
+    Glob(String),
+    // Path tests; `~` is expanded and `.`/`..` are resolved first. Relative
+    // paths never count as under a directory.
+    Under(String),
+    NotUnder(String),
+}
+
+// The rule that decided a call, and whether it blocks it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Verdict {
+    pub rule: String,
+    pub action: RuleAction,
+    pub dry_run: bool,
+}
+
+impl Verdict {
+    pub fn blocks(&self) -> bool {
+        self.action == RuleAction::Deny && !self.dry_run
+    }
+}
+
+pub fn tool_rules_path(cp: &dyn ConfigProvider) -> PathBuf {
+    cp.base_dir().join("tool_rules.json")
+}
+
+// A missing file means no rules; a file that does not parse is an error so
+// calls are not forwarded with the rules silently dropped.
+pub fn load_tool_rules_with(cp: &dyn ConfigProvider) -> Result<ToolRules, String> {
+    match fs::read_to_string(tool_rules_path(cp)) {
+        Ok(content) => serde_json::from_str(&content).map_err(|e| format!("tool_rules.json: {e}")),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(ToolRules::default()),
+        Err(e) => Err(format!("read tool_rules.json: {e}")),
+    }
+}
+
+impl ToolRules {
+    pub fn evaluate(
+        &self,
+        server_name: &str,
+        tool_name: &str,
+        arguments: Option<&JsonObject>,
+    ) -> Option<Verdict> {
+        let empty = JsonObject::new();
+        let args = arguments.unwrap_or(&empty);
+        self.rules
+            .iter()
+            .find(|r| {
+                glob_match(&r.server, server_name)
+                    && glob_match(&r.tool, tool_name)
+                    && r.when.iter().all(|c| c.holds(args))
+            })
+            .map(|r| Verdict {
+                rule: r.name.clone(),
+                action: r.action,
+                dry_run: self.dry_run || r.dry_run,
+            })
+    }
+}
+
+impl ArgCondition {
+    // Conditions on an absent argument never hold.
+    fn holds(&self, args: &JsonObject) -> bool {
+        let mut parts = self.arg.split('.');
+        let first = parts.next().unwrap_or_default();
+        let value = parts.try_fold(args.get(first), |cur, seg| Some(cur?.get(seg)));
+        let Some(value) = value.flatten() else {
+            return false;
+        };
+        let text = match value {
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        };
+        match &self.test {
+            ArgTest::Equals(expected) => value == expected,
+            ArgTest::Contains(needle) => text.to_lowercase().contains(&needle.to_lowercase()),
+            ArgTest::Glob(pattern) => glob_match(pattern, &text),
+            ArgTest::Under(dir) => is_under(&text, dir),
+            ArgTest::NotUnder(dir) => !is_under(&text, dir),
+        }
+    }
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match path.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => dirs::home_dir()
+            .unwrap_or_default()
+            .join(rest.trim_start_matches('/')),
+        _ => PathBuf::from(path),
+    }
+}
+
+// Lexically resolves `.` and `..`; symlinks are not followed.
+fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for comp in path.components() {
+        match comp {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                out.pop();
+            }
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+fn is_under(path: &str, dir: &str) -> bool {
+    let path = normalize(&expand_home(path));
+    path.is_absolute() && path.starts_with(normalize(&expand_home(dir)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn rules(value: Value) -> ToolRules {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn args(value: Value) -> JsonObject {
+        value.as_object().cloned().unwrap()
+    }
+
+    #[test]
+    fn denies_writes_outside_a_directory() {
+        let r = rules(json!({
+            "rules": [{
+                "name": "fs-in-work",
+                "server": "fs",
+                "tool": "write_file",
+                "when": [{ "arg": "path", "not_under": "/home/me/work" }]
+            }]
+        }));
+        let inside = args(json!({ "path": "/home/me/work/a.txt" }));
+        let escape = args(json!({ "path": "/home/me/work/../.ssh/config" }));
+        let relative = args(json!({ "path": "notes.txt" }));
+        assert!(r.evaluate("fs", "write_file", Some(&inside)).is_none());
+        let verdict = r.evaluate("fs", "write_file", Some(&escape)).unwrap();
+        assert_eq!(verdict.rule, "fs-in-work");
+        assert!(verdict.blocks());
+        assert!(r.evaluate("fs", "write_file", Some(&relative)).is_some());
+        assert!(r.evaluate("fs", "read_file", Some(&escape)).is_none());
+        assert!(r.evaluate("fs", "write_file", None).is_none());
+    }
+
+    #[test]
+    fn first_match_wins_and_dry_run_never_blocks() {
+        let r = rules(json!({
+            "dry_run": true,
+            "rules": [
+                { "name": "allow-temp", "when": [{ "arg": "sql", "contains": "drop table tmp_" }], "action": "allow" },
+                { "name": "no-drop", "server": "db", "when": [{ "arg": "sql", "contains": "DROP" }] }
+            ]
+        }));
+        let temp = args(json!({ "sql": "DROP TABLE tmp_x" }));
+        let real = args(json!({ "sql": "drop table users" }));
+        assert_eq!(
+            r.evaluate("db", "query", Some(&temp)).unwrap().rule,
+            "allow-temp"
+        );
+        let verdict = r.evaluate("db", "query", Some(&real)).unwrap();
+        assert_eq!(verdict.rule, "no-drop");
+        assert!(verdict.dry_run && !verdict.blocks());
+    }
+
+    #[test]
+    fn nested_arguments_and_equals() {
+        let r = rules(json!({
+            "rules": [{ "name": "no-force", "when": [{ "arg": "options.force", "equals": true }] }]
+        }));
+        let forced = args(json!({ "options": { "force": true } }));
+        let gentle = args(json!({ "options": { "force": false } }));
+        assert!(r.evaluate("git", "push", Some(&forced)).is_some());
+        assert!(r.evaluate("git", "push", Some(&gentle)).is_none());
+    }
+}
@@ -0,0 +1,179 @@
+use rmcp::model::{JsonObject, Tool};
+use serde_json::{Value, json};
+
+// Synthetic tools exposed instead of the full list when tool search is enabled.
+pub const SEARCH_TOOLS: &str = "search_tools";
+pub const CALL_TOOL: &str = "call_tool";
+
+const DEFAULT_LIMIT: usize = 10;
+const MAX_LIMIT: usize = 50;
+
+fn schema(v: Value) -> JsonObject {
+    serde_json::from_value(v).unwrap_or_default()
+}
+
+pub fn meta_tools() -> Vec<Tool> {
+    vec![
+        Tool::new(
+            SEARCH_TOOLS,
+            "Search the available tools by keyword. Returns matching tool names, descriptions \
+             and input schemas; invoke a result with call_tool.",
+            schema(json!({
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string", "description": "Keywords describing the task" },
+                    "limit": {
+                        "type": "integer",
+                        "minimum": 1,
+                        "maximum": MAX_LIMIT,
+                        "description": "Maximum number of results (default 10)"
+                    }
+                },
+                "required": ["query"]
+            })),
+        ),
+        Tool::new(
+            CALL_TOOL,
+            "Call a tool found with search_tools by its exact name.",
+            schema(json!({
+                "type": "object",
+                "properties": {
+                    "name": { "type": "string", "description": "Tool name from search_tools" },
+                    "arguments": { "type": "object", "description": "Arguments for the tool" }
+                },
+                "required": ["name"]
+            })),
+        ),
+    ]
+}
+
+// Parses search_tools arguments into (query, limit).
+pub fn parse_search(args: Option<&JsonObject>) -> Result<(String, usize), String> {
+    let query = args
+        .and_then(|a| a.get("query"))
+        .and_then(|q| q.as_str())
+        .ok_or_else(|| "search_tools requires a string 'query'".to_string())?;
+    let limit = args
+        .and_then(|a| a.get("limit"))
+        .and_then(|l| l.as_u64())
+        .map(|l| (l as usize).clamp(1, MAX_LIMIT))
+        .unwrap_or(DEFAULT_LIMIT);
+    Ok((query.to_string(), limit))
+}
+
+// Parses call_tool arguments into the target tool name and its arguments.
+pub fn parse_call(args: Option<&JsonObject>) -> Result<(String, Option<JsonObject>), String> {
+    let name = args
+        .and_then(|a| a.get("name"))
+        .and_then(|n| n.as_str())
+        .filter(|n| !n.is_empty())
+        .ok_or_else(|| "call_tool requires a string 'name'".to_string())?;
+    let arguments = match args.and_then(|a| a.get("arguments")) {
+        None | Some(Value::Null) => None,
+        Some(Value::Object(map)) => Some(map.clone()),
+        Some(_) => return Err("call_tool 'arguments' must be an object".into()),
+    };
+    Ok((name.to_string(), arguments))
+}
+
+// Ranks tools against the query: every keyword must appear in the name,
+// title or description, either as a substring or (for names) as a fuzzy
+// subsequence. Name hits weigh more than description hits.
+pub fn search<'a>(tools: &'a [Tool], query: &str, limit: usize) -> Vec<&'a Tool> {
+    let terms: Vec<String> = query
+        .split(|c: char| c.is_whitespace() || c == '_' || c == '-')
+        .filter(|t| !t.is_empty())
+        .map(|t| t.to_lowercase())
+        .collect();
+    let mut scored: Vec<(u32, &Tool)> = tools
+        .iter()
+        .filter_map(|tool| score(tool, &terms).map(|s| (s, tool)))
+        .collect();
+    scored.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.name.cmp(&b.1.name)));
+    scored.into_iter().take(limit).map(|(_, t)| t).collect()
+}
+
+fn score(tool: &Tool, terms: &[String]) -> Option<u32> {
+    let name = tool.name.to_lowercase();
+    let text = format!(
+        "{} {}",
+        tool.title.as_deref().unwrap_or_default(),
+        tool.description.as_deref().unwrap_or_default()
+    )
+    .to_lowercase();
+    let mut total = 0;
+    for term in terms {
+        total += if name.contains(term.as_str()) {
+            3
+        } else if text.contains(term.as_str()) {
+            2
+        } else if is_subsequence(term, &name) {
+            1
+        } else {
+            return None;
+        };
+    }
+    Some(total)
+}
+
+fn is_subsequence(needle: &str, haystack: &str) -> bool {
+    let mut rest = haystack.chars();
+    needle.chars().all(|c| rest.any(|h| h == c))
+}
+
+// Result body for search_tools: enough for the model to pick and call a tool.
+pub fn describe(tools: &[&Tool]) -> Value {
+    Value::Array(
+        tools
+            .iter()
+            .map(|t| {
+                json!({
+                    "name": t.name,
+                    "description": t.description,
+                    "inputSchema": t.input_schema,
+                })
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool(name: &str, description: &str) -> Tool {
+        Tool::new(name.to_string(), description.to_string(), JsonObject::new())
+    }
+
+    #[test]
+    fn ranks_name_hits_first_and_requires_every_term() {
+        let tools = vec![
+            tool("github__create_issue", "Open a new issue"),
+            tool("jira__new_ticket", "Create an issue in Jira"),
+            tool("fs__read_file", "Read a file"),
+        ];
+        let hits = search(&tools, "create issue", 10);
+        let names: Vec<&str> = hits.iter().map(|t| t.name.as_ref()).collect();
+        assert_eq!(names, vec!["github__create_issue", "jira__new_ticket"]);
+        assert!(search(&tools, "issue delete", 10).is_empty());
+    }
+
+    #[test]
+    fn fuzzy_matches_names_and_respects_limit() {
+        let tools = vec![tool("fs__read_file", ""), tool("fs__write_file", "")];
+        let hits = search(&tools, "rdfile", 1);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].name, "fs__read_file");
+    }
+
+    #[test]
+    fn parses_call_arguments() {
+        let args = schema(json!({ "name": "fs__read_file", "arguments": { "path": "/a" } }));
+        let (name, inner) = parse_call(Some(&args)).unwrap();
+        assert_eq!(name, "fs__read_file");
+        assert_eq!(inner.unwrap()["path"], "/a");
+        assert!(parse_call(None).is_err());
+        let bad = schema(json!({ "name": "x", "arguments": 3 }));
+        assert!(parse_call(Some(&bad)).is_err());
+    }
+}
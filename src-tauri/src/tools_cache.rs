@@ -60,6 +60,8 @@ mod tests {
             name: "srv::tool".to_string(),
             description: Some("desc".to_string()),
             input_schema: Some(serde_json::json!({"type":"object"})),
+            annotations: Some(serde_json::json!({"readOnlyHint": true})),
+            ..Default::default()
         }];
         set("srv", items.clone()).await;
         let out = get("srv").await.unwrap();
@@ -67,6 +69,7 @@ mod tests {
         assert_eq!(out[0].name, items[0].name);
         assert_eq!(out[0].description, items[0].description);
         assert_eq!(out[0].input_schema, items[0].input_schema);
+        assert_eq!(out[0].annotations, items[0].annotations);
     }
 
     #[tokio::test]
@@ -98,13 +101,11 @@ mod tests {
         let list = vec![
             ToolInfo {
                 name: "x".into(),
-                description: None,
-                input_schema: None,
+                ..Default::default()
             },
             ToolInfo {
                 name: "y".into(),
-                description: None,
-                input_schema: None,
+                ..Default::default()
             },
         ];
         let filtered = filter_enabled_with(&cp, "srv", list);
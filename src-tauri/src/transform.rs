@@ -0,0 +1,204 @@
+use std::collections::HashSet;
+
+use rmcp::model::{Implementation, JsonObject, Tool};
+use serde_json::Value;
+
+use crate::config::ToolOverride;
+
+// Values available to {{...}} placeholders in injected arguments.
+#[derive(Debug, Clone, Default)]
+pub struct TemplateContext {
+    pub client_name: Option<String>,
+    pub client_version: Option<String>,
+}
+
+impl TemplateContext {
+    pub fn from_client(info: Option<&Implementation>) -> Self {
+        Self {
+            client_name: info.map(|i| i.name.clone()),
+            client_version: info.map(|i| i.version.clone()),
+        }
+    }
+
+    fn lookup(&self, key: &str) -> Result<Value, String> {
+        match key {
+            "client.name" => Ok(self.client_name.clone().unwrap_or_default().into()),
+            "client.version" => Ok(self.client_version.clone().unwrap_or_default().into()),
+            _ => lookup_env(key),
+        }
+    }
+}
+
+// Resolves `env.NAME` placeholders; anything else is unknown.
+pub fn lookup_env(key: &str) -> Result<Value, String> {
+    match key.strip_prefix("env.") {
+        Some(var) => std::env::var(var)
+            .map(Value::String)
+            .map_err(|_| format!("env var {var} is not set")),
+        None => Err(format!("unknown placeholder {{{{{key}}}}}")),
+    }
+}
+
+fn hidden_keys(ov: &ToolOverride) -> HashSet<&str> {
+    ov.hidden
+        .iter()
+        .map(|k| k.as_str())
+        .chain(ov.inject.keys().map(|k| k.as_str()))
+        .collect()
+}
+
+// Removes hidden and injected properties from the schema the model sees.
+pub fn apply_to_tool(tool: &mut Tool, ov: &ToolOverride) {
+    let hidden = hidden_keys(ov);
+    if hidden.is_empty() {
+        return;
+    }
+    let mut schema = (*tool.input_schema).clone();
+    if let Some(Value::Object(props)) = schema.get_mut("properties") {
+        props.retain(|k, _| !hidden.contains(k.as_str()));
+    }
+    if let Some(Value::Array(required)) = schema.get_mut("required") {
+        required.retain(|k| !k.as_str().is_some_and(|k| hidden.contains(k)));
+    }
+    tool.input_schema = std::sync::Arc::new(schema);
+}
+
+// Drops any hidden argument the caller supplied and pins the injected ones.
+pub fn apply_to_arguments(
+    arguments: Option<JsonObject>,
+    ov: &ToolOverride,
+    ctx: &TemplateContext,
+) -> Result<Option<JsonObject>, String> {
+    let hidden = hidden_keys(ov);
+    if hidden.is_empty() {
+        return Ok(arguments);
+    }
+    let mut args = arguments.unwrap_or_default();
+    args.retain(|k, _| !hidden.contains(k.as_str()));
+    for (key, value) in ov.inject.iter() {
+        args.insert(key.clone(), render(value, &|k| ctx.lookup(k))?);
+    }
+    Ok(Some(args))
+}
+
+type Lookup<'a> = &'a dyn Fn(&str) -> Result<Value, String>;
+
+// Replaces {{key}} placeholders in every string of `value`. A string that is a
+// single placeholder takes the looked-up value as-is, so numbers and objects
+// keep their type; otherwise values are spliced in as text.
+pub fn render(value: &Value, lookup: Lookup<'_>) -> Result<Value, String> {
+    Ok(match value {
+        Value::String(s) => render_str(s, lookup)?,
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|v| render(v, lookup))
+                .collect::<Result<_, _>>()?,
+        ),
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| Ok((k.clone(), render(v, lookup)?)))
+                .collect::<Result<_, String>>()?,
+        ),
+        other => other.clone(),
+    })
+}
+
+fn render_str(template: &str, lookup: Lookup<'_>) -> Result<Value, String> {
+    if let Some(key) = template
+        .strip_prefix("{{")
+        .and_then(|t| t.strip_suffix("}}"))
+        .filter(|k| !k.contains("{{") && !k.contains("}}"))
+    {
+        return lookup(key.trim());
+    }
+    let mut out = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        out.push_str(&rest[..start]);
+        match lookup(rest[start + 2..start + 2 + len].trim())? {
+            Value::String(s) => out.push_str(&s),
+            other => out.push_str(&other.to_string()),
+        }
+        rest = &rest[start + 2 + len + 2..];
+    }
+    out.push_str(rest);
+    Ok(Value::String(out))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn pinned() -> ToolOverride {
+        ToolOverride {
+            hidden: vec!["timeout".into()],
+            inject: [
+                ("database".to_string(), json!("analytics")),
+                ("tag".to_string(), json!("via {{client.name}}")),
+            ]
+            .into_iter()
+            .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn hides_properties_from_schema() {
+        let schema: JsonObject = serde_json::from_value(json!({
+            "type": "object",
+            "properties": {
+                "sql": { "type": "string" },
+                "database": { "type": "string" },
+                "timeout": { "type": "number" }
+            },
+            "required": ["sql", "database"]
+        }))
+        .unwrap();
+        let mut tool = Tool::new("query", "run sql", schema);
+        apply_to_tool(&mut tool, &pinned());
+        assert_eq!(
+            serde_json::Value::Object((*tool.input_schema).clone()),
+            json!({
+                "type": "object",
+                "properties": { "sql": { "type": "string" } },
+                "required": ["sql"]
+            })
+        );
+    }
+
+    #[test]
+    fn pins_injected_arguments_over_caller_values() {
+        let args: JsonObject = serde_json::from_value(json!({
+            "sql": "select 1",
+            "database": "prod",
+            "timeout": 5
+        }))
+        .unwrap();
+        let ctx = TemplateContext {
+            client_name: Some("cli".into()),
+            client_version: None,
+        };
+        let out = apply_to_arguments(Some(args), &pinned(), &ctx)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            Value::Object(out),
+            json!({ "sql": "select 1", "database": "analytics", "tag": "via cli" })
+        );
+    }
+
+    #[test]
+    fn unknown_placeholders_fail() {
+        let ov = ToolOverride {
+            inject: [("x".to_string(), json!("{{nope}}"))].into_iter().collect(),
+            ..Default::default()
+        };
+        let err = apply_to_arguments(None, &ov, &TemplateContext::default()).unwrap_err();
+        assert!(err.contains("nope"));
+    }
+}
@@ -14,6 +14,7 @@ use rmcp::{
     },
 };
 use tokio::sync::{Mutex, RwLock};
+use uuid::Uuid;
 
 use crate::{
     events::{self, EventEmitter},
@@ -101,6 +102,7 @@ where
 {
     state: Arc<InterceptState<E, L>>,
     request_id: RequestId,
+    event_id: Option<Uuid>,
 }
 
 impl<E, L> RequestLogContext<E, L>
@@ -133,11 +135,64 @@ where
         }
     }
 
+    pub async fn set_policy(&self, policy: impl Into<String>) {
+        let mut guard = self.state.pending.lock().await;
+        if let Some(pending) = guard.get_mut(&self.request_id) {
+            pending.event.policy = Some(policy.into());
+        }
+    }
+
+    pub async fn session_id(&self) -> String {
+        self.state.current_session_id().await
+    }
+
     pub async fn log_local_result(&self, result: &ServerResult) {
         self.state
             .log_local_result(self.request_id.clone(), result)
             .await;
     }
+
+    /// Record an event that belongs to this request (relayed progress, forwarded
+    /// cancellation, ...) in the same session, linked through `parent_id`.
+    pub async fn log_linked(
+        &self,
+        method: &str,
+        server_name: Option<String>,
+        request_json: Option<serde_json::Value>,
+    ) {
+        let session_id = self.state.current_session_id().await;
+        let mut event = Event::new(method, session_id);
+        event.origin = Some("external".into());
+        event.server_name = server_name;
+        event.parent_id = self.event_id;
+        event.request_json = request_json;
+        event.ok = true;
+        self.state.logger.log_and_emit(&self.state.emitter, event);
+    }
+
+    /// Record a call the bouncer made on behalf of this request (one step of a
+    /// macro tool, ...) together with its outcome, linked through `parent_id`.
+    pub async fn log_linked_call(
+        &self,
+        method: &str,
+        server_name: Option<String>,
+        request_json: Option<serde_json::Value>,
+        response_json: Option<serde_json::Value>,
+        error: Option<String>,
+        duration_ms: i64,
+    ) {
+        let session_id = self.state.current_session_id().await;
+        let mut event = Event::new(method, session_id);
+        event.origin = Some("external".into());
+        event.server_name = server_name;
+        event.parent_id = self.event_id;
+        event.request_json = request_json;
+        event.response_json = response_json;
+        event.ok = error.is_none();
+        event.error = error;
+        event.duration_ms = Some(duration_ms);
+        self.state.logger.log_and_emit(&self.state.emitter, event);
+    }
 }
 
 struct PendingRequest {
@@ -171,6 +226,25 @@ fn enrich_call_tool(event: &mut Event, result: &ServerResult) {
     }
 }
 
+// Upstream log messages at error level or above are flagged so they stand out
+// on the Logs page next to failed RPCs.
+fn enrich_logging_message(event: &mut Event, params: &mcp::LoggingMessageNotificationParam) {
+    use mcp::LoggingLevel;
+    if matches!(
+        params.level,
+        LoggingLevel::Error
+            | LoggingLevel::Critical
+            | LoggingLevel::Alert
+            | LoggingLevel::Emergency
+    ) {
+        event.ok = false;
+        event.error = Some(match &params.data {
+            serde_json::Value::String(text) => text.clone(),
+            other => other.to_string(),
+        });
+    }
+}
+
 fn client_request_envelope_json(
     request: &ClientRequest,
     id: &RequestId,
@@ -275,14 +349,19 @@ where
         request: &mut ClientRequest,
         id: &RequestId,
     ) {
+        let mut pending = self.build_pending(request, id).await;
+        if let Some(pending) = pending.as_mut() {
+            pending.event.identity = token_identity(request);
+        }
         let context = RequestLogContext {
             state: self.clone(),
             request_id: id.clone(),
+            event_id: pending.as_ref().map(|p| p.event.id),
         };
         // Ensure the context handle is available for downstream request handlers.
-        request.extensions_mut().insert(context.clone());
+        request.extensions_mut().insert(context);
 
-        if let Some(pending) = self.build_pending(request, id).await {
+        if let Some(pending) = pending {
             self.pending.lock().await.insert(id.clone(), pending);
         }
     }
@@ -332,6 +411,9 @@ where
                 pending.event.ok = true;
             }
         }
+        if let ServerResult::InitializeResult(res) = result {
+            pending.event.client_protocol = Some(res.protocol_version.to_string());
+        }
         pending.event.response_json = server_response_envelope_json(result, &id);
         self.logger.log_and_emit(&self.emitter, pending.event);
     }
@@ -394,8 +476,10 @@ where
                         ],
                     )
                     .map(|s| s.to_string());
-                    event.client_protocol = Some("jsonrpc-2.0".into());
                 }
+                // Record the version the session will run at, not just the one requested.
+                event.client_protocol =
+                    Some(crate::protocol::negotiate(&req.params.protocol_version).to_string());
                 let method = method_from_envelope_or_fallback(request_json.as_ref(), "initialize");
                 event.request_json = request_json;
                 event.method = method;
@@ -676,6 +760,9 @@ where
             method_from_envelope_or_fallback(request_json.as_ref(), "notifications/unknown");
         event.request_json = request_json;
         event.method = method;
+        if let ServerNotification::LoggingMessageNotification(log) = &notification {
+            enrich_logging_message(&mut event, &log.params);
+        }
         self.populate_server_details(&mut event).await;
         self.logger.log_and_emit(&self.emitter, event);
     }
@@ -895,6 +982,16 @@ where
     }
 }
 
+// Label of the API token the HTTP request carrying this message was
+// authenticated with; see `server::require_api_token`.
+fn token_identity(request: &ClientRequest) -> Option<String> {
+    request
+        .extensions()
+        .get::<axum::http::request::Parts>()
+        .and_then(|parts| parts.extensions.get::<crate::api_tokens::TokenIdentity>())
+        .map(|identity| identity.0.clone())
+}
+
 fn extract_str<'a>(val: &'a serde_json::Value, paths: &[&str]) -> Option<&'a str> {
     for path in paths {
         let mut cur = val;
@@ -1104,6 +1201,65 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn linked_events_reference_the_request_event() {
+        let emitter = BufferingEventEmitter::default();
+        let logger = TestLogger::default();
+        let (incoming_tx, incoming_rx) = mpsc::channel(1);
+
+        let request_id = mcp::RequestId::Number(2);
+        let call_req = mcp::CallToolRequest::new(mcp::CallToolRequestParam {
+            name: "srv::slow".into(),
+            arguments: None,
+        });
+        incoming_tx
+            .send(mcp::ClientJsonRpcMessage::request(
+                mcp::ClientRequest::CallToolRequest(call_req),
+                request_id.clone(),
+            ))
+            .await
+            .unwrap();
+
+        let (mock_transport, _out_rx) = MockTransport::new(incoming_rx);
+        let mut transport =
+            InterceptingTransport::new(mock_transport, emitter.clone(), logger.clone());
+        let message = transport.receive().await.expect("message");
+        let ctx = match &message {
+            JsonRpcMessage::Request(envelope) => envelope
+                .request
+                .extensions()
+                .get::<RequestLogContext<BufferingEventEmitter, TestLogger>>()
+                .cloned()
+                .expect("log context"),
+            _ => panic!("expected request"),
+        };
+        ctx.log_linked(
+            "notifications/progress",
+            Some("srv".into()),
+            Some(serde_json::json!({ "method": "notifications/progress" })),
+        )
+        .await;
+
+        let server_result = mcp::ServerResult::CallToolResult(mcp::CallToolResult::success(vec![]));
+        transport
+            .send(TxJsonRpcMessage::<RoleServer>::response(
+                server_result,
+                request_id,
+            ))
+            .await
+            .unwrap();
+
+        let events = logger.take();
+        assert_eq!(events.len(), 2);
+        let (linked, call) = (&events[0], &events[1]);
+        assert_eq!(linked.method, "notifications/progress");
+        assert_eq!(linked.server_name.as_deref(), Some("srv"));
+        assert_eq!(linked.session_id, call.session_id);
+        assert_eq!(linked.parent_id, Some(call.id));
+        assert_eq!(call.method, "tools/call");
+        assert!(call.parent_id.is_none());
+    }
+
     #[tokio::test]
     async fn logs_initialize_and_emits_incoming() {
         incoming::clear_incoming().await;
@@ -1171,6 +1327,7 @@ mod tests {
         assert_eq!(event.method, "initialize");
         assert_eq!(event.origin.as_deref(), Some("external"));
         assert_eq!(event.client_name.as_deref(), Some("cli"));
+        assert_eq!(event.client_protocol.as_deref(), Some("2025-03-26"));
         assert!(event.ok);
         let request_json = event.request_json.as_ref().expect("request json");
         let request_obj = request_json.as_object().expect("request json object");
@@ -1209,6 +1366,38 @@ mod tests {
         incoming::clear_incoming().await;
     }
 
+    #[tokio::test]
+    async fn outbound_logs_upstream_log_messages() {
+        let emitter = BufferingEventEmitter::default();
+        let logger = TestLogger::default();
+        let state = OutboundInterceptState::new("srv", emitter, logger.clone());
+        for (level, data) in [
+            (mcp::LoggingLevel::Info, serde_json::json!("started")),
+            (mcp::LoggingLevel::Error, serde_json::json!("disk full")),
+        ] {
+            state
+                .log_server_notification(ServerNotification::LoggingMessageNotification(
+                    mcp::Notification::new(mcp::LoggingMessageNotificationParam {
+                        level,
+                        logger: None,
+                        data,
+                    }),
+                ))
+                .await;
+        }
+        let events = logger.take();
+        assert_eq!(events.len(), 2);
+        assert!(events.iter().all(|e| e.method == "notifications/message"));
+        assert!(
+            events
+                .iter()
+                .all(|e| e.server_name.as_deref() == Some("srv"))
+        );
+        assert!(events[0].ok);
+        assert!(!events[1].ok);
+        assert_eq!(events[1].error.as_deref(), Some("disk full"));
+    }
+
     #[tokio::test]
     async fn outbound_defaults_to_internal_origin() {
         let emitter = BufferingEventEmitter::default();
@@ -2,11 +2,81 @@ use serde::{Deserialize, Serialize};
 use specta::Type;
 
 // Shared ToolInfo type between backend and frontend bridge
-#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
 pub struct ToolInfo {
     pub name: String,
     #[specta(optional)]
+    pub title: Option<String>,
+    #[specta(optional)]
     pub description: Option<String>,
     #[specta(optional)]
     pub input_schema: Option<serde_json::Value>,
+    #[specta(optional)]
+    pub output_schema: Option<serde_json::Value>,
+    // Behaviour hints such as readOnlyHint / destructiveHint, as sent upstream
+    #[specta(optional)]
+    pub annotations: Option<serde_json::Value>,
+    #[specta(optional)]
+    pub icons: Option<serde_json::Value>,
+    #[specta(optional)]
+    pub meta: Option<serde_json::Value>,
+}
+
+impl ToolInfo {
+    // Accepts both the wire (camelCase) and snake_case spellings of each field.
+    pub fn from_tool_json(v: &serde_json::Value) -> Self {
+        let field = |wire: &str, snake: &str| {
+            v.get(wire)
+                .or_else(|| v.get(snake))
+                .filter(|x| !x.is_null())
+                .cloned()
+        };
+        let text = |key: &str| v.get(key).and_then(|x| x.as_str()).map(|s| s.to_string());
+        Self {
+            name: text("name").unwrap_or_default(),
+            title: text("title"),
+            description: text("description"),
+            input_schema: field("inputSchema", "input_schema"),
+            output_schema: field("outputSchema", "output_schema"),
+            annotations: field("annotations", "annotations"),
+            icons: field("icons", "icons"),
+            meta: field("_meta", "meta"),
+        }
+    }
+
+    // Wire (camelCase) form, the inverse of `from_tool_json`.
+    pub fn to_tool_json(&self) -> serde_json::Value {
+        let mut obj = serde_json::Map::new();
+        obj.insert("name".into(), self.name.clone().into());
+        let fields = [
+            ("title", self.title.clone().map(serde_json::Value::String)),
+            (
+                "description",
+                self.description.clone().map(serde_json::Value::String),
+            ),
+            ("inputSchema", self.input_schema.clone()),
+            ("outputSchema", self.output_schema.clone()),
+            ("annotations", self.annotations.clone()),
+            ("icons", self.icons.clone()),
+            ("_meta", self.meta.clone()),
+        ];
+        for (key, value) in fields {
+            if let Some(value) = value {
+                obj.insert(key.into(), value);
+            }
+        }
+        serde_json::Value::Object(obj)
+    }
+}
+
+impl From<&rmcp::model::Tool> for ToolInfo {
+    fn from(tool: &rmcp::model::Tool) -> Self {
+        serde_json::to_value(tool)
+            .map(|v| Self::from_tool_json(&v))
+            .unwrap_or_else(|_| Self {
+                name: tool.name.to_string(),
+                description: tool.description.as_ref().map(|d| d.to_string()),
+                ..Default::default()
+            })
+    }
 }
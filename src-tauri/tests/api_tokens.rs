@@ -0,0 +1,81 @@
+use mcp_bouncer::api_tokens::{create_token, revoke_token};
+use mcp_bouncer::config::{default_settings, save_settings_with};
+use mcp_bouncer::events::{BufferingEventEmitter, EVENT_LOGS_RPC_EVENT};
+use mcp_bouncer::server::stop_server;
+use std::time::Duration;
+
+mod common;
+use common::{TestProvider, start_bouncer};
+
+const INITIALIZE: &str = r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2025-06-18","capabilities":{},"clientInfo":{"name":"probe","version":"0"}}}"#;
+
+#[tokio::test]
+async fn requests_need_a_live_token_and_carry_its_label() {
+    let cp = TestProvider::new();
+    let mut s = default_settings();
+    s.require_api_token = true;
+    save_settings_with(&cp, &s).expect("save settings");
+    let created = create_token(&cp, "cursor").expect("create token");
+
+    let emitter = BufferingEventEmitter::default();
+    let Some((handle, url)) = start_bouncer(
+        "requests_need_a_live_token_and_carry_its_label",
+        emitter.clone(),
+        &cp,
+    )
+    .await
+    else {
+        return;
+    };
+    let http = reqwest::Client::new();
+    let post = |authorization: Option<String>| {
+        let mut req = http
+            .post(&url)
+            .header("content-type", "application/json")
+            .header("accept", "application/json, text/event-stream")
+            .body(INITIALIZE);
+        if let Some(authorization) = authorization {
+            req = req.header("authorization", authorization);
+        }
+        req.send()
+    };
+
+    let missing = post(None).await.unwrap();
+    assert_eq!(missing.status(), reqwest::StatusCode::UNAUTHORIZED);
+    let wrong = post(Some("Bearer mcpb_wrong".into())).await.unwrap();
+    assert_eq!(wrong.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+    // The scheme is case-insensitive.
+    let accepted = post(Some(format!("bearer {}", created.token)))
+        .await
+        .unwrap();
+    assert!(accepted.status().is_success());
+    let _ = accepted.text().await;
+
+    let mut identity = serde_json::Value::Null;
+    for _ in 0..50 {
+        identity = emitter
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|(name, payload)| {
+                name == EVENT_LOGS_RPC_EVENT && payload["method"] == "initialize"
+            })
+            .map(|(_, payload)| payload["identity"].clone())
+            .unwrap_or_default();
+        if !identity.is_null() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    assert_eq!(identity, "cursor");
+
+    revoke_token(&cp, &created.info.id).expect("revoke token");
+    let revoked = post(Some(format!("Bearer {}", created.token)))
+        .await
+        .unwrap();
+    assert_eq!(revoked.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+    stop_server(&handle);
+}
@@ -0,0 +1,254 @@
+use mcp_bouncer::approvals::{self, ApprovalDecision};
+use mcp_bouncer::config::{MacroStep, MacroTool, default_settings, save_settings_with};
+use mcp_bouncer::events::{BufferingEventEmitter, EVENT_APPROVAL_REQUESTED};
+use mcp_bouncer::server::stop_server;
+use rmcp::ServiceExt;
+use rmcp::model as mcp;
+use rmcp::transport::StreamableHttpClientTransport;
+use std::time::Duration;
+
+mod common;
+use common::{TestProvider, spawn_upstream, start_bouncer, upstream_server};
+
+// Upstream with one harmless and one destructive tool.
+#[derive(Clone)]
+struct Upstream;
+
+impl rmcp::handler::server::ServerHandler for Upstream {
+    fn get_info(&self) -> mcp::ServerInfo {
+        mcp::ServerInfo {
+            protocol_version: mcp::ProtocolVersion::V_2025_03_26,
+            capabilities: mcp::ServerCapabilities::builder().enable_tools().build(),
+            server_info: mcp::Implementation {
+                name: "vault".into(),
+                title: None,
+                version: "0.0.1".into(),
+                icons: None,
+                website_url: None,
+            },
+            instructions: None,
+        }
+    }
+
+    fn list_tools(
+        &self,
+        _request: Option<mcp::PaginatedRequestParam>,
+        _context: rmcp::service::RequestContext<rmcp::RoleServer>,
+    ) -> impl core::future::Future<Output = Result<mcp::ListToolsResult, mcp::ErrorData>> + Send + '_
+    {
+        let schema: mcp::JsonObject = Default::default();
+        std::future::ready(Ok(mcp::ListToolsResult {
+            tools: vec![
+                mcp::Tool::new("read_secret", "Read a secret", schema.clone())
+                    .annotate(mcp::ToolAnnotations::new().read_only(true)),
+                mcp::Tool::new("delete_secret", "Delete a secret", schema)
+                    .annotate(mcp::ToolAnnotations::new().destructive(true)),
+            ],
+            next_cursor: None,
+        }))
+    }
+
+    async fn call_tool(
+        &self,
+        request: mcp::CallToolRequestParam,
+        _context: rmcp::service::RequestContext<rmcp::RoleServer>,
+    ) -> Result<mcp::CallToolResult, mcp::ErrorData> {
+        Ok(mcp::CallToolResult::success(vec![mcp::Content::text(
+            request.name.to_string(),
+        )]))
+    }
+}
+
+// Answers the next approval prompt that appears after `seen` earlier ones.
+async fn answer_next(emitter: BufferingEventEmitter, seen: usize, decision: ApprovalDecision) {
+    for _ in 0..500 {
+        let id = emitter
+            .0
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(name, _)| name == EVENT_APPROVAL_REQUESTED)
+            .nth(seen)
+            .and_then(|(_, payload)| payload["id"].as_str().map(str::to_string));
+        if let Some(id) = id {
+            approvals::resolve(&id, decision).await.expect("resolve");
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("no approval request appeared");
+}
+
+#[tokio::test]
+async fn destructive_calls_wait_for_a_decision() {
+    let Some(endpoint) = spawn_upstream("destructive_calls_wait_for_a_decision", Upstream).await
+    else {
+        return;
+    };
+
+    let cp = TestProvider::new();
+    let mut s = default_settings();
+    s.mcp_servers.push(upstream_server("vault", &endpoint));
+    s.approve_destructive_tools = true;
+    s.approval_timeout_secs = Some(1);
+    save_settings_with(&cp, &s).expect("save settings");
+
+    let emitter = BufferingEventEmitter::default();
+    let Some((handle, url)) = start_bouncer(
+        "destructive_calls_wait_for_a_decision",
+        emitter.clone(),
+        &cp,
+    )
+    .await
+    else {
+        return;
+    };
+    let client =
+        ().serve(StreamableHttpClientTransport::from_uri(url.clone()))
+            .await
+            .expect("serve client");
+    client.list_all_tools().await.expect("list tools");
+    let call = |name: &str| {
+        client.call_tool(mcp::CallToolRequestParam {
+            name: name.to_string().into(),
+            arguments: None,
+        })
+    };
+
+    let res = call("vault__read_secret").await.expect("call tool");
+    assert_ne!(res.is_error, Some(true));
+
+    let res = call("vault__delete_secret").await.expect("call tool");
+    assert_eq!(res.is_error, Some(true), "unanswered call times out");
+
+    let (res, ()) = tokio::join!(
+        call("vault__delete_secret"),
+        answer_next(emitter.clone(), 1, ApprovalDecision::Deny)
+    );
+    let res = res.expect("call tool");
+    assert!(res.content[0].as_text().unwrap().text.contains("denied"));
+
+    let (res, ()) = tokio::join!(
+        call("vault__delete_secret"),
+        answer_next(emitter.clone(), 2, ApprovalDecision::AllowForSession)
+    );
+    assert_ne!(res.expect("call tool").is_error, Some(true));
+
+    let res = call("vault__delete_secret").await.expect("call tool");
+    assert_ne!(res.is_error, Some(true), "session stays approved");
+    let prompts = emitter
+        .0
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|(name, _)| name == EVENT_APPROVAL_REQUESTED)
+        .count();
+    assert_eq!(prompts, 3);
+
+    // The grant belongs to the first client's session only.
+    let other = ().serve(StreamableHttpClientTransport::from_uri(url)).await.expect("serve client");
+    other.list_all_tools().await.expect("list tools");
+    let res = other
+        .call_tool(mcp::CallToolRequestParam {
+            name: "vault__delete_secret".into(),
+            arguments: None,
+        })
+        .await
+        .expect("call tool");
+    assert_eq!(res.is_error, Some(true), "another session is asked again");
+
+    stop_server(&handle);
+}
+
+#[tokio::test]
+async fn macro_steps_ask_for_approval_in_the_callers_session() {
+    let Some(endpoint) = spawn_upstream(
+        "macro_steps_ask_for_approval_in_the_callers_session",
+        Upstream,
+    )
+    .await
+    else {
+        return;
+    };
+
+    // Named apart from the other test's server; upstream clients are shared
+    // by name.
+    let cp = TestProvider::new();
+    let mut s = default_settings();
+    s.mcp_servers.push(upstream_server("safe", &endpoint));
+    s.macros.push(MacroTool {
+        name: "rotate".into(),
+        description: "Read then delete a secret".into(),
+        input_schema: None,
+        steps: vec![
+            MacroStep {
+                server: "safe".into(),
+                tool: "read_secret".into(),
+                arguments: Default::default(),
+            },
+            MacroStep {
+                server: "safe".into(),
+                tool: "delete_secret".into(),
+                arguments: Default::default(),
+            },
+        ],
+    });
+    s.approve_destructive_tools = true;
+    s.approval_timeout_secs = Some(5);
+    save_settings_with(&cp, &s).expect("save settings");
+
+    let emitter = BufferingEventEmitter::default();
+    let Some((handle, url)) = start_bouncer(
+        "macro_steps_ask_for_approval_in_the_callers_session",
+        emitter.clone(),
+        &cp,
+    )
+    .await
+    else {
+        return;
+    };
+    let client =
+        ().serve(StreamableHttpClientTransport::from_uri(url))
+            .await
+            .expect("serve client");
+    // No tools/list first: the destructive hint is looked up on demand.
+    let call = |name: &str| {
+        client.call_tool(mcp::CallToolRequestParam {
+            name: name.to_string().into(),
+            arguments: None,
+        })
+    };
+
+    let (res, ()) = tokio::join!(
+        call("rotate"),
+        answer_next(emitter.clone(), 0, ApprovalDecision::Deny)
+    );
+    let res = res.expect("call macro");
+    assert_eq!(res.is_error, Some(true));
+    let text = &res.content[0].as_text().unwrap().text;
+    assert!(text.contains("step 2 failed"), "{text}");
+    assert!(text.contains("denied"), "{text}");
+
+    let (res, ()) = tokio::join!(
+        call("rotate"),
+        answer_next(emitter.clone(), 1, ApprovalDecision::AllowForSession)
+    );
+    assert_ne!(res.expect("call macro").is_error, Some(true));
+
+    // The session approval covers direct calls of the same tool too.
+    client.list_all_tools().await.expect("list tools");
+    let res = call("safe__delete_secret").await.expect("call tool");
+    assert_ne!(res.is_error, Some(true), "session stays approved");
+    let prompts: Vec<serde_json::Value> = emitter
+        .0
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|(name, _)| name == EVENT_APPROVAL_REQUESTED)
+        .map(|(_, payload)| payload.clone())
+        .collect();
+    assert_eq!(prompts.len(), 2);
+    assert_eq!(prompts[0]["tool_name"], "delete_secret");
+
+    stop_server(&handle);
+}
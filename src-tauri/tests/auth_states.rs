@@ -19,6 +19,8 @@ async fn mark_unauthorized_sets_state_and_clears_error() {
         endpoint: "http://127.0.0.1".into(),
         headers: Default::default(),
         enabled: true,
+        prefix: None,
+        limits: None,
     });
     save_settings_with(&cp, &s).unwrap();
 
@@ -50,6 +52,8 @@ async fn authorizing_state_is_exposed() {
         endpoint: "http://127.0.0.1".into(),
         headers: Default::default(),
         enabled: true,
+        prefix: None,
+        limits: None,
     });
     save_settings_with(&cp, &s).unwrap();
 
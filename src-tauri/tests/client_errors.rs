@@ -36,6 +36,8 @@ async fn http_missing_endpoint_errors() {
         endpoint: String::new(),
         headers: Default::default(),
         enabled: true,
+        prefix: None,
+        limits: None,
     };
     let emitter = BufferingEventEmitter::default();
     let logger = NoopLogger;
@@ -58,6 +60,8 @@ async fn missing_command_for_stdio_errors() {
         endpoint: String::new(),
         headers: Default::default(),
         enabled: true,
+        prefix: None,
+        limits: None,
     };
     let emitter = BufferingEventEmitter::default();
     let logger = NoopLogger;
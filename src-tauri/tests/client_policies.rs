@@ -0,0 +1,159 @@
+use mcp_bouncer::config::{ClientPolicy, default_settings, save_settings_with};
+use mcp_bouncer::server::stop_server;
+use rmcp::ServiceExt;
+use rmcp::model as mcp;
+use rmcp::transport::StreamableHttpClientTransport;
+
+mod common;
+use common::{NoopEmitter, TestProvider, spawn_upstream, start_bouncer, upstream_server};
+
+// Upstream with one read-only and one writing tool.
+#[derive(Clone)]
+struct Upstream;
+
+impl rmcp::handler::server::ServerHandler for Upstream {
+    fn get_info(&self) -> mcp::ServerInfo {
+        mcp::ServerInfo {
+            protocol_version: mcp::ProtocolVersion::V_2025_03_26,
+            capabilities: mcp::ServerCapabilities::builder().enable_tools().build(),
+            server_info: mcp::Implementation {
+                name: "forge".into(),
+                title: None,
+                version: "0.0.1".into(),
+                icons: None,
+                website_url: None,
+            },
+            instructions: None,
+        }
+    }
+
+    fn list_tools(
+        &self,
+        _request: Option<mcp::PaginatedRequestParam>,
+        _context: rmcp::service::RequestContext<rmcp::RoleServer>,
+    ) -> impl core::future::Future<Output = Result<mcp::ListToolsResult, mcp::ErrorData>> + Send + '_
+    {
+        let schema: mcp::JsonObject = Default::default();
+        std::future::ready(Ok(mcp::ListToolsResult {
+            tools: vec![
+                mcp::Tool::new("get_issue", "Fetch an issue", schema.clone())
+                    .annotate(mcp::ToolAnnotations::new().read_only(true)),
+                mcp::Tool::new("create_branch", "Create a branch", schema),
+            ],
+            next_cursor: None,
+        }))
+    }
+
+    async fn call_tool(
+        &self,
+        request: mcp::CallToolRequestParam,
+        _context: rmcp::service::RequestContext<rmcp::RoleServer>,
+    ) -> Result<mcp::CallToolResult, mcp::ErrorData> {
+        let args = request.arguments.unwrap_or_default();
+        let text = match request.name.as_ref() {
+            "get_issue" => {
+                serde_json::json!({ "number": args["id"], "title": "Crash" }).to_string()
+            }
+            "create_branch" => format!(
+                "{}:{}",
+                args["repo"].as_str().unwrap_or_default(),
+                args["name"].as_str().unwrap_or_default()
+            ),
+            other => return Err(mcp::ErrorData::invalid_params(format!("no {other}"), None)),
+        };
+        Ok(mcp::CallToolResult::success(vec![mcp::Content::text(text)]))
+    }
+}
+
+fn client_named(name: &str) -> mcp::ClientInfo {
+    mcp::ClientInfo {
+        client_info: mcp::Implementation {
+            name: name.into(),
+            title: None,
+            version: "1.0.0".into(),
+            icons: None,
+            website_url: None,
+        },
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn read_only_policy_applies_to_matching_client() {
+    let Some(endpoint) =
+        spawn_upstream("read_only_policy_applies_to_matching_client", Upstream).await
+    else {
+        return;
+    };
+
+    let cp = TestProvider::new();
+    let mut s = default_settings();
+    s.mcp_servers.push(upstream_server("forge", &endpoint));
+    s.client_policies.push(ClientPolicy {
+        name: "cursor-readonly".into(),
+        client: "cursor*".into(),
+        allow_servers: vec![],
+        deny_servers: vec![],
+        allow_tools: vec![],
+        deny_tools: vec![],
+        read_only: true,
+    });
+    save_settings_with(&cp, &s).expect("save settings");
+
+    let Some((handle, url)) = start_bouncer(
+        "read_only_policy_applies_to_matching_client",
+        NoopEmitter,
+        &cp,
+    )
+    .await
+    else {
+        return;
+    };
+
+    let cursor = client_named("cursor")
+        .serve(StreamableHttpClientTransport::from_uri(url.clone()))
+        .await
+        .expect("serve cursor client");
+    let tools = cursor.list_all_tools().await.expect("list tools");
+    let names: Vec<&str> = tools.iter().map(|t| t.name.as_ref()).collect();
+    assert_eq!(names, vec!["forge__get_issue"]);
+    let res = cursor
+        .call_tool(mcp::CallToolRequestParam {
+            name: "forge__get_issue".into(),
+            arguments: serde_json::json!({ "id": 3 }).as_object().cloned(),
+        })
+        .await
+        .expect("call read-only tool");
+    assert_ne!(res.is_error, Some(true), "listed tools can be called");
+    assert!(
+        res.content[0]
+            .as_text()
+            .expect("text")
+            .text
+            .contains("Crash")
+    );
+    let res = cursor
+        .call_tool(mcp::CallToolRequestParam {
+            name: "forge__create_branch".into(),
+            arguments: None,
+        })
+        .await
+        .expect("call tool");
+    assert_eq!(res.is_error, Some(true));
+    assert!(
+        res.content[0]
+            .as_text()
+            .expect("text")
+            .text
+            .contains("cursor-readonly")
+    );
+
+    let other = client_named("desktop")
+        .serve(StreamableHttpClientTransport::from_uri(url))
+        .await
+        .expect("serve other client");
+    let tools = other.list_all_tools().await.expect("list tools");
+    assert_eq!(tools.len(), 2);
+
+    stop_server(&handle);
+}
@@ -1,12 +1,24 @@
+// Each test binary uses a different subset of these helpers.
+#![allow(dead_code)]
+
 use std::fs;
 use std::path::PathBuf;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-use mcp_bouncer::config::ConfigProvider;
+use mcp_bouncer::config::{ConfigProvider, MCPServerConfig, ServerTransport, TransportType};
+use mcp_bouncer::events::EventEmitter;
+use mcp_bouncer::logging::SqlitePublisher;
+use mcp_bouncer::secrets::{MemorySecretStore, SecretStore};
+use mcp_bouncer::server::start_server;
+use rmcp::transport::streamable_http_server::{
+    StreamableHttpServerConfig, StreamableHttpService, session::local::LocalSessionManager,
+};
 
 #[derive(Clone)]
 pub struct TestProvider {
     base: PathBuf,
+    secrets: Arc<MemorySecretStore>,
 }
 
 impl TestProvider {
@@ -23,7 +35,10 @@ impl TestProvider {
             stamp
         ));
         fs::create_dir_all(&dir).unwrap();
-        Self { base: dir }
+        Self {
+            base: dir,
+            secrets: Arc::default(),
+        }
     }
 }
 
@@ -37,4 +52,97 @@ impl ConfigProvider for TestProvider {
     fn base_dir(&self) -> PathBuf {
         self.base.clone()
     }
+
+    fn secret_store(&self) -> &dyn SecretStore {
+        self.secrets.as_ref()
+    }
+}
+
+#[derive(Clone)]
+pub struct NoopEmitter;
+
+impl EventEmitter for NoopEmitter {
+    fn emit(&self, _e: &str, _p: &serde_json::Value) {}
+}
+
+// Serves `handler` as an upstream MCP server on a local port and returns its
+// endpoint; None (after noting why) when the sandbox forbids binding.
+pub async fn spawn_upstream<H>(test: &str, handler: H) -> Option<String>
+where
+    H: rmcp::ServerHandler + Clone,
+{
+    let listener = match tokio::net::TcpListener::bind("127.0.0.1:0").await {
+        Ok(l) => l,
+        Err(err) if err.kind() == std::io::ErrorKind::PermissionDenied => {
+            eprintln!("skipping {test}: {err}");
+            return None;
+        }
+        Err(err) => panic!("failed to bind upstream listener: {err}"),
+    };
+    let addr = listener.local_addr().unwrap();
+    let service: StreamableHttpService<H, LocalSessionManager> = StreamableHttpService::new(
+        move || Ok(handler.clone()),
+        Default::default(),
+        StreamableHttpServerConfig {
+            stateful_mode: true,
+            sse_keep_alive: Some(Duration::from_secs(15)),
+        },
+    );
+    let router = axum::Router::new().nest_service("/mcp", service);
+    tokio::spawn(async move {
+        let _ = axum::serve(listener, router).await;
+    });
+    Some(format!("http://{}:{}/mcp", addr.ip(), addr.port()))
+}
+
+// Enabled streamable HTTP server entry for an upstream from `spawn_upstream`.
+pub fn upstream_server(name: &str, endpoint: &str) -> MCPServerConfig {
+    MCPServerConfig {
+        name: name.into(),
+        description: "test".into(),
+        transport: TransportType::StreamableHttp,
+        command: String::new(),
+        args: vec![],
+        env: Default::default(),
+        endpoint: endpoint.into(),
+        headers: Default::default(),
+        enabled: true,
+        prefix: None,
+        limits: None,
+    }
+}
+
+// Starts the bouncer on a local port and returns its handle and /mcp URL;
+// None (after noting why) when the sandbox forbids binding.
+pub async fn start_bouncer<E>(
+    test: &str,
+    emitter: E,
+    cp: &TestProvider,
+) -> Option<(tokio::task::JoinHandle<()>, String)>
+where
+    E: EventEmitter + Clone + Send + Sync + 'static,
+{
+    let (handle, bound_opt) = match start_server(
+        emitter,
+        cp.clone(),
+        SqlitePublisher,
+        ServerTransport::StreamableHttp,
+        "127.0.0.1:0".to_string(),
+    )
+    .await
+    {
+        Ok(res) => res,
+        Err(err) => {
+            if err.contains("Operation not permitted") {
+                eprintln!("skipping {test}: {err}");
+                return None;
+            }
+            panic!("start_server failed: {err}");
+        }
+    };
+    let bound = bound_opt.expect("TCP server should return bound address");
+    Some((
+        handle,
+        format!("http://{}:{}/mcp", bound.ip(), bound.port()),
+    ))
 }
@@ -0,0 +1,131 @@
+use mcp_bouncer::config::{default_settings, save_settings_with};
+use mcp_bouncer::server::stop_server;
+use rmcp::ServiceExt;
+use rmcp::model as mcp;
+use rmcp::transport::StreamableHttpClientTransport;
+
+mod common;
+use common::{NoopEmitter, TestProvider, spawn_upstream, start_bouncer, upstream_server};
+
+// Upstream whose completions echo the reference it was asked about.
+#[derive(Clone)]
+struct Upstream;
+
+impl rmcp::handler::server::ServerHandler for Upstream {
+    fn get_info(&self) -> mcp::ServerInfo {
+        mcp::ServerInfo {
+            protocol_version: mcp::ProtocolVersion::V_2025_03_26,
+            capabilities: mcp::ServerCapabilities::builder()
+                .enable_completions()
+                .enable_prompts()
+                .enable_resources()
+                .build(),
+            server_info: mcp::Implementation {
+                name: "compl".into(),
+                title: None,
+                version: "0.0.1".into(),
+                icons: None,
+                website_url: None,
+            },
+            instructions: None,
+        }
+    }
+
+    async fn list_prompts(
+        &self,
+        _request: Option<mcp::PaginatedRequestParam>,
+        _context: rmcp::service::RequestContext<rmcp::RoleServer>,
+    ) -> Result<mcp::ListPromptsResult, mcp::ErrorData> {
+        Ok(mcp::ListPromptsResult {
+            prompts: vec![mcp::Prompt::new("greet", Some("greet"), None)],
+            next_cursor: None,
+        })
+    }
+
+    async fn complete(
+        &self,
+        request: mcp::CompleteRequestParam,
+        _context: rmcp::service::RequestContext<rmcp::RoleServer>,
+    ) -> Result<mcp::CompleteResult, mcp::ErrorData> {
+        let target = match &request.r#ref {
+            mcp::Reference::Prompt(p) => format!("prompt:{}", p.name),
+            mcp::Reference::Resource(r) => format!("resource:{}", r.uri),
+        };
+        Ok(mcp::CompleteResult {
+            completion: mcp::CompletionInfo {
+                values: vec![format!("{target}:{}", request.argument.value)],
+                total: None,
+                has_more: None,
+            },
+        })
+    }
+}
+
+fn completion_request(r#ref: mcp::Reference) -> mcp::CompleteRequestParam {
+    mcp::CompleteRequestParam {
+        r#ref,
+        argument: mcp::ArgumentInfo {
+            name: "arg".into(),
+            value: "a".into(),
+        },
+        context: None,
+    }
+}
+
+#[tokio::test]
+async fn completions_are_routed_to_the_owning_server() {
+    let Some(endpoint) =
+        spawn_upstream("completions_are_routed_to_the_owning_server", Upstream).await
+    else {
+        return;
+    };
+
+    let cp = TestProvider::new();
+    let mut s = default_settings();
+    s.mcp_servers.push(upstream_server("compl", &endpoint));
+    save_settings_with(&cp, &s).expect("save settings");
+
+    let Some((handle, url)) = start_bouncer(
+        "completions_are_routed_to_the_owning_server",
+        NoopEmitter,
+        &cp,
+    )
+    .await
+    else {
+        return;
+    };
+    let client =
+        ().serve(StreamableHttpClientTransport::from_uri(url))
+            .await
+            .expect("serve client");
+
+    let prompts = client.list_all_prompts().await.expect("list prompts");
+    assert!(prompts.iter().any(|p| p.name == "compl__greet"));
+    let res = client
+        .complete(completion_request(mcp::Reference::for_prompt(
+            "compl__greet",
+        )))
+        .await
+        .expect("complete prompt");
+    assert_eq!(res.completion.values, vec!["prompt:greet:a".to_string()]);
+
+    let res = client
+        .complete(completion_request(mcp::Reference::for_resource(
+            "bouncer://compl/db://{table}",
+        )))
+        .await
+        .expect("complete resource template");
+    assert_eq!(
+        res.completion.values,
+        vec!["resource:db://{table}:a".to_string()]
+    );
+
+    let err = client
+        .complete(completion_request(mcp::Reference::for_resource(
+            "db://{table}",
+        )))
+        .await;
+    assert!(err.is_err(), "unnamespaced reference should be rejected");
+
+    stop_server(&handle);
+}
@@ -22,6 +22,8 @@ fn settings_and_tools_persist() {
         endpoint: "http://127.0.0.1".into(),
         headers: Default::default(),
         enabled: true,
+        prefix: None,
+        limits: None,
     });
     save_settings_with(&cp, &s).unwrap();
     let loaded = load_settings_with(&cp);
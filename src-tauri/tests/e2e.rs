@@ -1,5 +1,6 @@
 use mcp_bouncer::config::{
-    ConfigProvider, MCPServerConfig, TransportType, default_settings, save_settings_with,
+    ConfigProvider, MCPServerConfig, TransportType, default_settings, save_prompts_toggle_with,
+    save_settings_with,
 };
 use mcp_bouncer::{
     config::ServerTransport,
@@ -137,6 +138,8 @@ async fn e2e_list_and_echo_hermetic_http() {
         endpoint: format!("http://{}:{}/mcp", upstream_addr.ip(), upstream_addr.port()),
         headers: Default::default(),
         enabled: true,
+        prefix: None,
+        limits: None,
     });
     save_settings_with(&cp, &s).expect("save settings");
 
@@ -214,3 +217,347 @@ async fn e2e_list_and_echo_hermetic_http() {
     // Cleanup
     stop_server(&handle);
 }
+
+#[tokio::test]
+async fn e2e_resources_are_namespaced_and_routed() {
+    #[derive(Clone)]
+    struct Upstream;
+    impl rmcp::handler::server::ServerHandler for Upstream {
+        fn get_info(&self) -> mcp::ServerInfo {
+            mcp::ServerInfo {
+                protocol_version: mcp::ProtocolVersion::V_2025_03_26,
+                capabilities: mcp::ServerCapabilities::builder()
+                    .enable_resources()
+                    .build(),
+                server_info: mcp::Implementation {
+                    name: "docs".into(),
+                    title: None,
+                    version: "0.0.1".into(),
+                    icons: None,
+                    website_url: None,
+                },
+                instructions: None,
+            }
+        }
+        fn list_resources(
+            &self,
+            _request: Option<mcp::PaginatedRequestParam>,
+            _context: rmcp::service::RequestContext<rmcp::RoleServer>,
+        ) -> impl core::future::Future<Output = Result<mcp::ListResourcesResult, mcp::ErrorData>>
+        + Send
+        + '_ {
+            use mcp::AnnotateAble;
+            std::future::ready(Ok(mcp::ListResourcesResult {
+                resources: vec![
+                    mcp::RawResource::new("file:///readme.md", "readme").no_annotation(),
+                ],
+                next_cursor: None,
+            }))
+        }
+        fn read_resource(
+            &self,
+            request: mcp::ReadResourceRequestParam,
+            _context: rmcp::service::RequestContext<rmcp::RoleServer>,
+        ) -> impl core::future::Future<Output = Result<mcp::ReadResourceResult, mcp::ErrorData>>
+        + Send
+        + '_ {
+            let result = if request.uri == "file:///readme.md" {
+                Ok(mcp::ReadResourceResult {
+                    contents: vec![mcp::ResourceContents::text("hello docs", request.uri)],
+                })
+            } else {
+                Err(mcp::ErrorData::resource_not_found("missing", None))
+            };
+            std::future::ready(result)
+        }
+    }
+
+    let upstream_listener = match tokio::net::TcpListener::bind("127.0.0.1:0").await {
+        Ok(l) => l,
+        Err(err) if err.kind() == std::io::ErrorKind::PermissionDenied => {
+            eprintln!("skipping e2e_resources_are_namespaced_and_routed: {err}");
+            return;
+        }
+        Err(err) => panic!("failed to bind upstream listener: {err}"),
+    };
+    let upstream_addr = upstream_listener.local_addr().unwrap();
+    let upstream_service: StreamableHttpService<Upstream, LocalSessionManager> =
+        StreamableHttpService::new(
+            || Ok(Upstream),
+            Default::default(),
+            StreamableHttpServerConfig {
+                stateful_mode: true,
+                sse_keep_alive: Some(std::time::Duration::from_secs(15)),
+            },
+        );
+    let upstream_router = axum::Router::new().nest_service("/mcp", upstream_service);
+    tokio::spawn(async move {
+        let _ = axum::serve(upstream_listener, upstream_router).await;
+    });
+
+    let cp = TempConfigProvider::new();
+    let mut s = default_settings();
+    s.mcp_servers.push(MCPServerConfig {
+        name: "docs".into(),
+        description: "test".into(),
+        transport: TransportType::StreamableHttp,
+        command: String::new(),
+        args: vec![],
+        env: Default::default(),
+        endpoint: format!("http://{}:{}/mcp", upstream_addr.ip(), upstream_addr.port()),
+        headers: Default::default(),
+        enabled: true,
+        prefix: None,
+        limits: None,
+    });
+    save_settings_with(&cp, &s).expect("save settings");
+
+    #[derive(Clone)]
+    struct NoopEmitter;
+    impl EventEmitter for NoopEmitter {
+        fn emit(&self, _e: &str, _p: &serde_json::Value) {}
+    }
+    let (handle, bound_opt) = match start_server(
+        NoopEmitter,
+        cp.clone(),
+        SqlitePublisher,
+        ServerTransport::StreamableHttp,
+        "127.0.0.1:0".to_string(),
+    )
+    .await
+    {
+        Ok(res) => res,
+        Err(err) => {
+            if err.contains("Operation not permitted") {
+                eprintln!("skipping e2e_resources_are_namespaced_and_routed: {err}");
+                return;
+            }
+            panic!("start_server failed: {err}");
+        }
+    };
+    let bound = bound_opt.expect("TCP server should return bound address");
+    let url = format!("http://{}:{}/mcp", bound.ip(), bound.port());
+    let client =
+        ().serve(StreamableHttpClientTransport::from_uri(url))
+            .await
+            .expect("serve client");
+    assert!(
+        client
+            .peer_info()
+            .and_then(|info| info.capabilities.resources.clone())
+            .is_some(),
+        "bouncer should advertise resources capability"
+    );
+
+    let namespaced = "bouncer://docs/file:///readme.md";
+    let start = std::time::Instant::now();
+    loop {
+        let resources = client.list_all_resources().await.expect("list resources");
+        let uris: Vec<String> = resources.into_iter().map(|r| r.raw.uri).collect();
+        if uris.iter().any(|u| u == namespaced) {
+            break;
+        }
+        if start.elapsed() > std::time::Duration::from_secs(45) {
+            panic!("resources did not include {namespaced} within timeout; got: {uris:?}");
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    }
+
+    let res = client
+        .read_resource(mcp::ReadResourceRequestParam {
+            uri: namespaced.into(),
+        })
+        .await
+        .expect("read resource");
+    match &res.contents[..] {
+        [mcp::ResourceContents::TextResourceContents { uri, text, .. }] => {
+            assert_eq!(uri, namespaced);
+            assert_eq!(text, "hello docs");
+        }
+        other => panic!("unexpected contents: {other:?}"),
+    }
+
+    let missing = client
+        .read_resource(mcp::ReadResourceRequestParam {
+            uri: "bouncer://nope/file:///x".into(),
+        })
+        .await;
+    assert!(missing.is_err(), "unknown server should produce an error");
+
+    stop_server(&handle);
+}
+
+#[tokio::test]
+async fn e2e_prompts_are_namespaced_filtered_and_routed() {
+    #[derive(Clone)]
+    struct Upstream;
+    impl rmcp::handler::server::ServerHandler for Upstream {
+        fn get_info(&self) -> mcp::ServerInfo {
+            mcp::ServerInfo {
+                protocol_version: mcp::ProtocolVersion::V_2025_03_26,
+                capabilities: mcp::ServerCapabilities::builder().enable_prompts().build(),
+                server_info: mcp::Implementation {
+                    name: "lib".into(),
+                    title: None,
+                    version: "0.0.1".into(),
+                    icons: None,
+                    website_url: None,
+                },
+                instructions: None,
+            }
+        }
+        fn list_prompts(
+            &self,
+            _request: Option<mcp::PaginatedRequestParam>,
+            _context: rmcp::service::RequestContext<rmcp::RoleServer>,
+        ) -> impl core::future::Future<Output = Result<mcp::ListPromptsResult, mcp::ErrorData>> + Send + '_
+        {
+            std::future::ready(Ok(mcp::ListPromptsResult {
+                prompts: vec![
+                    mcp::Prompt::new("summarize", Some("summarize text"), None),
+                    mcp::Prompt::new("hidden", None::<String>, None),
+                ],
+                next_cursor: None,
+            }))
+        }
+        fn get_prompt(
+            &self,
+            request: mcp::GetPromptRequestParam,
+            _context: rmcp::service::RequestContext<rmcp::RoleServer>,
+        ) -> impl core::future::Future<Output = Result<mcp::GetPromptResult, mcp::ErrorData>> + Send + '_
+        {
+            let topic = request
+                .arguments
+                .and_then(|m| m.get("topic").and_then(|v| v.as_str()).map(str::to_string))
+                .unwrap_or_default();
+            std::future::ready(Ok(mcp::GetPromptResult {
+                description: None,
+                messages: vec![mcp::PromptMessage::new_text(
+                    mcp::PromptMessageRole::User,
+                    format!("{}: {topic}", request.name),
+                )],
+            }))
+        }
+    }
+
+    let upstream_listener = match tokio::net::TcpListener::bind("127.0.0.1:0").await {
+        Ok(l) => l,
+        Err(err) if err.kind() == std::io::ErrorKind::PermissionDenied => {
+            eprintln!("skipping e2e_prompts_are_namespaced_filtered_and_routed: {err}");
+            return;
+        }
+        Err(err) => panic!("failed to bind upstream listener: {err}"),
+    };
+    let upstream_addr = upstream_listener.local_addr().unwrap();
+    let upstream_service: StreamableHttpService<Upstream, LocalSessionManager> =
+        StreamableHttpService::new(
+            || Ok(Upstream),
+            Default::default(),
+            StreamableHttpServerConfig {
+                stateful_mode: true,
+                sse_keep_alive: Some(std::time::Duration::from_secs(15)),
+            },
+        );
+    let upstream_router = axum::Router::new().nest_service("/mcp", upstream_service);
+    tokio::spawn(async move {
+        let _ = axum::serve(upstream_listener, upstream_router).await;
+    });
+
+    let cp = TempConfigProvider::new();
+    let mut s = default_settings();
+    s.mcp_servers.push(MCPServerConfig {
+        name: "lib".into(),
+        description: "test".into(),
+        transport: TransportType::StreamableHttp,
+        command: String::new(),
+        args: vec![],
+        env: Default::default(),
+        endpoint: format!("http://{}:{}/mcp", upstream_addr.ip(), upstream_addr.port()),
+        headers: Default::default(),
+        enabled: true,
+        prefix: None,
+        limits: None,
+    });
+    save_settings_with(&cp, &s).expect("save settings");
+    save_prompts_toggle_with(&cp, "lib", "hidden", false).expect("save prompt toggle");
+
+    #[derive(Clone)]
+    struct NoopEmitter;
+    impl EventEmitter for NoopEmitter {
+        fn emit(&self, _e: &str, _p: &serde_json::Value) {}
+    }
+    let (handle, bound_opt) = match start_server(
+        NoopEmitter,
+        cp.clone(),
+        SqlitePublisher,
+        ServerTransport::StreamableHttp,
+        "127.0.0.1:0".to_string(),
+    )
+    .await
+    {
+        Ok(res) => res,
+        Err(err) => {
+            if err.contains("Operation not permitted") {
+                eprintln!("skipping e2e_prompts_are_namespaced_filtered_and_routed: {err}");
+                return;
+            }
+            panic!("start_server failed: {err}");
+        }
+    };
+    let bound = bound_opt.expect("TCP server should return bound address");
+    let url = format!("http://{}:{}/mcp", bound.ip(), bound.port());
+    let client =
+        ().serve(StreamableHttpClientTransport::from_uri(url))
+            .await
+            .expect("serve client");
+
+    let prompt_name = "lib__summarize";
+    let start = std::time::Instant::now();
+    let names = loop {
+        let prompts = client.list_all_prompts().await.expect("list prompts");
+        let names: Vec<String> = prompts.into_iter().map(|p| p.name).collect();
+        if names.iter().any(|n| n == prompt_name) {
+            break names;
+        }
+        if start.elapsed() > std::time::Duration::from_secs(45) {
+            panic!("prompts did not include {prompt_name} within timeout; got: {names:?}");
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    };
+    assert!(
+        !names.iter().any(|n| n == "lib__hidden"),
+        "disabled prompt should not be listed: {names:?}"
+    );
+
+    let res = client
+        .get_prompt(mcp::GetPromptRequestParam {
+            name: prompt_name.into(),
+            arguments: Some(
+                serde_json::json!({ "topic": "rust" })
+                    .as_object()
+                    .unwrap()
+                    .clone(),
+            ),
+        })
+        .await
+        .expect("get prompt");
+    match &res.messages[..] {
+        [
+            mcp::PromptMessage {
+                content: mcp::PromptMessageContent::Text { text },
+                ..
+            },
+        ] => assert_eq!(text, "summarize: rust"),
+        other => panic!("unexpected messages: {other:?}"),
+    }
+
+    let hidden = client
+        .get_prompt(mcp::GetPromptRequestParam {
+            name: "lib::hidden".into(),
+            arguments: None,
+        })
+        .await;
+    assert!(hidden.is_err(), "disabled prompt should not be routable");
+
+    stop_server(&handle);
+}
@@ -131,6 +131,8 @@ async fn http_client_can_connect_list_tools_and_send_headers() {
         endpoint: format!("http://{}:{}/mcp", addr.ip(), addr.port()),
         headers,
         enabled: true,
+        prefix: None,
+        limits: None,
     };
 
     let emitter = BufferingEventEmitter::default();
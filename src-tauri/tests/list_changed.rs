@@ -0,0 +1,154 @@
+use mcp_bouncer::config::{default_settings, save_settings_with};
+use mcp_bouncer::server::stop_server;
+use rmcp::ServiceExt;
+use rmcp::model as mcp;
+use rmcp::transport::StreamableHttpClientTransport;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+mod common;
+use common::{NoopEmitter, TestProvider, spawn_upstream, start_bouncer, upstream_server};
+
+// Downstream client that reports list_changed notifications on a channel.
+#[derive(Clone)]
+struct RecordingClient(mpsc::UnboundedSender<&'static str>);
+
+impl rmcp::ClientHandler for RecordingClient {
+    async fn on_tool_list_changed(
+        &self,
+        _context: rmcp::service::NotificationContext<rmcp::RoleClient>,
+    ) {
+        let _ = self.0.send("tools");
+    }
+
+    async fn on_prompt_list_changed(
+        &self,
+        _context: rmcp::service::NotificationContext<rmcp::RoleClient>,
+    ) {
+        let _ = self.0.send("prompts");
+    }
+
+    async fn on_resource_list_changed(
+        &self,
+        _context: rmcp::service::NotificationContext<rmcp::RoleClient>,
+    ) {
+        let _ = self.0.send("resources");
+    }
+}
+
+async fn expect_kind(rx: &mut mpsc::UnboundedReceiver<&'static str>, kind: &str) {
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(10);
+    loop {
+        match tokio::time::timeout_at(deadline, rx.recv()).await {
+            Ok(Some(k)) if k == kind => return,
+            Ok(Some(_)) => continue,
+            _ => panic!("did not receive {kind} list_changed"),
+        }
+    }
+}
+
+#[tokio::test]
+async fn upstream_and_bouncer_list_changes_reach_downstream() {
+    // Upstream exposes a `bump` tool that announces a tool list change.
+    #[derive(Clone)]
+    struct Upstream;
+    impl rmcp::handler::server::ServerHandler for Upstream {
+        fn get_info(&self) -> mcp::ServerInfo {
+            mcp::ServerInfo {
+                protocol_version: mcp::ProtocolVersion::V_2025_03_26,
+                capabilities: mcp::ServerCapabilities::builder()
+                    .enable_tools()
+                    .enable_tool_list_changed()
+                    .build(),
+                server_info: mcp::Implementation {
+                    name: "changing".into(),
+                    title: None,
+                    version: "0.0.1".into(),
+                    icons: None,
+                    website_url: None,
+                },
+                instructions: None,
+            }
+        }
+        fn list_tools(
+            &self,
+            _request: Option<mcp::PaginatedRequestParam>,
+            _context: rmcp::service::RequestContext<rmcp::RoleServer>,
+        ) -> impl core::future::Future<Output = Result<mcp::ListToolsResult, mcp::ErrorData>> + Send + '_
+        {
+            let schema: mcp::JsonObject = Default::default();
+            std::future::ready(Ok(mcp::ListToolsResult {
+                tools: vec![mcp::Tool::new("bump", "bump", schema)],
+                next_cursor: None,
+            }))
+        }
+        async fn call_tool(
+            &self,
+            _request: mcp::CallToolRequestParam,
+            context: rmcp::service::RequestContext<rmcp::RoleServer>,
+        ) -> Result<mcp::CallToolResult, mcp::ErrorData> {
+            let _ = context.peer.notify_tool_list_changed().await;
+            Ok(mcp::CallToolResult::success(vec![mcp::Content::text(
+                "bumped",
+            )]))
+        }
+    }
+
+    let Some(endpoint) = spawn_upstream(
+        "upstream_and_bouncer_list_changes_reach_downstream",
+        Upstream,
+    )
+    .await
+    else {
+        return;
+    };
+
+    let cp = TestProvider::new();
+    let mut s = default_settings();
+    s.mcp_servers.push(upstream_server("changing", &endpoint));
+    save_settings_with(&cp, &s).expect("save settings");
+
+    let Some((handle, url)) = start_bouncer(
+        "upstream_and_bouncer_list_changes_reach_downstream",
+        NoopEmitter,
+        &cp,
+    )
+    .await
+    else {
+        return;
+    };
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let client = RecordingClient(tx)
+        .serve(StreamableHttpClientTransport::from_uri(url))
+        .await
+        .expect("serve client");
+
+    let start = std::time::Instant::now();
+    loop {
+        let tools = client.list_all_tools().await.expect("list tools");
+        if tools.iter().any(|t| t.name == "changing__bump") {
+            break;
+        }
+        if start.elapsed() > Duration::from_secs(45) {
+            panic!("tools did not include changing__bump within timeout");
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+
+    // Upstream-originated change is relayed downstream.
+    client
+        .call_tool(mcp::CallToolRequestParam {
+            name: "changing__bump".into(),
+            arguments: None,
+        })
+        .await
+        .expect("call bump");
+    expect_kind(&mut rx, "tools").await;
+
+    // Bouncer-originated changes (server add/remove/toggle) fan out to every list.
+    mcp_bouncer::downstream::notify_all_lists_changed().await;
+    expect_kind(&mut rx, "resources").await;
+    expect_kind(&mut rx, "prompts").await;
+
+    stop_server(&handle);
+}
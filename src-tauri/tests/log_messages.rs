@@ -0,0 +1,170 @@
+use mcp_bouncer::config::{default_settings, save_settings_with};
+use mcp_bouncer::server::stop_server;
+use rmcp::ServiceExt;
+use rmcp::model as mcp;
+use rmcp::transport::StreamableHttpClientTransport;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+mod common;
+use common::{NoopEmitter, TestProvider, spawn_upstream, start_bouncer, upstream_server};
+
+// Upstream that records the requested level and logs one message per `work` call.
+#[derive(Clone)]
+struct Upstream(Arc<Mutex<Option<mcp::LoggingLevel>>>);
+
+impl rmcp::handler::server::ServerHandler for Upstream {
+    fn get_info(&self) -> mcp::ServerInfo {
+        mcp::ServerInfo {
+            protocol_version: mcp::ProtocolVersion::V_2025_03_26,
+            capabilities: mcp::ServerCapabilities::builder()
+                .enable_logging()
+                .enable_tools()
+                .build(),
+            server_info: mcp::Implementation {
+                name: "chatty".into(),
+                title: None,
+                version: "0.0.1".into(),
+                icons: None,
+                website_url: None,
+            },
+            instructions: None,
+        }
+    }
+
+    async fn set_level(
+        &self,
+        request: mcp::SetLevelRequestParam,
+        _context: rmcp::service::RequestContext<rmcp::RoleServer>,
+    ) -> Result<(), mcp::ErrorData> {
+        *self.0.lock().unwrap() = Some(request.level);
+        Ok(())
+    }
+
+    fn list_tools(
+        &self,
+        _request: Option<mcp::PaginatedRequestParam>,
+        _context: rmcp::service::RequestContext<rmcp::RoleServer>,
+    ) -> impl core::future::Future<Output = Result<mcp::ListToolsResult, mcp::ErrorData>> + Send + '_
+    {
+        let schema: mcp::JsonObject = Default::default();
+        std::future::ready(Ok(mcp::ListToolsResult {
+            tools: vec![mcp::Tool::new("work", "work", schema)],
+            next_cursor: None,
+        }))
+    }
+
+    async fn call_tool(
+        &self,
+        _request: mcp::CallToolRequestParam,
+        context: rmcp::service::RequestContext<rmcp::RoleServer>,
+    ) -> Result<mcp::CallToolResult, mcp::ErrorData> {
+        let _ = context
+            .peer
+            .notify_logging_message(mcp::LoggingMessageNotificationParam {
+                level: mcp::LoggingLevel::Warning,
+                logger: Some("worker".into()),
+                data: serde_json::json!("low disk"),
+            })
+            .await;
+        Ok(mcp::CallToolResult::success(vec![mcp::Content::text(
+            "done",
+        )]))
+    }
+}
+
+// Downstream client that reports log messages on a channel.
+#[derive(Clone)]
+struct RecordingClient(mpsc::UnboundedSender<mcp::LoggingMessageNotificationParam>);
+
+impl rmcp::ClientHandler for RecordingClient {
+    async fn on_logging_message(
+        &self,
+        params: mcp::LoggingMessageNotificationParam,
+        _context: rmcp::service::NotificationContext<rmcp::RoleClient>,
+    ) {
+        let _ = self.0.send(params);
+    }
+}
+
+#[tokio::test]
+async fn set_level_fans_out_and_upstream_logs_reach_downstream() {
+    let requested = Arc::new(Mutex::new(None));
+    let Some(endpoint) = spawn_upstream(
+        "set_level_fans_out_and_upstream_logs_reach_downstream",
+        Upstream(requested.clone()),
+    )
+    .await
+    else {
+        return;
+    };
+
+    let cp = TestProvider::new();
+    let mut s = default_settings();
+    s.mcp_servers.push(upstream_server("chatty", &endpoint));
+    save_settings_with(&cp, &s).expect("save settings");
+
+    let Some((handle, url)) = start_bouncer(
+        "set_level_fans_out_and_upstream_logs_reach_downstream",
+        NoopEmitter,
+        &cp,
+    )
+    .await
+    else {
+        return;
+    };
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let client = RecordingClient(tx)
+        .serve(StreamableHttpClientTransport::from_uri(url.clone()))
+        .await
+        .expect("serve client");
+
+    client
+        .set_level(mcp::SetLevelRequestParam {
+            level: mcp::LoggingLevel::Debug,
+        })
+        .await
+        .expect("set level");
+    assert_eq!(*requested.lock().unwrap(), Some(mcp::LoggingLevel::Debug));
+
+    // A second session asking for errors only must not lower the upstream level
+    // for the first, nor receive its warnings.
+    let (quiet_tx, mut quiet_rx) = mpsc::unbounded_channel();
+    let quiet = RecordingClient(quiet_tx)
+        .serve(StreamableHttpClientTransport::from_uri(url.clone()))
+        .await
+        .expect("serve quiet client");
+    quiet
+        .set_level(mcp::SetLevelRequestParam {
+            level: mcp::LoggingLevel::Error,
+        })
+        .await
+        .expect("set quiet level");
+    assert_eq!(*requested.lock().unwrap(), Some(mcp::LoggingLevel::Debug));
+
+    let tools = client.list_all_tools().await.expect("list tools");
+    assert!(tools.iter().any(|t| t.name == "chatty__work"));
+    client
+        .call_tool(mcp::CallToolRequestParam {
+            name: "chatty__work".into(),
+            arguments: None,
+        })
+        .await
+        .expect("call work");
+    let msg = tokio::time::timeout(Duration::from_secs(10), rx.recv())
+        .await
+        .expect("log message within timeout")
+        .expect("log message");
+    assert_eq!(msg.level, mcp::LoggingLevel::Warning);
+    assert_eq!(msg.logger.as_deref(), Some("chatty/worker"));
+    assert_eq!(msg.data, serde_json::json!("low disk"));
+    assert!(
+        tokio::time::timeout(Duration::from_millis(500), quiet_rx.recv())
+            .await
+            .is_err(),
+        "warning must not reach a session that asked for errors only"
+    );
+
+    stop_server(&handle);
+}
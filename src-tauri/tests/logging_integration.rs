@@ -147,6 +147,8 @@ async fn logging_persists_events_to_sqlite() {
         endpoint: format!("http://{}:{}/mcp", upstream_addr.ip(), upstream_addr.port()),
         headers: Default::default(),
         enabled: true,
+        prefix: None,
+        limits: None,
     });
     save_settings_with(&cp, &s).expect("save settings");
 
@@ -344,6 +346,8 @@ async fn logging_persists_error_and_redacts_sensitive_fields() {
         endpoint: format!("http://{}:{}/mcp", upstream_addr.ip(), upstream_addr.port()),
         headers: Default::default(),
         enabled: true,
+        prefix: None,
+        limits: None,
     });
     save_settings_with(&cp, &s).expect("save settings");
 
@@ -536,6 +540,8 @@ async fn logging_persists_many_calltool_events_in_batches() {
         endpoint: format!("http://{}:{}/mcp", upstream_addr.ip(), upstream_addr.port()),
         headers: Default::default(),
         enabled: true,
+        prefix: None,
+        limits: None,
     });
     save_settings_with(&cp, &s).expect("save settings");
 
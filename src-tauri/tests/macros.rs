@@ -0,0 +1,189 @@
+use mcp_bouncer::config::{
+    ConfigProvider, MacroStep, MacroTool, default_settings, save_settings_with,
+};
+use mcp_bouncer::server::stop_server;
+use rmcp::ServiceExt;
+use rmcp::model as mcp;
+use rmcp::transport::StreamableHttpClientTransport;
+use std::fs;
+
+mod common;
+use common::{NoopEmitter, TestProvider, spawn_upstream, start_bouncer, upstream_server};
+
+// Upstream with an issue tracker and a branch creator.
+#[derive(Clone)]
+struct Upstream;
+
+impl rmcp::handler::server::ServerHandler for Upstream {
+    fn get_info(&self) -> mcp::ServerInfo {
+        mcp::ServerInfo {
+            protocol_version: mcp::ProtocolVersion::V_2025_03_26,
+            capabilities: mcp::ServerCapabilities::builder().enable_tools().build(),
+            server_info: mcp::Implementation {
+                name: "forge".into(),
+                title: None,
+                version: "0.0.1".into(),
+                icons: None,
+                website_url: None,
+            },
+            instructions: None,
+        }
+    }
+
+    fn list_tools(
+        &self,
+        _request: Option<mcp::PaginatedRequestParam>,
+        _context: rmcp::service::RequestContext<rmcp::RoleServer>,
+    ) -> impl core::future::Future<Output = Result<mcp::ListToolsResult, mcp::ErrorData>> + Send + '_
+    {
+        let schema: mcp::JsonObject = Default::default();
+        std::future::ready(Ok(mcp::ListToolsResult {
+            tools: vec![
+                mcp::Tool::new("get_issue", "Fetch an issue", schema.clone()),
+                mcp::Tool::new("create_branch", "Create a branch", schema),
+            ],
+            next_cursor: None,
+        }))
+    }
+
+    async fn call_tool(
+        &self,
+        request: mcp::CallToolRequestParam,
+        _context: rmcp::service::RequestContext<rmcp::RoleServer>,
+    ) -> Result<mcp::CallToolResult, mcp::ErrorData> {
+        let args = request.arguments.unwrap_or_default();
+        let text = match request.name.as_ref() {
+            "get_issue" => {
+                serde_json::json!({ "number": args["id"], "title": "Crash" }).to_string()
+            }
+            "create_branch" => format!(
+                "{}:{}",
+                args["repo"].as_str().unwrap_or_default(),
+                args["name"].as_str().unwrap_or_default()
+            ),
+            other => return Err(mcp::ErrorData::invalid_params(format!("no {other}"), None)),
+        };
+        Ok(mcp::CallToolResult::success(vec![mcp::Content::text(text)]))
+    }
+}
+
+// The issue-to-branch macro against server `server`.
+fn start_issue(server: &str) -> MacroTool {
+    MacroTool {
+        name: "start_issue".into(),
+        description: "Fetch an issue and branch for it".into(),
+        input_schema: None,
+        steps: vec![
+            MacroStep {
+                server: server.into(),
+                tool: "get_issue".into(),
+                arguments: [("id".to_string(), serde_json::json!("{{input.issue}}"))]
+                    .into_iter()
+                    .collect(),
+            },
+            MacroStep {
+                server: server.into(),
+                tool: "create_branch".into(),
+                arguments: [
+                    ("repo".to_string(), serde_json::json!("{{input.repo}}")),
+                    (
+                        "name".to_string(),
+                        serde_json::json!("issue-{{steps.0.json.number}}"),
+                    ),
+                ]
+                .into_iter()
+                .collect(),
+            },
+        ],
+    }
+}
+
+#[tokio::test]
+async fn macro_runs_steps_with_templated_arguments() {
+    let test = "macro_runs_steps_with_templated_arguments";
+    let Some(endpoint) = spawn_upstream(test, Upstream).await else {
+        return;
+    };
+    let cp = TestProvider::new();
+    let mut s = default_settings();
+    s.mcp_servers.push(upstream_server("forge", &endpoint));
+    s.macros.push(start_issue("forge"));
+    save_settings_with(&cp, &s).expect("save settings");
+
+    let Some((handle, url)) = start_bouncer(test, NoopEmitter, &cp).await else {
+        return;
+    };
+    let client =
+        ().serve(StreamableHttpClientTransport::from_uri(url))
+            .await
+            .expect("serve client");
+
+    let tools = client.list_all_tools().await.expect("list tools");
+    let names: Vec<&str> = tools.iter().map(|t| t.name.as_ref()).collect();
+    assert!(names.contains(&"start_issue"));
+    assert!(names.contains(&"forge__get_issue"));
+
+    let res = client
+        .call_tool(mcp::CallToolRequestParam {
+            name: "start_issue".into(),
+            arguments: serde_json::json!({ "issue": 7, "repo": "acme/app" })
+                .as_object()
+                .cloned(),
+        })
+        .await
+        .expect("call macro");
+    assert_ne!(res.is_error, Some(true));
+    assert_eq!(
+        res.content[0].as_text().expect("text").text,
+        "acme/app:issue-7"
+    );
+
+    stop_server(&handle);
+}
+
+#[tokio::test]
+async fn macro_steps_are_checked_against_tool_rules() {
+    let test = "macro_steps_are_checked_against_tool_rules";
+    let Some(endpoint) = spawn_upstream(test, Upstream).await else {
+        return;
+    };
+    // A server name of its own keeps this test's upstream client apart from
+    // the other test's in the shared registry.
+    let cp = TestProvider::new();
+    let mut s = default_settings();
+    s.mcp_servers.push(upstream_server("tracker", &endpoint));
+    s.macros.push(start_issue("tracker"));
+    save_settings_with(&cp, &s).expect("save settings");
+    fs::write(
+        cp.base_dir().join("tool_rules.json"),
+        serde_json::json!({
+            "rules": [{ "name": "no-branches", "server": "tracker", "tool": "create_branch" }]
+        })
+        .to_string(),
+    )
+    .expect("write tool rules");
+
+    let Some((handle, url)) = start_bouncer(test, NoopEmitter, &cp).await else {
+        return;
+    };
+    let client =
+        ().serve(StreamableHttpClientTransport::from_uri(url))
+            .await
+            .expect("serve client");
+
+    let res = client
+        .call_tool(mcp::CallToolRequestParam {
+            name: "start_issue".into(),
+            arguments: serde_json::json!({ "issue": 7, "repo": "acme/app" })
+                .as_object()
+                .cloned(),
+        })
+        .await
+        .expect("call macro");
+    assert_eq!(res.is_error, Some(true));
+    let text = &res.content[0].as_text().expect("text").text;
+    assert!(text.contains("step 2 failed"), "{text}");
+    assert!(text.contains("no-branches"), "{text}");
+
+    stop_server(&handle);
+}
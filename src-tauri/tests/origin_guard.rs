@@ -0,0 +1,66 @@
+use mcp_bouncer::config::{default_settings, save_settings_with};
+use mcp_bouncer::events::{BufferingEventEmitter, EVENT_LOGS_RPC_EVENT};
+use mcp_bouncer::server::stop_server;
+
+mod common;
+use common::{TestProvider, start_bouncer};
+
+const INITIALIZE: &str = r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2025-06-18","capabilities":{},"clientInfo":{"name":"probe","version":"0"}}}"#;
+
+#[tokio::test]
+async fn rejects_foreign_host_and_origin() {
+    let cp = TestProvider::new();
+    let mut s = default_settings();
+    s.allowed_origins.push("https://app.example.com".into());
+    save_settings_with(&cp, &s).expect("save settings");
+
+    let emitter = BufferingEventEmitter::default();
+    let Some((handle, url)) =
+        start_bouncer("rejects_foreign_host_and_origin", emitter.clone(), &cp).await
+    else {
+        return;
+    };
+    let http = reqwest::Client::new();
+    let post = |origin: Option<&str>, host: Option<&str>| {
+        let mut req = http
+            .post(&url)
+            .header("content-type", "application/json")
+            .header("accept", "application/json, text/event-stream")
+            .body(INITIALIZE);
+        if let Some(origin) = origin {
+            req = req.header("origin", origin);
+        }
+        if let Some(host) = host {
+            req = req.header("host", host);
+        }
+        req.send()
+    };
+
+    let rebound = post(None, Some("attacker.example:8091")).await.unwrap();
+    assert_eq!(rebound.status(), reqwest::StatusCode::FORBIDDEN);
+    let cross_site = post(Some("https://evil.example"), None).await.unwrap();
+    assert_eq!(cross_site.status(), reqwest::StatusCode::FORBIDDEN);
+
+    let local_page = post(Some("http://localhost:5173"), None).await.unwrap();
+    assert!(local_page.status().is_success());
+    let listed = post(Some("https://app.example.com"), None).await.unwrap();
+    assert!(listed.status().is_success());
+
+    let rejections: Vec<serde_json::Value> = emitter
+        .0
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|(name, payload)| {
+            name == EVENT_LOGS_RPC_EVENT && payload["method"] == "http/rejected"
+        })
+        .map(|(_, payload)| payload.clone())
+        .collect();
+    assert_eq!(rejections.len(), 2);
+    assert_eq!(
+        rejections[1]["request_json"]["origin"],
+        "https://evil.example"
+    );
+
+    stop_server(&handle);
+}
@@ -0,0 +1,124 @@
+use mcp_bouncer::config::{Profile, default_settings, save_settings_with};
+use mcp_bouncer::server::stop_server;
+use rmcp::ServiceExt;
+use rmcp::model as mcp;
+use rmcp::transport::StreamableHttpClientTransport;
+
+mod common;
+use common::{NoopEmitter, TestProvider, spawn_upstream, start_bouncer, upstream_server};
+
+// Upstream with two tools, of which the profile allows one.
+#[derive(Clone)]
+struct Upstream;
+
+impl rmcp::handler::server::ServerHandler for Upstream {
+    fn get_info(&self) -> mcp::ServerInfo {
+        mcp::ServerInfo {
+            protocol_version: mcp::ProtocolVersion::V_2025_03_26,
+            capabilities: mcp::ServerCapabilities::builder().enable_tools().build(),
+            server_info: mcp::Implementation {
+                name: "forge".into(),
+                title: None,
+                version: "0.0.1".into(),
+                icons: None,
+                website_url: None,
+            },
+            instructions: None,
+        }
+    }
+
+    fn list_tools(
+        &self,
+        _request: Option<mcp::PaginatedRequestParam>,
+        _context: rmcp::service::RequestContext<rmcp::RoleServer>,
+    ) -> impl core::future::Future<Output = Result<mcp::ListToolsResult, mcp::ErrorData>> + Send + '_
+    {
+        let schema: mcp::JsonObject = Default::default();
+        std::future::ready(Ok(mcp::ListToolsResult {
+            tools: vec![
+                mcp::Tool::new("get_issue", "Fetch an issue", schema.clone()),
+                mcp::Tool::new("create_branch", "Create a branch", schema),
+            ],
+            next_cursor: None,
+        }))
+    }
+
+    async fn call_tool(
+        &self,
+        request: mcp::CallToolRequestParam,
+        _context: rmcp::service::RequestContext<rmcp::RoleServer>,
+    ) -> Result<mcp::CallToolResult, mcp::ErrorData> {
+        let args = request.arguments.unwrap_or_default();
+        let text = match request.name.as_ref() {
+            "get_issue" => {
+                serde_json::json!({ "number": args["id"], "title": "Crash" }).to_string()
+            }
+            "create_branch" => format!(
+                "{}:{}",
+                args["repo"].as_str().unwrap_or_default(),
+                args["name"].as_str().unwrap_or_default()
+            ),
+            other => return Err(mcp::ErrorData::invalid_params(format!("no {other}"), None)),
+        };
+        Ok(mcp::CallToolResult::success(vec![mcp::Content::text(text)]))
+    }
+}
+
+#[tokio::test]
+async fn profile_endpoint_exposes_only_its_tools() {
+    let Some(endpoint) = spawn_upstream("profile_endpoint_exposes_only_its_tools", Upstream).await
+    else {
+        return;
+    };
+
+    let cp = TestProvider::new();
+    let mut s = default_settings();
+    s.mcp_servers.push(upstream_server("forge", &endpoint));
+    s.profiles.push(Profile {
+        name: "readonly".into(),
+        servers: vec![],
+        tools: vec!["forge::get_issue".into()],
+    });
+    save_settings_with(&cp, &s).expect("save settings");
+
+    let Some((handle, base)) =
+        start_bouncer("profile_endpoint_exposes_only_its_tools", NoopEmitter, &cp).await
+    else {
+        return;
+    };
+
+    let client = ()
+        .serve(StreamableHttpClientTransport::from_uri(format!(
+            "{base}/readonly"
+        )))
+        .await
+        .expect("serve profile client");
+    let tools = client.list_all_tools().await.expect("list tools");
+    let names: Vec<&str> = tools.iter().map(|t| t.name.as_ref()).collect();
+    assert_eq!(names, vec!["forge__get_issue"]);
+
+    let res = client
+        .call_tool(mcp::CallToolRequestParam {
+            name: "forge__create_branch".into(),
+            arguments: None,
+        })
+        .await
+        .expect("call tool");
+    assert_eq!(res.is_error, Some(true));
+
+    let full =
+        ().serve(StreamableHttpClientTransport::from_uri(base.clone()))
+            .await
+            .expect("serve client");
+    let tools = full.list_all_tools().await.expect("list tools");
+    assert_eq!(tools.len(), 2);
+
+    let missing = reqwest::Client::new()
+        .post(format!("{base}/nope"))
+        .send()
+        .await
+        .expect("request unknown profile");
+    assert_eq!(missing.status(), reqwest::StatusCode::NOT_FOUND);
+
+    stop_server(&handle);
+}
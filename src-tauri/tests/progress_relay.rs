@@ -0,0 +1,163 @@
+use mcp_bouncer::config::{default_settings, save_settings_with};
+use mcp_bouncer::server::stop_server;
+use rmcp::ServiceExt;
+use rmcp::model as mcp;
+use rmcp::transport::StreamableHttpClientTransport;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Notify, mpsc};
+
+mod common;
+use common::{NoopEmitter, TestProvider, spawn_upstream, start_bouncer, upstream_server};
+
+// Upstream `build` tool: reports progress twice, then waits until cancelled.
+#[derive(Clone)]
+struct Upstream {
+    cancelled: Arc<Notify>,
+}
+
+impl rmcp::handler::server::ServerHandler for Upstream {
+    fn get_info(&self) -> mcp::ServerInfo {
+        mcp::ServerInfo {
+            protocol_version: mcp::ProtocolVersion::V_2025_03_26,
+            capabilities: mcp::ServerCapabilities::builder().enable_tools().build(),
+            server_info: mcp::Implementation {
+                name: "builder".into(),
+                title: None,
+                version: "0.0.1".into(),
+                icons: None,
+                website_url: None,
+            },
+            instructions: None,
+        }
+    }
+
+    fn list_tools(
+        &self,
+        _request: Option<mcp::PaginatedRequestParam>,
+        _context: rmcp::service::RequestContext<rmcp::RoleServer>,
+    ) -> impl core::future::Future<Output = Result<mcp::ListToolsResult, mcp::ErrorData>> + Send + '_
+    {
+        let schema: mcp::JsonObject = Default::default();
+        std::future::ready(Ok(mcp::ListToolsResult {
+            tools: vec![mcp::Tool::new("build", "build", schema)],
+            next_cursor: None,
+        }))
+    }
+
+    async fn call_tool(
+        &self,
+        _request: mcp::CallToolRequestParam,
+        context: rmcp::service::RequestContext<rmcp::RoleServer>,
+    ) -> Result<mcp::CallToolResult, mcp::ErrorData> {
+        if let Some(token) = context.meta.get_progress_token() {
+            for step in 1..=2 {
+                let _ = context
+                    .peer
+                    .notify_progress(mcp::ProgressNotificationParam {
+                        progress_token: token.clone(),
+                        progress: step as f64,
+                        total: Some(2.0),
+                        message: Some(format!("step {step}")),
+                    })
+                    .await;
+            }
+        }
+        context.ct.cancelled().await;
+        self.cancelled.notify_one();
+        Ok(mcp::CallToolResult::success(vec![]))
+    }
+}
+
+#[derive(Clone)]
+struct ProgressClient(mpsc::UnboundedSender<mcp::ProgressNotificationParam>);
+
+impl rmcp::ClientHandler for ProgressClient {
+    async fn on_progress(
+        &self,
+        params: mcp::ProgressNotificationParam,
+        _context: rmcp::service::NotificationContext<rmcp::RoleClient>,
+    ) {
+        let _ = self.0.send(params);
+    }
+}
+
+#[tokio::test]
+async fn progress_is_relayed_and_cancel_reaches_upstream() {
+    let cancelled = Arc::new(Notify::new());
+    let upstream = Upstream {
+        cancelled: cancelled.clone(),
+    };
+    let Some(endpoint) =
+        spawn_upstream("progress_is_relayed_and_cancel_reaches_upstream", upstream).await
+    else {
+        return;
+    };
+
+    let cp = TestProvider::new();
+    let mut s = default_settings();
+    s.mcp_servers.push(upstream_server("builder", &endpoint));
+    save_settings_with(&cp, &s).expect("save settings");
+
+    let Some((handle, url)) = start_bouncer(
+        "progress_is_relayed_and_cancel_reaches_upstream",
+        NoopEmitter,
+        &cp,
+    )
+    .await
+    else {
+        return;
+    };
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let client = ProgressClient(tx)
+        .serve(StreamableHttpClientTransport::from_uri(url))
+        .await
+        .expect("serve client");
+
+    let start = std::time::Instant::now();
+    loop {
+        let tools = client.list_all_tools().await.expect("list tools");
+        if tools.iter().any(|t| t.name == "builder__build") {
+            break;
+        }
+        if start.elapsed() > Duration::from_secs(45) {
+            panic!("tools did not include builder__build within timeout");
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+
+    let request = client
+        .send_cancellable_request(
+            mcp::ClientRequest::CallToolRequest(mcp::Request::new(mcp::CallToolRequestParam {
+                name: "builder__build".into(),
+                arguments: None,
+            })),
+            rmcp::service::PeerRequestOptions::no_options(),
+        )
+        .await
+        .expect("send call");
+    let downstream_token = request.progress_token.clone();
+
+    for step in 1..=2 {
+        let progress = tokio::time::timeout(Duration::from_secs(10), rx.recv())
+            .await
+            .expect("progress within timeout")
+            .expect("progress channel open");
+        assert_eq!(progress.progress_token, downstream_token);
+        assert_eq!(progress.progress, step as f64);
+        assert_eq!(
+            progress.message.as_deref(),
+            Some(format!("step {step}").as_str())
+        );
+    }
+
+    request
+        .cancel(Some("user aborted".into()))
+        .await
+        .expect("send cancel");
+    tokio::time::timeout(Duration::from_secs(10), cancelled.notified())
+        .await
+        .expect("upstream request should observe cancellation");
+
+    stop_server(&handle);
+}
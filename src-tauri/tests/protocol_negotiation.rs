@@ -0,0 +1,149 @@
+use mcp_bouncer::config::{default_settings, save_settings_with};
+use mcp_bouncer::server::stop_server;
+use rmcp::ServiceExt;
+use rmcp::model as mcp;
+use rmcp::transport::StreamableHttpClientTransport;
+use std::sync::Arc;
+
+mod common;
+use common::{NoopEmitter, TestProvider, spawn_upstream, start_bouncer, upstream_server};
+
+// Upstream on the newest protocol with a tool that only returns structured output.
+#[derive(Clone)]
+struct Upstream;
+
+impl rmcp::handler::server::ServerHandler for Upstream {
+    fn get_info(&self) -> mcp::ServerInfo {
+        mcp::ServerInfo {
+            protocol_version: mcp::ProtocolVersion::V_2025_06_18,
+            capabilities: mcp::ServerCapabilities::builder().enable_tools().build(),
+            server_info: mcp::Implementation {
+                name: "modern".into(),
+                title: None,
+                version: "0.0.1".into(),
+                icons: None,
+                website_url: None,
+            },
+            instructions: None,
+        }
+    }
+
+    fn list_tools(
+        &self,
+        _request: Option<mcp::PaginatedRequestParam>,
+        _context: rmcp::service::RequestContext<rmcp::RoleServer>,
+    ) -> impl core::future::Future<Output = Result<mcp::ListToolsResult, mcp::ErrorData>> + Send + '_
+    {
+        let schema: mcp::JsonObject = Default::default();
+        let output: mcp::JsonObject = serde_json::from_value(serde_json::json!({
+            "type": "object",
+            "properties": { "total": { "type": "number" } }
+        }))
+        .unwrap();
+        let mut tool = mcp::Tool::new("sum", "sum", schema);
+        tool.output_schema = Some(Arc::new(output));
+        std::future::ready(Ok(mcp::ListToolsResult {
+            tools: vec![tool],
+            next_cursor: None,
+        }))
+    }
+
+    async fn call_tool(
+        &self,
+        _request: mcp::CallToolRequestParam,
+        _context: rmcp::service::RequestContext<rmcp::RoleServer>,
+    ) -> Result<mcp::CallToolResult, mcp::ErrorData> {
+        Ok(mcp::CallToolResult {
+            content: vec![],
+            structured_content: Some(serde_json::json!({ "total": 3 })),
+            is_error: Some(false),
+            meta: None,
+        })
+    }
+}
+
+fn client_info(version: mcp::ProtocolVersion) -> mcp::ClientInfo {
+    mcp::ClientInfo {
+        protocol_version: version,
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+async fn sessions_negotiate_version_and_adapt_structured_content() {
+    let Some(endpoint) = spawn_upstream(
+        "sessions_negotiate_version_and_adapt_structured_content",
+        Upstream,
+    )
+    .await
+    else {
+        return;
+    };
+
+    let cp = TestProvider::new();
+    let mut s = default_settings();
+    s.mcp_servers.push(upstream_server("modern", &endpoint));
+    save_settings_with(&cp, &s).expect("save settings");
+
+    let Some((handle, url)) = start_bouncer(
+        "sessions_negotiate_version_and_adapt_structured_content",
+        NoopEmitter,
+        &cp,
+    )
+    .await
+    else {
+        return;
+    };
+
+    // An older session gets its own version back and plain-text results.
+    let old = client_info(mcp::ProtocolVersion::V_2025_03_26)
+        .serve(StreamableHttpClientTransport::from_uri(url.clone()))
+        .await
+        .expect("serve old client");
+    assert_eq!(
+        old.peer_info().expect("server info").protocol_version,
+        mcp::ProtocolVersion::V_2025_03_26
+    );
+    let tools = old.list_all_tools().await.expect("list tools");
+    let sum = tools.iter().find(|t| t.name == "modern__sum").expect("sum");
+    assert!(sum.output_schema.is_none());
+    let res = old
+        .call_tool(mcp::CallToolRequestParam {
+            name: "modern__sum".into(),
+            arguments: None,
+        })
+        .await
+        .expect("call sum");
+    assert!(res.structured_content.is_none());
+    let text = res.content[0].as_text().expect("text content");
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&text.text).unwrap(),
+        serde_json::json!({ "total": 3 })
+    );
+
+    // A current session keeps structured output untouched.
+    let new = client_info(mcp::ProtocolVersion::V_2025_06_18)
+        .serve(StreamableHttpClientTransport::from_uri(url))
+        .await
+        .expect("serve new client");
+    assert_eq!(
+        new.peer_info().expect("server info").protocol_version,
+        mcp::ProtocolVersion::V_2025_06_18
+    );
+    let tools = new.list_all_tools().await.expect("list tools");
+    let sum = tools.iter().find(|t| t.name == "modern__sum").expect("sum");
+    assert!(sum.output_schema.is_some());
+    let res = new
+        .call_tool(mcp::CallToolRequestParam {
+            name: "modern__sum".into(),
+            arguments: None,
+        })
+        .await
+        .expect("call sum");
+    assert_eq!(
+        res.structured_content,
+        Some(serde_json::json!({ "total": 3 }))
+    );
+
+    stop_server(&handle);
+}
@@ -0,0 +1,213 @@
+use mcp_bouncer::config::{default_settings, save_settings_with};
+use mcp_bouncer::server::stop_server;
+use rmcp::ServiceExt;
+use rmcp::model as mcp;
+use rmcp::transport::StreamableHttpClientTransport;
+use std::time::Duration;
+
+mod common;
+use common::{NoopEmitter, TestProvider, spawn_upstream, start_bouncer, upstream_server};
+
+// Upstream `ask` tool: asks the host for roots and an LLM completion mid-call.
+#[derive(Clone)]
+struct Upstream;
+
+impl rmcp::handler::server::ServerHandler for Upstream {
+    fn get_info(&self) -> mcp::ServerInfo {
+        mcp::ServerInfo {
+            protocol_version: mcp::ProtocolVersion::V_2025_03_26,
+            capabilities: mcp::ServerCapabilities::builder().enable_tools().build(),
+            server_info: mcp::Implementation {
+                name: "agentic".into(),
+                title: None,
+                version: "0.0.1".into(),
+                icons: None,
+                website_url: None,
+            },
+            instructions: None,
+        }
+    }
+
+    fn list_tools(
+        &self,
+        _request: Option<mcp::PaginatedRequestParam>,
+        _context: rmcp::service::RequestContext<rmcp::RoleServer>,
+    ) -> impl core::future::Future<Output = Result<mcp::ListToolsResult, mcp::ErrorData>> + Send + '_
+    {
+        let schema: mcp::JsonObject = Default::default();
+        std::future::ready(Ok(mcp::ListToolsResult {
+            tools: vec![mcp::Tool::new("ask", "ask", schema)],
+            next_cursor: None,
+        }))
+    }
+
+    async fn call_tool(
+        &self,
+        _request: mcp::CallToolRequestParam,
+        context: rmcp::service::RequestContext<rmcp::RoleServer>,
+    ) -> Result<mcp::CallToolResult, mcp::ErrorData> {
+        let roots = match context.peer.list_roots().await {
+            Ok(res) => res.roots,
+            Err(e) => {
+                return Ok(mcp::CallToolResult::error(vec![mcp::Content::text(
+                    e.to_string(),
+                )]));
+            }
+        };
+        let sampled = context
+            .peer
+            .create_message(mcp::CreateMessageRequestParam {
+                messages: vec![mcp::SamplingMessage {
+                    role: mcp::Role::User,
+                    content: mcp::Content::text("hi"),
+                }],
+                model_preferences: None,
+                system_prompt: None,
+                include_context: None,
+                temperature: None,
+                max_tokens: 16,
+                stop_sequences: None,
+                metadata: None,
+            })
+            .await;
+        match sampled {
+            Ok(res) => {
+                let text = res
+                    .message
+                    .content
+                    .as_text()
+                    .map(|t| t.text.clone())
+                    .unwrap_or_default();
+                let root = roots.first().map(|r| r.uri.clone()).unwrap_or_default();
+                Ok(mcp::CallToolResult::success(vec![mcp::Content::text(
+                    format!("{root} {text}"),
+                )]))
+            }
+            Err(e) => Ok(mcp::CallToolResult::error(vec![mcp::Content::text(
+                e.to_string(),
+            )])),
+        }
+    }
+}
+
+// Host client that supports roots and sampling.
+#[derive(Clone)]
+struct HostClient;
+
+impl rmcp::ClientHandler for HostClient {
+    async fn create_message(
+        &self,
+        _params: mcp::CreateMessageRequestParam,
+        _context: rmcp::service::RequestContext<rmcp::RoleClient>,
+    ) -> Result<mcp::CreateMessageResult, mcp::ErrorData> {
+        Ok(mcp::CreateMessageResult {
+            model: "host-model".into(),
+            stop_reason: Some(mcp::CreateMessageResult::STOP_REASON_END_TURN.into()),
+            message: mcp::SamplingMessage {
+                role: mcp::Role::Assistant,
+                content: mcp::Content::text("sampled"),
+            },
+        })
+    }
+
+    async fn list_roots(
+        &self,
+        _context: rmcp::service::RequestContext<rmcp::RoleClient>,
+    ) -> Result<mcp::ListRootsResult, mcp::ErrorData> {
+        Ok(mcp::ListRootsResult {
+            roots: vec![mcp::Root {
+                uri: "file:///workspace".into(),
+                name: None,
+            }],
+        })
+    }
+
+    fn get_info(&self) -> mcp::ClientInfo {
+        mcp::ClientInfo {
+            capabilities: mcp::ClientCapabilities::builder()
+                .enable_roots()
+                .enable_sampling()
+                .build(),
+            ..Default::default()
+        }
+    }
+}
+
+fn tool_text(res: &mcp::CallToolResult) -> String {
+    res.content
+        .iter()
+        .filter_map(|c| c.as_text().map(|t| t.text.clone()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[tokio::test]
+async fn upstream_requests_reach_the_calling_downstream_session() {
+    let Some(endpoint) = spawn_upstream(
+        "upstream_requests_reach_the_calling_downstream_session",
+        Upstream,
+    )
+    .await
+    else {
+        return;
+    };
+
+    let cp = TestProvider::new();
+    let mut s = default_settings();
+    s.mcp_servers.push(upstream_server("agentic", &endpoint));
+    save_settings_with(&cp, &s).expect("save settings");
+
+    let Some((handle, url)) = start_bouncer(
+        "upstream_requests_reach_the_calling_downstream_session",
+        NoopEmitter,
+        &cp,
+    )
+    .await
+    else {
+        return;
+    };
+
+    let host = HostClient
+        .serve(StreamableHttpClientTransport::from_uri(url.clone()))
+        .await
+        .expect("serve host client");
+    let start = std::time::Instant::now();
+    loop {
+        let tools = host.list_all_tools().await.expect("list tools");
+        if tools.iter().any(|t| t.name == "agentic__ask") {
+            break;
+        }
+        if start.elapsed() > Duration::from_secs(45) {
+            panic!("tools did not include agentic__ask within timeout");
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+
+    let ask = mcp::CallToolRequestParam {
+        name: "agentic__ask".into(),
+        arguments: None,
+    };
+    let res = host.call_tool(ask.clone()).await.expect("call ask");
+    assert_ne!(
+        res.is_error,
+        Some(true),
+        "unexpected error: {}",
+        tool_text(&res)
+    );
+    assert_eq!(tool_text(&res), "file:///workspace sampled");
+
+    // A client without roots/sampling support gets a clear error instead of a hang.
+    let plain =
+        ().serve(StreamableHttpClientTransport::from_uri(url))
+            .await
+            .expect("serve plain client");
+    let res = plain.call_tool(ask).await.expect("call ask");
+    assert_eq!(res.is_error, Some(true));
+    let text = tool_text(&res);
+    assert!(
+        text.contains("does not support"),
+        "expected capability error, got: {text}"
+    );
+
+    stop_server(&handle);
+}
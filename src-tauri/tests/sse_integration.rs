@@ -128,6 +128,8 @@ async fn sse_client_can_connect_list_tools_and_send_headers() {
         endpoint: format!("http://{}:{}/sse", addr.ip(), addr.port()),
         headers,
         enabled: true,
+        prefix: None,
+        limits: None,
     };
 
     let emitter = BufferingEventEmitter::default();
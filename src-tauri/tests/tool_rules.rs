@@ -0,0 +1,147 @@
+use mcp_bouncer::config::{ConfigProvider, default_settings, save_settings_with};
+use mcp_bouncer::events::{BufferingEventEmitter, EVENT_LOGS_RPC_EVENT};
+use mcp_bouncer::server::stop_server;
+use rmcp::ServiceExt;
+use rmcp::model as mcp;
+use rmcp::transport::StreamableHttpClientTransport;
+
+mod common;
+use common::{TestProvider, spawn_upstream, start_bouncer, upstream_server};
+
+// Upstream with a file writer that echoes the path it was given.
+#[derive(Clone)]
+struct Upstream;
+
+impl rmcp::handler::server::ServerHandler for Upstream {
+    fn get_info(&self) -> mcp::ServerInfo {
+        mcp::ServerInfo {
+            protocol_version: mcp::ProtocolVersion::V_2025_03_26,
+            capabilities: mcp::ServerCapabilities::builder().enable_tools().build(),
+            server_info: mcp::Implementation {
+                name: "files".into(),
+                title: None,
+                version: "0.0.1".into(),
+                icons: None,
+                website_url: None,
+            },
+            instructions: None,
+        }
+    }
+
+    fn list_tools(
+        &self,
+        _request: Option<mcp::PaginatedRequestParam>,
+        _context: rmcp::service::RequestContext<rmcp::RoleServer>,
+    ) -> impl core::future::Future<Output = Result<mcp::ListToolsResult, mcp::ErrorData>> + Send + '_
+    {
+        let schema: mcp::JsonObject = Default::default();
+        std::future::ready(Ok(mcp::ListToolsResult {
+            tools: vec![mcp::Tool::new("write_file", "Write a file", schema)],
+            next_cursor: None,
+        }))
+    }
+
+    async fn call_tool(
+        &self,
+        request: mcp::CallToolRequestParam,
+        _context: rmcp::service::RequestContext<rmcp::RoleServer>,
+    ) -> Result<mcp::CallToolResult, mcp::ErrorData> {
+        let path = request
+            .arguments
+            .as_ref()
+            .and_then(|args| args.get("path"))
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string();
+        Ok(mcp::CallToolResult::success(vec![mcp::Content::text(path)]))
+    }
+}
+
+fn write_rules(cp: &TestProvider, dry_run: bool) {
+    let rules = serde_json::json!({
+        "dry_run": dry_run,
+        "rules": [{
+            "name": "stay-in-work",
+            "server": "files",
+            "tool": "write_file",
+            "when": [{ "arg": "path", "not_under": "/srv/work" }]
+        }]
+    });
+    std::fs::write(cp.base_dir().join("tool_rules.json"), rules.to_string())
+        .expect("write tool rules");
+}
+
+#[tokio::test]
+async fn rules_block_calls_and_dry_run_only_logs() {
+    let Some(endpoint) = spawn_upstream("rules_block_calls_and_dry_run_only_logs", Upstream).await
+    else {
+        return;
+    };
+
+    let cp = TestProvider::new();
+    let mut s = default_settings();
+    s.mcp_servers.push(upstream_server("files", &endpoint));
+    save_settings_with(&cp, &s).expect("save settings");
+    write_rules(&cp, false);
+
+    let emitter = BufferingEventEmitter::default();
+    let Some((handle, url)) = start_bouncer(
+        "rules_block_calls_and_dry_run_only_logs",
+        emitter.clone(),
+        &cp,
+    )
+    .await
+    else {
+        return;
+    };
+    let client =
+        ().serve(StreamableHttpClientTransport::from_uri(url))
+            .await
+            .expect("serve client");
+    client.list_all_tools().await.expect("list tools");
+    let call = |path: &str| {
+        client.call_tool(mcp::CallToolRequestParam {
+            name: "files__write_file".into(),
+            arguments: serde_json::json!({ "path": path }).as_object().cloned(),
+        })
+    };
+
+    let res = call("/srv/work/notes.txt").await.expect("call tool");
+    assert_ne!(res.is_error, Some(true));
+
+    let res = call("/etc/passwd").await.expect("call tool");
+    assert_eq!(res.is_error, Some(true));
+    assert!(
+        res.content[0]
+            .as_text()
+            .unwrap()
+            .text
+            .contains("stay-in-work")
+    );
+    assert_eq!(
+        res.structured_content.as_ref().unwrap()["blocked_by"],
+        "stay-in-work"
+    );
+
+    write_rules(&cp, true);
+    let res = call("/etc/passwd").await.expect("call tool");
+    assert_ne!(res.is_error, Some(true), "dry run forwards the call");
+    assert_eq!(res.content[0].as_text().unwrap().text, "/etc/passwd");
+
+    let would_deny: Vec<serde_json::Value> = emitter
+        .0
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|(name, payload)| {
+            name == EVENT_LOGS_RPC_EVENT && payload["method"] == "rules/would_deny"
+        })
+        .map(|(_, payload)| payload.clone())
+        .collect();
+    assert_eq!(would_deny.len(), 1);
+    assert_eq!(would_deny[0]["request_json"]["rule"], "stay-in-work");
+    assert_eq!(would_deny[0]["server_name"], "files");
+    assert!(would_deny[0]["parent_id"].is_string());
+
+    stop_server(&handle);
+}
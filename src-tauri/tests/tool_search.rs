@@ -0,0 +1,126 @@
+use mcp_bouncer::config::{MCPServerConfig, default_settings, save_settings_with};
+use mcp_bouncer::server::stop_server;
+use rmcp::ServiceExt;
+use rmcp::model as mcp;
+use rmcp::transport::StreamableHttpClientTransport;
+
+mod common;
+use common::{NoopEmitter, TestProvider, spawn_upstream, start_bouncer, upstream_server};
+
+// Upstream with a handful of tools; `echo` returns its `text` argument.
+#[derive(Clone)]
+struct Upstream;
+
+impl rmcp::handler::server::ServerHandler for Upstream {
+    fn get_info(&self) -> mcp::ServerInfo {
+        mcp::ServerInfo {
+            protocol_version: mcp::ProtocolVersion::V_2025_03_26,
+            capabilities: mcp::ServerCapabilities::builder().enable_tools().build(),
+            server_info: mcp::Implementation {
+                name: "kit".into(),
+                title: None,
+                version: "0.0.1".into(),
+                icons: None,
+                website_url: None,
+            },
+            instructions: None,
+        }
+    }
+
+    fn list_tools(
+        &self,
+        _request: Option<mcp::PaginatedRequestParam>,
+        _context: rmcp::service::RequestContext<rmcp::RoleServer>,
+    ) -> impl core::future::Future<Output = Result<mcp::ListToolsResult, mcp::ErrorData>> + Send + '_
+    {
+        let schema: mcp::JsonObject = Default::default();
+        std::future::ready(Ok(mcp::ListToolsResult {
+            tools: vec![
+                mcp::Tool::new("echo", "Echo text back", schema.clone()),
+                mcp::Tool::new("weather", "Current weather for a city", schema.clone()),
+                mcp::Tool::new("translate", "Translate text", schema),
+            ],
+            next_cursor: None,
+        }))
+    }
+
+    async fn call_tool(
+        &self,
+        request: mcp::CallToolRequestParam,
+        _context: rmcp::service::RequestContext<rmcp::RoleServer>,
+    ) -> Result<mcp::CallToolResult, mcp::ErrorData> {
+        let text = request
+            .arguments
+            .and_then(|a| a.get("text").and_then(|t| t.as_str()).map(str::to_string))
+            .unwrap_or_default();
+        Ok(mcp::CallToolResult::success(vec![mcp::Content::text(
+            format!("{}:{text}", request.name),
+        )]))
+    }
+}
+
+fn args(v: serde_json::Value) -> Option<mcp::JsonObject> {
+    v.as_object().cloned()
+}
+
+#[tokio::test]
+async fn search_mode_exposes_meta_tools_and_proxies_calls() {
+    let Some(endpoint) =
+        spawn_upstream("search_mode_exposes_meta_tools_and_proxies_calls", Upstream).await
+    else {
+        return;
+    };
+
+    let cp = TestProvider::new();
+    let mut s = default_settings();
+    s.tool_search = true;
+    s.mcp_servers.push(MCPServerConfig {
+        prefix: Some("kit".into()),
+        ..upstream_server("kit-search", &endpoint)
+    });
+    save_settings_with(&cp, &s).expect("save settings");
+
+    let Some((handle, url)) = start_bouncer(
+        "search_mode_exposes_meta_tools_and_proxies_calls",
+        NoopEmitter,
+        &cp,
+    )
+    .await
+    else {
+        return;
+    };
+    let client =
+        ().serve(StreamableHttpClientTransport::from_uri(url))
+            .await
+            .expect("serve client");
+
+    let tools = client.list_all_tools().await.expect("list tools");
+    let names: Vec<&str> = tools.iter().map(|t| t.name.as_ref()).collect();
+    assert_eq!(names, vec!["search_tools", "call_tool"]);
+
+    let res = client
+        .call_tool(mcp::CallToolRequestParam {
+            name: "search_tools".into(),
+            arguments: args(serde_json::json!({ "query": "weather city" })),
+        })
+        .await
+        .expect("search");
+    let text = &res.content[0].as_text().expect("text").text;
+    let hits: serde_json::Value = serde_json::from_str(text).unwrap();
+    assert_eq!(hits.as_array().unwrap().len(), 1);
+    assert_eq!(hits[0]["name"], "kit__weather");
+
+    let res = client
+        .call_tool(mcp::CallToolRequestParam {
+            name: "call_tool".into(),
+            arguments: args(serde_json::json!({
+                "name": "kit__echo",
+                "arguments": { "text": "hi" }
+            })),
+        })
+        .await
+        .expect("call through meta tool");
+    assert_eq!(res.content[0].as_text().expect("text").text, "echo:hi");
+
+    stop_server(&handle);
+}
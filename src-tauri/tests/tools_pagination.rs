@@ -0,0 +1,149 @@
+use mcp_bouncer::config::{ConfigProvider, default_settings, save_settings_with};
+use mcp_bouncer::server::stop_server;
+use rmcp::ServiceExt;
+use rmcp::model as mcp;
+use rmcp::transport::StreamableHttpClientTransport;
+use std::fs;
+
+mod common;
+use common::{NoopEmitter, TestProvider, spawn_upstream, start_bouncer, upstream_server};
+
+// Upstream that serves its three tools across two pages.
+#[derive(Clone)]
+struct Upstream;
+
+impl rmcp::handler::server::ServerHandler for Upstream {
+    fn get_info(&self) -> mcp::ServerInfo {
+        mcp::ServerInfo {
+            protocol_version: mcp::ProtocolVersion::V_2025_03_26,
+            capabilities: mcp::ServerCapabilities::builder().enable_tools().build(),
+            server_info: mcp::Implementation {
+                name: "paged".into(),
+                title: None,
+                version: "0.0.1".into(),
+                icons: None,
+                website_url: None,
+            },
+            instructions: None,
+        }
+    }
+
+    async fn list_tools(
+        &self,
+        request: Option<mcp::PaginatedRequestParam>,
+        _context: rmcp::service::RequestContext<rmcp::RoleServer>,
+    ) -> Result<mcp::ListToolsResult, mcp::ErrorData> {
+        let tool = |name: &'static str| {
+            let schema: mcp::JsonObject = Default::default();
+            mcp::Tool::new(name, name, schema)
+        };
+        match request.and_then(|r| r.cursor).as_deref() {
+            None => Ok(mcp::ListToolsResult {
+                tools: vec![tool("a"), tool("b")],
+                next_cursor: Some("page-2".into()),
+            }),
+            Some("page-2") => Ok(mcp::ListToolsResult {
+                tools: vec![tool("c")],
+                next_cursor: None,
+            }),
+            Some(other) => Err(mcp::ErrorData::invalid_params(
+                format!("bad cursor {other}"),
+                None,
+            )),
+        }
+    }
+
+    async fn call_tool(
+        &self,
+        request: mcp::CallToolRequestParam,
+        _context: rmcp::service::RequestContext<rmcp::RoleServer>,
+    ) -> Result<mcp::CallToolResult, mcp::ErrorData> {
+        Ok(mcp::CallToolResult::success(vec![mcp::Content::text(
+            request.name.to_string(),
+        )]))
+    }
+}
+
+#[tokio::test]
+async fn tools_list_pages_through_a_stable_snapshot() {
+    let Some(endpoint) =
+        spawn_upstream("tools_list_pages_through_a_stable_snapshot", Upstream).await
+    else {
+        return;
+    };
+
+    let cp = TestProvider::new();
+    let mut s = default_settings();
+    s.tools_page_size = Some(2);
+    s.mcp_servers.push(upstream_server("paged", &endpoint));
+    save_settings_with(&cp, &s).expect("save settings");
+
+    let Some((handle, url)) = start_bouncer(
+        "tools_list_pages_through_a_stable_snapshot",
+        NoopEmitter,
+        &cp,
+    )
+    .await
+    else {
+        return;
+    };
+    let client =
+        ().serve(StreamableHttpClientTransport::from_uri(url.clone()))
+            .await
+            .expect("serve client");
+
+    let first = client.list_tools(None).await.expect("first page");
+    let names: Vec<_> = first.tools.iter().map(|t| t.name.to_string()).collect();
+    assert_eq!(names, vec!["paged__a", "paged__b"]);
+    let cursor = first.next_cursor.expect("cursor for second page");
+
+    let second = client
+        .list_tools(Some(mcp::PaginatedRequestParam {
+            cursor: Some(cursor),
+        }))
+        .await
+        .expect("second page");
+    let names: Vec<_> = second.tools.iter().map(|t| t.name.to_string()).collect();
+    assert_eq!(names, vec!["paged__c"]);
+    assert!(second.next_cursor.is_none());
+
+    let res = client
+        .call_tool(mcp::CallToolRequestParam {
+            name: "paged__c".into(),
+            arguments: None,
+        })
+        .await
+        .expect("call tool from second page");
+    assert_ne!(res.is_error, Some(true));
+
+    // Another session lists after `c` was renamed; this session's names still
+    // resolve through the listing it was served.
+    fs::write(
+        cp.base_dir().join("tool_overrides.json"),
+        serde_json::json!({ "paged": { "c": { "name": "see" } } }).to_string(),
+    )
+    .expect("write tool overrides");
+    let other =
+        ().serve(StreamableHttpClientTransport::from_uri(url.clone()))
+            .await
+            .expect("serve second client");
+    let renamed = other.list_all_tools().await.expect("list renamed tools");
+    assert!(renamed.iter().any(|t| t.name == "see"));
+    let res = client
+        .call_tool(mcp::CallToolRequestParam {
+            name: "paged__c".into(),
+            arguments: None,
+        })
+        .await
+        .expect("call tool by its listed name");
+    assert_eq!(res.content[0].as_text().expect("text").text, "c");
+
+    let bad = client
+        .list_tools(Some(mcp::PaginatedRequestParam {
+            cursor: Some("nope".into()),
+        }))
+        .await;
+    assert!(bad.is_err(), "unknown cursor should be rejected");
+
+    stop_server(&handle);
+}
@@ -12,6 +12,8 @@ import { ToastContainer } from './components/Toast';
 import { useToast } from './contexts/ToastContext';
 import { useState, useEffect, useMemo, useCallback } from 'react';
 import { useIncomingClients } from './hooks/useIncomingClients';
+import { useApprovals } from './hooks/useApprovals';
+import { ApprovalPrompt } from './components/ApprovalPrompt';
 import { on, safeUnlisten, EVENT_LOGS_RPC_EVENT } from './tauri/events';
 import LogsPage from './pages/LogsPage';
 import { sqlLoggingService } from './lib/sqlLogging';
@@ -36,6 +38,7 @@ function AppContent() {
     loadSettings,
     openConfigDirectory,
     settingsPath,
+    settingsIssues,
     updateSettings,
   } = useSettingsState();
   const { clientStatus, loadClientStatus, loaded: statusLoaded } = useClientStatusState();
@@ -67,6 +70,7 @@ function AppContent() {
   }, [loadServers, loadClientStatus]);
 
   const { clients } = useIncomingClients();
+  const { pending: pendingApprovals, resolve: resolveApproval } = useApprovals();
 
   const { theme, toggleTheme } = useTheme();
   const { toasts, removeToast } = useToast();
@@ -157,11 +161,19 @@ function AppContent() {
         onClose={handleCloseSettings}
         settings={settings}
         settingsPath={settingsPath}
+        settingsIssues={settingsIssues}
         socketBridgePath={socketBridgePath}
         onSave={handleSaveSettings}
         onOpenDirectory={openConfigDirectory}
       />
       <ToastContainer toasts={toasts} onClose={removeToast} />
+      {pendingApprovals[0] && (
+        <ApprovalPrompt
+          request={pendingApprovals[0]}
+          queued={pendingApprovals.length - 1}
+          onResolve={resolveApproval}
+        />
+      )}
       <main className="mx-auto max-w-5xl px-6 pb-10 pt-16">
         <TabSwitcher
           value={tab}
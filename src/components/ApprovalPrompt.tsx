@@ -0,0 +1,63 @@
+import { ShieldExclamationIcon } from '@heroicons/react/24/outline';
+import type { ApprovalDecision } from '../tauri/bridge';
+import type { ApprovalRequestedPayload } from '../types/events';
+
+interface ApprovalPromptProps {
+  request: ApprovalRequestedPayload;
+  queued: number;
+  onResolve: (id: string, decision: ApprovalDecision) => void;
+}
+
+export function ApprovalPrompt({ request, queued, onResolve }: ApprovalPromptProps) {
+  const args = JSON.stringify(request.arguments ?? {}, null, 2);
+  return (
+    <div className="fixed inset-0 z-50 flex items-center justify-center bg-black/40 p-4">
+      <div
+        role="alertdialog"
+        aria-labelledby="approval-title"
+        className="w-full max-w-lg rounded-xl border border-surface-200 bg-white p-5 shadow-xl dark:border-surface-700 dark:bg-surface-900"
+      >
+        <div className="flex items-start gap-3">
+          <ShieldExclamationIcon className="h-6 w-6 flex-shrink-0 text-yellow-500" />
+          <div className="min-w-0 flex-1">
+            <h2 id="approval-title" className="text-base font-semibold text-surface-900 dark:text-white">
+              Allow {request.tool_name} on {request.server_name}?
+            </h2>
+            <p className="mt-1 text-sm text-surface-600 dark:text-surface-400">
+              Requested by {request.client_name || 'an unknown client'}. The call is denied
+              automatically after {Math.round(request.timeout_ms / 1000)}s.
+              {queued > 0 ? ` ${queued} more waiting.` : ''}
+            </p>
+          </div>
+        </div>
+        <pre className="mt-4 max-h-64 overflow-auto rounded-lg bg-surface-100 p-3 text-xs text-surface-800 dark:bg-surface-800 dark:text-surface-200">
+          {args}
+        </pre>
+        <div className="mt-4 flex flex-wrap justify-end gap-2">
+          <button
+            type="button"
+            onClick={() => onResolve(request.id, 'deny')}
+            className="rounded-lg border border-surface-300 px-3 py-1.5 text-sm font-medium text-surface-700 hover:bg-surface-100 dark:border-surface-600 dark:text-surface-200 dark:hover:bg-surface-800"
+          >
+            Deny
+          </button>
+          <button
+            type="button"
+            onClick={() => onResolve(request.id, 'allow_for_session')}
+            className="rounded-lg border border-brand-300 px-3 py-1.5 text-sm font-medium text-brand-700 hover:bg-brand-50 dark:border-brand-700 dark:text-brand-200 dark:hover:bg-brand-900/40"
+          >
+            Always allow this session
+          </button>
+          <button
+            type="button"
+            data-initial-focus
+            onClick={() => onResolve(request.id, 'allow')}
+            className="rounded-lg bg-brand-600 px-3 py-1.5 text-sm font-medium text-white hover:bg-brand-700"
+          >
+            Allow
+          </button>
+        </div>
+      </div>
+    </div>
+  );
+}
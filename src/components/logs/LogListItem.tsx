@@ -71,6 +71,12 @@ export function LogListItem({ item }: { item: RpcLog }) {
             {item.server_name ? (
               <span className="ml-1 text-xs font-normal text-surface-500 dark:text-surface-400">· {item.server_name}</span>
             ) : null}
+            {item.policy ? (
+              <span className="ml-1 text-xs font-normal text-surface-500 dark:text-surface-400">· policy {item.policy}</span>
+            ) : null}
+            {item.identity ? (
+              <span className="ml-1 text-xs font-normal text-surface-500 dark:text-surface-400">· token {item.identity}</span>
+            ) : null}
             {originBadge(item.origin)}
           </div>
         </div>
@@ -395,6 +395,29 @@ export function ServerCard({
           </div>
         )}
 
+      {/* Exposed names shared with other servers */}
+      {server.enabled && clientStatus?.name_conflicts && clientStatus.name_conflicts.length > 0 && (
+        <div className="mb-2 p-2 bg-amber-50 dark:bg-amber-900/20 border border-amber-200 dark:border-amber-800 rounded-md animate-fadeIn">
+          {clientStatus.name_conflicts.map(conflict => (
+            <div
+              key={conflict}
+              className="flex items-center gap-2 text-xs text-amber-700 dark:text-amber-400"
+            >
+              <NoSymbolIcon className="w-3 h-3 flex-shrink-0" />
+              <span>Name conflict: {conflict}</span>
+            </div>
+          ))}
+        </div>
+      )}
+
+      {/* Call limits: live load and throttled calls */}
+      {server.enabled && clientStatus?.usage && (clientStatus.usage.in_flight > 0 || clientStatus.usage.throttled > 0) && (
+        <div className="mb-2 text-xs text-surface-600 dark:text-surface-400">
+          {clientStatus.usage.in_flight} in flight · {clientStatus.usage.throttled} throttled
+          {clientStatus.usage.tokens_available != null && ` · ${clientStatus.usage.tokens_available} calls left in bucket`}
+        </div>
+      )}
+
       <div className={`space-y-1.5 transition-all duration-200 ${toggleLoading ? 'opacity-75' : ''}`}>
         {/* stdio transport fields */}
         {server.transport === 'stdio' && (
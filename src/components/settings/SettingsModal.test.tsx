@@ -116,4 +116,26 @@ describe('SettingsModal', () => {
     expect(await screen.findByText(/nope/i)).toBeInTheDocument();
     expect(addToastMock).not.toHaveBeenCalled();
   });
+
+  it('lists problems found in the settings file', () => {
+    render(
+      <SettingsModal
+        isOpen
+        settings={baseSettings}
+        settingsPath="/tmp/settings.json"
+        settingsIssues={[
+          { message: 'trailing comma', line: 4, column: 2 },
+          { field: 'mcp_servers[1].name', message: "duplicate server name 'gh'" },
+        ]}
+        socketBridgePath={null}
+        onSave={vi.fn()}
+        onClose={() => {}}
+        onOpenDirectory={vi.fn()}
+      />,
+    );
+
+    const alert = screen.getByRole('alert');
+    expect(alert).toHaveTextContent('Line 4, column 2: trailing comma');
+    expect(alert).toHaveTextContent("mcp_servers[1].name: duplicate server name 'gh'");
+  });
 });
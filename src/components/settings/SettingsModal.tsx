@@ -1,6 +1,6 @@
 import { useEffect, useRef, useState, type FormEvent, type RefObject } from 'react';
 import { Cog6ToothIcon, FolderOpenIcon, XMarkIcon } from '@heroicons/react/24/outline';
-import type { ServerTransport, Settings, SocketBridgeInfo } from '../../tauri/bridge';
+import type { ServerTransport, Settings, SettingsIssue, SocketBridgeInfo } from '../../tauri/bridge';
 import { FormInput } from '../FormInput';
 import { LoadingButton } from '../LoadingButton';
 import { useFocusTrap } from '../../hooks/useFocusTrap';
@@ -11,6 +11,7 @@ interface SettingsModalProps {
   onClose: () => void;
   settings: Settings | null;
   settingsPath?: string;
+  settingsIssues?: SettingsIssue[];
   socketBridgePath: SocketBridgeInfo | null;
   onSave: (next: Settings) => Promise<void>;
   onOpenDirectory: () => Promise<void>;
@@ -43,6 +44,7 @@ export function SettingsModal({
   onClose,
   settings,
   settingsPath,
+  settingsIssues = [],
   socketBridgePath,
   onSave,
   onOpenDirectory,
@@ -304,6 +306,26 @@ export function SettingsModal({
                     ? settingsPath
                     : 'File will be created after you save settings.'}
                 </div>
+                {settingsIssues.length > 0 && (
+                  <div
+                    role="alert"
+                    className="rounded-md border border-amber-300 bg-amber-50 px-3 py-2 text-xs text-amber-800 dark:border-amber-700 dark:bg-amber-900/30 dark:text-amber-200"
+                  >
+                    <p className="font-semibold">Problems in the settings file</p>
+                    <ul className="mt-1 list-disc pl-5">
+                      {settingsIssues.map((issue, i) => (
+                        <li key={i}>
+                          {issue.line != null
+                            ? `Line ${issue.line}, column ${issue.column ?? 0}: `
+                            : issue.field
+                              ? `${issue.field}: `
+                              : ''}
+                          {issue.message}
+                        </li>
+                      ))}
+                    </ul>
+                  </div>
+                )}
               </section>
 
               {submitError && (
@@ -13,6 +13,9 @@ vi.mock('../../tauri/bridge', async () => {
         path: '/tmp/settings.json',
       })),
       OpenConfigDirectory: vi.fn(async () => {}),
+      ValidateSettings: vi.fn(async () => [
+        { field: 'mcp_servers[0].command', message: 'stdio server needs a command' },
+      ]),
       UpdateSettings: vi.fn(async () => {}),
     },
   };
@@ -35,6 +38,7 @@ describe('useSettingsState', () => {
       await st.loadSettings();
       expect(st.settings?.listen_addr).toBe('http://x');
       expect(st.settingsPath).toBe('/tmp/settings.json');
+      expect(st.settingsIssues).toHaveLength(1);
     });
 
     await st.openConfigDirectory();
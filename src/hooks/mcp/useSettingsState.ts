@@ -1,20 +1,22 @@
 import { useCallback, useState } from 'react';
-import type { Settings } from '../../tauri/bridge';
+import type { Settings, SettingsIssue } from '../../tauri/bridge';
 import { SettingsService } from '../../tauri/bridge';
 
 export function useSettingsState() {
   const [settings, setSettings] = useState<Settings | null>(null);
   const [settingsPath, setSettingsPath] = useState<string>('');
+  const [settingsIssues, setSettingsIssues] = useState<SettingsIssue[]>([]);
 
   const loadSettings = useCallback(async () => {
     try {
       const detail = await SettingsService.GetSettings();
       setSettings(detail.settings);
       setSettingsPath(detail.path);
+      setSettingsIssues(await SettingsService.ValidateSettings());
     } catch (error) {
       console.error('Failed to load settings:', error);
     }
-  }, [setSettings, setSettingsPath]);
+  }, [setSettings, setSettingsPath, setSettingsIssues]);
 
   const openConfigDirectory = useCallback(async () => {
     try {
@@ -40,6 +42,7 @@ export function useSettingsState() {
     loadSettings,
     openConfigDirectory,
     settingsPath,
+    settingsIssues,
     updateSettings,
   } as const;
 }
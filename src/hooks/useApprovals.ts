@@ -0,0 +1,46 @@
+import { useCallback, useEffect, useState } from 'react';
+import { MCPService, type ApprovalDecision } from '../tauri/bridge';
+import { on, safeUnlisten, EVENT_APPROVAL_REQUESTED, EVENT_APPROVAL_RESOLVED } from '../tauri/events';
+import type { ApprovalRequestedPayload, ApprovalResolvedPayload } from '../types/events';
+
+// Tool calls paused until the user allows or denies them, oldest first.
+export function useApprovals() {
+  const [pending, setPending] = useState<ApprovalRequestedPayload[]>([]);
+
+  useEffect(() => {
+    let cancelled = false;
+    const unsubs: Array<() => void> = [];
+
+    const unsub1Promise = on<ApprovalRequestedPayload>(EVENT_APPROVAL_REQUESTED, async (e) => {
+      const data = e.payload;
+      setPending(prev => [...prev.filter(p => p.id !== data.id), data]);
+    });
+
+    const unsub2Promise = on<ApprovalResolvedPayload>(EVENT_APPROVAL_RESOLVED, async (e) => {
+      const data = e.payload;
+      setPending(prev => prev.filter(p => p.id !== data.id));
+    });
+
+    unsub1Promise.then(u => (cancelled ? (void safeUnlisten(u)) : unsubs.push(u))).catch(() => {});
+    unsub2Promise.then(u => (cancelled ? (void safeUnlisten(u)) : unsubs.push(u))).catch(() => {});
+
+    return () => {
+      cancelled = true;
+      while (unsubs.length) {
+        const u = unsubs.pop();
+        if (u) safeUnlisten(u);
+      }
+    };
+  }, []);
+
+  const resolve = useCallback(async (id: string, decision: ApprovalDecision) => {
+    setPending(prev => prev.filter(p => p.id !== id));
+    try {
+      await MCPService.ResolveApproval(id, decision);
+    } catch (e) {
+      console.error('Failed to resolve approval', e);
+    }
+  }, []);
+
+  return { pending, resolve };
+}
@@ -41,6 +41,10 @@ const SCHEMA_QUERIES = [
   )`,
   `CREATE INDEX IF NOT EXISTS idx_events_ts ON rpc_events(ts_ms)`,
   `CREATE INDEX IF NOT EXISTS idx_events_session ON rpc_events(session_id)`,
+  `ALTER TABLE rpc_events ADD COLUMN parent_id TEXT`,
+  `ALTER TABLE rpc_events ADD COLUMN policy TEXT`,
+  `ALTER TABLE sessions ADD COLUMN identity TEXT`,
+  `ALTER TABLE rpc_events ADD COLUMN identity TEXT`,
 ] as const;
 
 // Bucket width candidates for histogram calculations
@@ -82,8 +86,8 @@ class SQLLoggingService {
         try {
           await db.execute(query);
         } catch (error) {
-          const alterOrigin = query.startsWith('ALTER TABLE rpc_events ADD COLUMN origin');
-          if (!alterOrigin || !isDuplicateColumnError(error)) {
+          const alterColumn = /^ALTER TABLE \w+ ADD COLUMN/.test(query);
+          if (!alterColumn || !isDuplicateColumnError(error)) {
             throw error;
           }
         }
@@ -107,7 +111,7 @@ class SQLLoggingService {
   private buildEventsQuery(params: QueryParams): { sql: string; values: any[] } {
     let sql = `
       SELECT id, ts_ms, session_id, method, server_name, server_version, 
-             server_protocol, duration_ms, ok, error, origin, request_json, response_json 
+             server_protocol, duration_ms, ok, error, origin, parent_id, policy, identity, request_json, response_json 
       FROM rpc_events
     `;
     
@@ -175,6 +179,9 @@ class SQLLoggingService {
       request_json: parseField(row.request_json),
       response_json: parseField(row.response_json),
       origin: row.origin ?? null,
+      parent_id: row.parent_id ?? null,
+      policy: row.policy ?? null,
+      identity: row.identity ?? null,
     };
   };
 
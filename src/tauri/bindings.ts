@@ -133,6 +133,78 @@ async mcpToggleTool(clientName: string, toolName: string, enabled: boolean) : Pr
     else return { status: "error", error: e  as any };
 }
 },
+async mcpSetToolOverride(clientName: string, toolName: string, toolOverride: ToolOverride | null) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("mcp_set_tool_override", { clientName, toolName, toolOverride }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async mcpTogglePrompt(clientName: string, promptName: string, enabled: boolean) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("mcp_toggle_prompt", { clientName, promptName, enabled }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async mcpListApiTokens() : Promise<Result<ApiTokenInfo[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("mcp_list_api_tokens") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async mcpCreateApiToken(label: string) : Promise<Result<CreatedApiToken, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("mcp_create_api_token", { label }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async mcpRevokeApiToken(id: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("mcp_revoke_api_token", { id }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async mcpListSecrets() : Promise<Result<NamedSecretInfo[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("mcp_list_secrets") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async mcpSetSecret(name: string, value: string) : Promise<Result<NamedSecretInfo, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("mcp_set_secret", { name, value }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async mcpDeleteSecret(name: string) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("mcp_delete_secret", { name }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
+async mcpResolveApproval(id: string, decision: ApprovalDecision) : Promise<Result<null, string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("mcp_resolve_approval", { id, decision }) };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
+},
 async settingsGetSettings() : Promise<Result<SettingsDetail, string>> {
     try {
     return { status: "ok", data: await TAURI_INVOKE("settings_get_settings") };
@@ -156,6 +228,14 @@ async settingsUpdateSettings(settings: Settings | null) : Promise<Result<null, s
     if(e instanceof Error) throw e;
     else return { status: "error", error: e  as any };
 }
+},
+async settingsValidateSettings() : Promise<Result<SettingsIssue[], string>> {
+    try {
+    return { status: "ok", data: await TAURI_INVOKE("settings_validate_settings") };
+} catch (e) {
+    if(e instanceof Error) throw e;
+    else return { status: "error", error: e  as any };
+}
 }
 }
 
@@ -169,17 +249,30 @@ async settingsUpdateSettings(settings: Settings | null) : Promise<Result<null, s
 
 /** user-defined types **/
 
+export type ApiTokenInfo = { id: string; label: string; created_at: string }
+export type ApprovalDecision = "allow" | "allow_for_session" | "deny"
+export type CallLimits = { per_minute?: number | null; burst?: number | null; max_in_flight?: number | null; queue_timeout_ms?: number | null }
+export type CallUsage = { in_flight: number; throttled: number; tokens_available?: number | null }
 export type ClientConnectionState = "disconnected" | "connecting" | "errored" | "connected" | "requires_authorization" | "authorizing"
-export type ClientStatus = { name: string; state: ClientConnectionState; tools: number; last_error?: string | null; authorization_required: boolean; oauth_authenticated: boolean }
+export type ClientPolicy = { name: string; client: string; allow_servers?: string[]; deny_servers?: string[]; allow_tools?: string[]; deny_tools?: string[]; read_only?: boolean }
+export type ClientStatus = { name: string; state: ClientConnectionState; tools: number; last_error?: string | null; authorization_required: boolean; oauth_authenticated: boolean; name_conflicts?: string[]; usage?: CallUsage }
+export type CreatedApiToken = { info: ApiTokenInfo; token: string }
 export type DebugCallToolResponse = { duration_ms: number; ok: boolean; result: JsonValue; request_arguments?: JsonValue | null }
 export type IncomingClient = { id: string; name: string; version: string; title?: string | null; connected_at?: string | null }
 export type JsonValue = null | boolean | number | string | JsonValue[] | Partial<{ [key in string]: JsonValue }>
-export type MCPServerConfig = { name: string; description: string; transport?: TransportType; command: string; args?: string[]; env?: Partial<{ [key in string]: string }>; endpoint?: string; headers?: Partial<{ [key in string]: string }>; enabled: boolean }
+export type MacroStep = { server: string; tool: string; arguments?: Partial<{ [key in string]: JsonValue }> }
+export type MacroTool = { name: string; description?: string; input_schema?: JsonValue | null; steps: MacroStep[] }
+export type MCPServerConfig = { name: string; description: string; transport?: TransportType; command: string; args?: string[]; env?: Partial<{ [key in string]: string }>; endpoint?: string; headers?: Partial<{ [key in string]: string }>; enabled: boolean; prefix?: string | null; limits?: CallLimits | null }
+export type NamedSecretInfo = { name: string; updated_at: string }
+export type Profile = { name: string; servers?: string[]; tools?: string[] }
 export type ServerTransport = "streamable_http" | "unix"
-export type Settings = { mcp_servers: MCPServerConfig[]; listen_addr: string; transport: ServerTransport }
+export type Settings = { mcp_servers: MCPServerConfig[]; listen_addr: string; transport: ServerTransport; tools_page_size?: number | null; tool_search?: boolean; macros?: MacroTool[]; profiles?: Profile[]; require_api_token?: boolean; allowed_hosts?: string[]; allowed_origins?: string[]; client_policies?: ClientPolicy[]; approve_destructive_tools?: boolean; approval_timeout_secs?: number | null }
 export type SettingsDetail = { settings: Settings; path: string }
+export type SettingsIssue = { field?: string | null; message: string; line?: number | null; column?: number | null }
 export type SocketBridgeInfo = { path: string; exists: boolean }
-export type ToolInfo = { name: string; description?: string | null; input_schema?: JsonValue | null }
+export type ToolApproval = "ask" | "auto"
+export type ToolOverride = { name?: string | null; description?: string | null; hidden?: string[]; inject?: Partial<{ [key in string]: JsonValue }>; approval?: ToolApproval | null; limits?: CallLimits | null }
+export type ToolInfo = { name: string; title?: string | null; description?: string | null; input_schema?: JsonValue | null; output_schema?: JsonValue | null; annotations?: JsonValue | null; icons?: JsonValue | null; meta?: JsonValue | null }
 export type TransportType = "stdio" | "sse" | "streamable_http"
 
 /** tauri-specta globals **/
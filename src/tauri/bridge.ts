@@ -16,9 +16,12 @@ export type MCPServerConfig = {
 export type ServerTransport = 'streamable_http' | 'unix';
 export type Settings = { mcp_servers: MCPServerConfig[]; listen_addr: string; transport: ServerTransport };
 export type SettingsDetail = { settings: Settings; path: string };
+export type SettingsIssue = { field?: string | null; message: string; line?: number | null; column?: number | null };
 export type SocketBridgeInfo = { path: string; exists: boolean };
 export type ClientConnectionState = 'disconnected' | 'connecting' | 'errored' | 'connected' | 'requires_authorization' | 'authorizing';
-export type ClientStatus = { name: string; state: ClientConnectionState; tools: number; last_error?: string | null; authorization_required: boolean; oauth_authenticated: boolean };
+export type ClientStatus = { name: string; state: ClientConnectionState; tools: number; last_error?: string | null; authorization_required: boolean; oauth_authenticated: boolean; name_conflicts?: string[]; usage?: CallUsage };
+export type CallUsage = { in_flight: number; throttled: number; tokens_available?: number | null };
+export type ApprovalDecision = 'allow' | 'allow_for_session' | 'deny';
 export type IncomingClient = { id: string; name: string; version: string; title?: string | null; connected_at?: string | null };
 export type Tool = { name: string; description?: string | null; input_schema?: unknown | null };
 export type DebugCallToolResponse = {
@@ -89,6 +92,9 @@ export const MCPService = {
       args: args ?? null,
     });
   },
+  async ResolveApproval(id: string, decision: ApprovalDecision): Promise<void> {
+    await invoke('mcp_resolve_approval', { id, decision });
+  },
 };
 
 export const SettingsService = {
@@ -101,6 +107,9 @@ export const SettingsService = {
   async UpdateSettings(settings: Settings): Promise<void> {
     await invoke('settings_update_settings', { settings });
   },
+  async ValidateSettings(): Promise<SettingsIssue[]> {
+    return await invoke('settings_validate_settings');
+  },
 };
 
 export const MiscService = {
@@ -28,4 +28,6 @@ export {
   EVENT_INCOMING_CLIENT_DISCONNECTED,
   EVENT_INCOMING_CLIENTS_UPDATED,
   EVENT_LOGS_RPC_EVENT,
+  EVENT_APPROVAL_REQUESTED,
+  EVENT_APPROVAL_RESOLVED,
 } from '../types/events';
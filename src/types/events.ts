@@ -7,6 +7,8 @@ export const EVENT_INCOMING_CLIENT_CONNECTED = 'mcp:incoming_client_connected' a
 export const EVENT_INCOMING_CLIENT_DISCONNECTED = 'mcp:incoming_client_disconnected' as const;
 export const EVENT_INCOMING_CLIENTS_UPDATED = 'mcp:incoming_clients_updated' as const;
 export const EVENT_LOGS_RPC_EVENT = 'logs:rpc_event' as const;
+export const EVENT_APPROVAL_REQUESTED = 'approval:requested' as const;
+export const EVENT_APPROVAL_RESOLVED = 'approval:resolved' as const;
 
 export type EventName =
   | typeof EVENT_SERVERS_UPDATED
@@ -16,7 +18,9 @@ export type EventName =
   | typeof EVENT_INCOMING_CLIENT_CONNECTED
   | typeof EVENT_INCOMING_CLIENT_DISCONNECTED
   | typeof EVENT_INCOMING_CLIENTS_UPDATED
-  | typeof EVENT_LOGS_RPC_EVENT;
+  | typeof EVENT_LOGS_RPC_EVENT
+  | typeof EVENT_APPROVAL_REQUESTED
+  | typeof EVENT_APPROVAL_RESOLVED;
 
 export interface ClientErrorPayload {
   server_name: string;
@@ -35,3 +39,18 @@ export interface IncomingClientConnectedPayload {
 export interface IncomingClientDisconnectedPayload {
   id: string;
 }
+
+export interface ApprovalRequestedPayload {
+  id: string;
+  session_id: string;
+  client_name?: string | null;
+  server_name: string;
+  tool_name: string;
+  arguments?: Record<string, unknown> | null;
+  timeout_ms: number;
+}
+
+export interface ApprovalResolvedPayload {
+  id: string;
+  outcome: 'allowed' | 'allowed_for_session' | 'denied' | 'timed_out';
+}
@@ -12,6 +12,9 @@ export interface RpcLog {
   request_json?: unknown | null;
   response_json?: unknown | null;
   origin?: string | null;
+  parent_id?: string | null;
+  policy?: string | null;
+  identity?: string | null;
 }
 
 export interface LogsQueryParams {